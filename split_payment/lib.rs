@@ -1,10 +1,89 @@
 #![cfg_attr(not(feature = "std"), no_std, no_main)]
 
+pub use self::split_payment::{Beneficiary, Error, SplitPayment, SplitPaymentRef};
+
+/// Messages other contracts can call on a splitter, e.g. through
+/// `ink::contract_ref!(SplitPaymentInterface)`
+///
+/// Build against this crate with the `ink-as-dependency` feature.
+#[ink::trait_definition]
+pub trait SplitPaymentInterface {
+    /// Pay in; the transferred value is split like a direct `receive_payment`
+    #[ink(message, payable)]
+    fn receive_payment(&mut self) -> Result<(), Error>;
+
+    /// Get a beneficiary by account
+    #[ink(message)]
+    fn get_beneficiary(&self, account: ink::H160) -> Option<Beneficiary>;
+
+    /// Get total shares allocated, in basis points
+    #[ink(message)]
+    fn get_total_shares(&self) -> u16;
+
+    /// Get the pending balance of an account
+    #[ink(message)]
+    fn get_pending_balance(&self, account: ink::H160) -> ink::U256;
+
+    /// Check if deposits or withdrawals are paused
+    #[ink(message)]
+    fn is_paused(&self) -> bool;
+}
+
 #[ink::contract]
-mod split_payment {
-    use ink::storage::Mapping;
+pub mod split_payment {
+    use ink::env::call::{build_call, build_create, ExecutionInput, Selector};
+    use ink::prelude::{string::String, vec::Vec};
+    use ink::storage::{Lazy, Mapping};
+
+    use ink::{ToAddr, H160, H256, U256};
+
+    /// Total number of basis points that can be allocated (100%)
+    pub const MAX_SHARE_BPS: u16 = 10_000;
+
+    /// Maximum length of a payment memo, in bytes
+    pub const MAX_MEMO_LEN: usize = 64;
+
+    /// Maximum length of a beneficiary label, in bytes
+    pub const MAX_LABEL_LEN: usize = 32;
+
+    /// Maximum length of the contract name, in bytes
+    pub const MAX_NAME_LEN: usize = 64;
+
+    /// Maximum length of the contract description, in bytes
+    pub const MAX_DESCRIPTION_LEN: usize = 256;
+
+    /// Maximum number of entries returned by a paginated query
+    pub const MAX_PAGE_SIZE: u32 = 50;
+
+    /// Largest keeper fee the owner can configure (1%)
+    pub const MAX_KEEPER_FEE_BPS: u16 = 100;
+
+    /// Largest protocol fee the owner can take from incoming payments (10%)
+    pub const MAX_FEE_BPS: u16 = 1_000;
+
+    /// Compute budget for an `on_split_payout` callback
+    pub const NOTIFY_REF_TIME_LIMIT: u64 = 5_000_000_000;
 
-    use ink::{storage::StorageVec, H160, U256};
+    /// Version of the contract logic, bumped with every release
+    pub const CODE_VERSION: u32 = 1;
+
+    /// Beneficiary limit used when none is given at construction
+    pub const DEFAULT_MAX_BENEFICIARIES: u32 = 100;
+
+    /// Most excess payment records a new payment deletes when trimming to `max_history`
+    pub const MAX_PRUNE_PER_PAYMENT: u64 = 2;
+
+    /// Most payment records a single `prune_payments` call deletes
+    pub const MAX_PRUNE_BATCH: u64 = 100;
+
+    /// Number of entries the admin log keeps before overwriting the oldest
+    pub const ADMIN_LOG_CAPACITY: u32 = 128;
+
+    /// Domain tag hashed into `approve_with_signature` payloads
+    pub const APPROVAL_TAG: &[u8] = b"split_payment:approve";
+
+    /// Domain tag hashed into `withdraw_with_signature` payloads
+    pub const WITHDRAWAL_TAG: &[u8] = b"split_payment:withdraw";
 
     /// Errors that can occur in the contract
     #[derive(Debug, PartialEq, Eq)]
@@ -12,14 +91,15 @@ mod split_payment {
     pub enum Error {
         /// Caller is not authorized to perform this action
         Unauthorized,
-        /// Insufficient U256 for the operation
-        InsufficientU256,
-        /// Insufficient allowance for the operation
-        InsufficientAllowance,
-        /// Invalid beneficiary (zero address or already exists)
-        InvalidBeneficiary,
-        /// Invalid share percentage (must be > 0 and total <= 100)
-        InvalidShare,
+        /// Pending balance is lower than the requested amount
+        InsufficientBalance { requested: U256, available: U256 },
+        /// Allowance is missing, expired or lower than the requested amount
+        InsufficientAllowance { requested: U256, available: U256 },
+        /// Zero address given where a real account is required
+        ZeroAddress,
+        /// Invalid share (must be > 0 and total <= 10_000 basis points);
+        /// `available` is the number of basis points still unallocated
+        InvalidShare { requested: u16, available: u16 },
         /// No funds available to withdraw
         NoFundsAvailable,
         /// Transfer failed
@@ -28,19 +108,317 @@ mod split_payment {
         BeneficiaryNotFound,
         /// Contract is paused
         ContractPaused,
+        /// Account is not a manager
+        ManagerNotFound,
+        /// Amount must be greater than zero
+        ZeroAmount,
+        /// Payment is below the configured minimum
+        PaymentTooSmall,
+        /// Payment memo exceeds `MAX_MEMO_LEN` bytes
+        MemoTooLong,
+        /// Beneficiary limit reached, or new limit is below the current count
+        TooManyBeneficiaries,
+        /// Account is already a beneficiary
+        BeneficiaryAlreadyExists,
+        /// Current allowance differs from the one the caller expected
+        AllowanceMismatch { expected: U256, current: U256 },
+        /// Approval exists but its `expires_at` has passed
+        ApprovalExpired,
+        /// Beneficiary has been frozen by the owner
+        BeneficiaryFrozen,
+        /// Keeper fee above `MAX_KEEPER_FEE_BPS`, or protocol fee above `MAX_FEE_BPS`
+        FeeTooHigh,
+        /// Withdrawal would exceed the per-window limit; `retry_after` is when the window resets
+        WithdrawalLimitExceeded { retry_after: u64 },
+        /// Last withdrawal was too recent; `remaining_ms` until the cooldown ends
+        CooldownActive { remaining_ms: u64 },
+        /// Funds are locked until the given timestamp
+        FundsLocked(u64),
+        /// Beneficiary has no pending removal
+        RemovalNotRequested,
+        /// Beneficiary already has a pending removal
+        RemovalAlreadyRequested,
+        /// Pending removal cannot be finalized before `effective_at`
+        RemovalNotDue { effective_at: u64 },
+        /// Strict allocation requires `total_shares == MAX_SHARE_BPS`
+        SharesNotFullyAllocated,
+        /// Beneficiary configuration was finalized and can no longer change
+        SharesLocked,
+        /// Message does not apply to the contract's share model
+        WrongShareModel,
+        /// Weight must be greater than zero
+        InvalidWeight,
+        /// At least one beneficiary must stay uncapped to absorb the remainder
+        NoUncappedBeneficiary,
+        /// No held payment with the given id
+        HeldPaymentNotFound,
+        /// Campaign deadline has passed, or the campaign failed
+        CampaignEnded,
+        /// Campaign cannot be finalized before its deadline
+        CampaignNotEnded,
+        /// No campaign configured, or it was already finalized
+        CampaignNotActive,
+        /// Refunds are only open after a campaign misses its goal
+        RefundsNotOpen,
+        /// Payments are only accepted inside the configured acceptance window
+        OutsideAcceptanceWindow,
+        /// Acceptance window would end before it starts
+        InvalidAcceptanceWindow,
+        /// PSP22 token is not on the accepted list
+        TokenNotAccepted,
+        /// Deposits must be paused first
+        ContractNotPaused,
+        /// Replacing the contract code failed
+        UpgradeFailed,
+        /// No pending admin action with the given id
+        ActionNotFound,
+        /// Admin action cannot be executed before `execute_after`
+        ActionNotReady { execute_after: u64 },
+        /// Admin delay can only be increased
+        AdminDelayTooShort,
+        /// Action must go through `propose_action` while an admin delay is set
+        ActionTimelocked,
+        /// Caller is not on the admin council
+        NotCouncilMember,
+        /// Caller already confirmed this admin transaction
+        AlreadyConfirmed,
+        /// Caller has not confirmed this admin transaction
+        NotConfirmed,
+        /// Threshold must be between 1 and the number of council members,
+        /// and members must be distinct non-zero accounts
+        InvalidThreshold,
+        /// Action must go through `submit_admin_tx` while a council is configured
+        CouncilApprovalRequired,
+        /// Parked, held, pledged, pooled or PSP22 funds must be settled before terminating
+        FundsOutstanding,
+        /// Final settlement could not pay these accounts; nothing was changed
+        SettlementFailed { failed: Vec<H160> },
+        /// Contract has been retired and no longer accepts funds
+        ContractRetired,
+        /// A transfer-bearing message was entered again before it returned
+        ReentrantCall,
+        /// No pending invitation for this account
+        InvitationNotFound,
+        /// Account already has a pending invitation
+        AlreadyInvited,
+        /// Beneficiaries must be invited and accept; they cannot be added directly
+        AcceptanceRequired,
+        /// Source and destination are the same account
+        SameAccount,
+        /// Signature does not recover to the expected signer
+        InvalidSignature,
+        /// Signed nonce is not the signer's next nonce
+        InvalidNonce { expected: u64 },
+        /// Signed payload's deadline has passed
+        SignatureExpired,
+        /// A payment with this idempotency key was already processed
+        DuplicatePayment,
+        /// A balance or running total would exceed `U256::MAX`
+        ArithmeticOverflow,
+        /// Beneficiary label exceeds `MAX_LABEL_LEN` bytes
+        LabelTooLong,
+        /// Beneficiary label is not valid UTF-8
+        InvalidLabel,
+        /// Name or description exceeds `MAX_NAME_LEN` / `MAX_DESCRIPTION_LEN` bytes
+        MetadataTooLong,
+        /// Instantiating a child splitter failed
+        InstantiationFailed,
+        /// Account is not an operator
+        OperatorNotFound,
+        /// Account is blocked by the owner
+        Blacklisted,
+        /// Pending balances exceed the contract's balance; the contract is paused
+        Insolvent,
+        /// A chunked distribution is under way; see `continue_distribution`
+        DistributionInProgress,
+        /// Flat-amount or capped beneficiaries can only be paid by `distribute`
+        ChunkingUnsupported,
+        /// No ownership proposal is pending
+        NoPendingOwner,
+    }
+
+    impl Error {
+        /// Stable numeric code for the variant, ignoring any attached data
+        pub fn error_code(&self) -> u8 {
+            match self {
+                Error::Unauthorized => 0,
+                Error::InsufficientBalance { .. } => 1,
+                Error::InsufficientAllowance { .. } => 2,
+                Error::ZeroAddress => 3,
+                Error::InvalidShare { .. } => 4,
+                Error::NoFundsAvailable => 5,
+                Error::TransferFailed => 6,
+                Error::BeneficiaryNotFound => 7,
+                Error::ContractPaused => 8,
+                Error::ManagerNotFound => 9,
+                Error::ZeroAmount => 10,
+                Error::PaymentTooSmall => 11,
+                Error::MemoTooLong => 12,
+                Error::TooManyBeneficiaries => 13,
+                Error::BeneficiaryAlreadyExists => 14,
+                Error::AllowanceMismatch { .. } => 15,
+                Error::ApprovalExpired => 16,
+                Error::BeneficiaryFrozen => 17,
+                Error::FeeTooHigh => 18,
+                Error::WithdrawalLimitExceeded { .. } => 19,
+                Error::CooldownActive { .. } => 20,
+                Error::FundsLocked(_) => 21,
+                Error::RemovalNotRequested => 22,
+                Error::RemovalAlreadyRequested => 23,
+                Error::RemovalNotDue { .. } => 24,
+                Error::SharesNotFullyAllocated => 25,
+                Error::SharesLocked => 26,
+                Error::WrongShareModel => 27,
+                Error::InvalidWeight => 28,
+                Error::NoUncappedBeneficiary => 29,
+                Error::HeldPaymentNotFound => 30,
+                Error::CampaignEnded => 31,
+                Error::CampaignNotEnded => 32,
+                Error::CampaignNotActive => 33,
+                Error::RefundsNotOpen => 34,
+                Error::OutsideAcceptanceWindow => 35,
+                Error::InvalidAcceptanceWindow => 36,
+                Error::TokenNotAccepted => 37,
+                Error::ContractNotPaused => 38,
+                Error::UpgradeFailed => 39,
+                Error::ActionNotFound => 40,
+                Error::ActionNotReady { .. } => 41,
+                Error::AdminDelayTooShort => 42,
+                Error::ActionTimelocked => 43,
+                Error::NotCouncilMember => 44,
+                Error::AlreadyConfirmed => 45,
+                Error::NotConfirmed => 46,
+                Error::InvalidThreshold => 47,
+                Error::CouncilApprovalRequired => 48,
+                Error::FundsOutstanding => 49,
+                Error::SettlementFailed { .. } => 50,
+                Error::ContractRetired => 51,
+                Error::ReentrantCall => 52,
+                Error::InvitationNotFound => 53,
+                Error::AlreadyInvited => 54,
+                Error::AcceptanceRequired => 55,
+                Error::SameAccount => 56,
+                Error::InvalidSignature => 57,
+                Error::InvalidNonce { .. } => 58,
+                Error::SignatureExpired => 59,
+                Error::DuplicatePayment => 60,
+                Error::ArithmeticOverflow => 61,
+                Error::LabelTooLong => 62,
+                Error::InvalidLabel => 63,
+                Error::MetadataTooLong => 64,
+                Error::InstantiationFailed => 65,
+                Error::OperatorNotFound => 66,
+                Error::Blacklisted => 67,
+                Error::Insolvent => 68,
+                Error::DistributionInProgress => 69,
+                Error::ChunkingUnsupported => 70,
+                Error::NoPendingOwner => 71,
+            }
+        }
     }
 
     /// Result type for contract operations
     pub type Result<T> = core::result::Result<T, Error>;
 
+    /// Errors returned by a PSP22 token contract
+    #[derive(Debug, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub enum PSP22Error {
+        Custom(String),
+        InsufficientBalance,
+        InsufficientAllowance,
+        ZeroRecipientAddress,
+        ZeroSenderAddress,
+        SafeTransferCheckFailed(String),
+    }
+
     /// Beneficiary information
     #[ink::scale_derive(Encode, Decode, TypeInfo)]
     #[derive(Debug, Clone, PartialEq, Eq)]
     pub struct Beneficiary {
         pub account: H160,
-        pub share_percentage: u8, // 0-100
-        pub pending_U256: U256,
+        pub share_bps: u16, // 0-10_000 basis points
+        /// Relative weight under `ShareModel::Weighted`; 0 under `Percentage`
+        pub weight: u64,
+        /// Mirrored from `balances`; only meaningful in query results
+        pub pending_balance: U256,
+        /// Mirrored from `balances`; only meaningful in query results
         pub total_withdrawn: U256,
+        pub payout_address: Option<H160>, // Where payouts go instead of `account`
+        /// Set by the owner to stop funds leaving this beneficiary; shares still accrue
+        pub frozen: bool,
+        /// Mirrored from `last_withdrawal_at`; only meaningful in query results
+        pub last_withdrawal_at: Option<u64>,
+        /// Mirrored from `withdrawal_counts`; only meaningful in query results
+        pub withdrawal_count: u32,
+        /// Gradual unlock of everything credited to this beneficiary, if any
+        pub vesting: Option<VestingSchedule>,
+        /// Nothing can be withdrawn before this timestamp
+        pub locked_until: Option<u64>,
+        /// Order in which capped beneficiaries are filled, lowest first
+        pub priority: u8,
+        /// Paid up to this much per distribution ahead of the proportional split
+        pub cap_per_distribution: Option<U256>,
+        /// Whether this beneficiary takes a share or a flat amount of each distribution
+        pub kind: ShareKind,
+        /// Stop crediting once this much has been credited in total
+        pub lifetime_cap: Option<U256>,
+        /// Mirrored from `credited`; only meaningful in query results
+        pub total_credited: U256,
+        /// Mirrored from `tips`; only meaningful in query results
+        pub total_tips: U256,
+        /// Call `on_split_payout(amount)` on a contract destination after push payouts
+        pub notify_on_payout: bool,
+        /// Deliver funds through `receive_payment` on the destination instead of a plain transfer
+        pub forward_as_payment: bool,
+        /// Mirrored from `labels`; only meaningful in query results
+        pub label: Option<Vec<u8>>,
+    }
+
+    impl Beneficiary {
+        /// Address that withdrawals and push payouts are delivered to
+        pub fn payout_destination(&self) -> H160 {
+            self.payout_address.unwrap_or(self.account)
+        }
+    }
+
+    /// How a beneficiary's cut of each distribution is worked out
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum ShareKind {
+        /// By `share_bps` or `weight`, depending on the contract's share model
+        Proportional,
+        /// A flat amount credited before anything is split; never counts toward `total_shares`
+        Fixed(U256),
+    }
+
+    /// Linear unlock of a beneficiary's lifetime credited amount
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct VestingSchedule {
+        pub start: u64,
+        /// Nothing unlocks before `start + cliff_ms`
+        pub cliff_ms: u64,
+        /// Everything is unlocked at `start + duration_ms`
+        pub duration_ms: u64,
+    }
+
+    impl VestingSchedule {
+        /// Portion of `total` unlocked at time `now`
+        pub fn vested_amount(&self, total: U256, now: u64) -> U256 {
+            if now < self.start.saturating_add(self.cliff_ms) {
+                return U256::zero();
+            }
+            
+            let elapsed = now.saturating_sub(self.start);
+            if elapsed >= self.duration_ms {
+                return total;
+            }
+            
+            total.saturating_mul(U256::from(elapsed)) / U256::from(self.duration_ms)
+        }
     }
 
     /// Approval information for spending allowance
@@ -52,26 +430,591 @@ mod split_payment {
         pub expires_at: Option<u64>, // Optional expiration timestamp
     }
 
+    /// A payment received through `receive_payment`
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct PaymentRecord {
+        pub payer: H160,
+        pub amount: U256,
+        pub timestamp: u64,
+        pub block: u32,
+        pub memo: Option<Vec<u8>>,
+    }
+
+    /// Owner-tunable settings, read together with `get_config`
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct Config {
+        /// Smallest payment `receive_payment` accepts
+        pub min_payment: U256,
+        /// Share of every incoming payment kept as a protocol fee, in basis points
+        pub fee_bps: u16,
+        /// Account allowed to withdraw accrued protocol fees
+        pub fee_recipient: H160,
+        /// Share of a manual distribution paid to a third-party caller, in basis points
+        pub keeper_fee_bps: u16,
+        /// Whether payments are split on receipt or on demand
+        pub distribution_mode: DistributionMode,
+        /// Upper bound on the number of beneficiaries, keeping distribution bounded
+        pub max_beneficiaries: u32,
+        /// Minimum time between two withdrawals by the same beneficiary (0 = disabled)
+        pub withdrawal_cooldown_ms: u64,
+        /// Most any beneficiary may withdraw per rolling window, as `(amount, window_ms)`
+        pub withdrawal_limit: Option<(U256, u64)>,
+        /// Whether payments are refused unless shares add up to `MAX_SHARE_BPS`
+        pub strict_allocation: bool,
+        /// Number of payment records kept (0 = unlimited)
+        pub max_history: u64,
+        /// Pool size at which `Immediate` mode splits pooled payments (0 = split every payment)
+        pub distribution_threshold: U256,
+    }
+
+    /// Changes for `update_config`; `None` leaves a setting as it is
+    ///
+    /// `withdrawal_limit` is doubly optional: `Some(None)` lifts the limit.
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[derive(Debug, Clone, Default, PartialEq, Eq)]
+    pub struct ConfigUpdate {
+        pub min_payment: Option<U256>,
+        pub fee_bps: Option<u16>,
+        pub fee_recipient: Option<H160>,
+        pub keeper_fee_bps: Option<u16>,
+        pub distribution_mode: Option<DistributionMode>,
+        pub max_beneficiaries: Option<u32>,
+        pub withdrawal_cooldown_ms: Option<u64>,
+        pub withdrawal_limit: Option<Option<(U256, u64)>>,
+        pub strict_allocation: Option<bool>,
+        pub max_history: Option<u64>,
+        pub distribution_threshold: Option<U256>,
+    }
+
+    /// One split of funds across beneficiaries, kept by `distribute_funds`
+    ///
+    /// Per-beneficiary amounts are in the `ShareCredited` events with the same id.
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct DistributionRecord {
+        /// Amount that was split, before unassigned shares and dust went to the pool
+        pub amount: U256,
+        pub timestamp: u64,
+        pub beneficiary_count: u32,
+        /// Basis points allocated when the split ran; 0 under `ShareModel::Weighted`
+        pub total_shares_at_time: u16,
+        /// Share table in effect, see `get_config_at`
+        pub config_version: u64,
+    }
+
+    /// State of a distribution split over several `continue_distribution` calls
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct DistributionProgress {
+        pub distribution_id: u64,
+        /// Pool amount taken by `start_distribution`
+        pub amount: U256,
+        /// `share_units` total the amount is split over
+        pub denominator: u64,
+        /// Index in the beneficiary list of the next beneficiary to credit
+        pub cursor: u32,
+        /// `share_units` of the beneficiaries before `cursor`
+        pub units_before: u64,
+        pub beneficiary_count: u32,
+        /// Credited to beneficiaries so far
+        pub credited: U256,
+    }
+
+    /// Destructive admin change that can be queued behind the admin delay
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum AdminAction {
+        /// Remove a beneficiary, paying out their whole pending balance
+        RemoveBeneficiary(H160),
+        /// Change a beneficiary's share, in basis points
+        SetShare(H160, u16),
+        /// Replace the contract code (deposits must be paused at execution)
+        Upgrade(H256),
+        /// Hand ownership to another account
+        TransferOwnership(H160),
+    }
+
+    /// Kind of change recorded in the admin log
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum AdminActionKind {
+        AddBeneficiary,
+        RemoveBeneficiary,
+        AddManager,
+        RemoveManager,
+        AddOperator,
+        RemoveOperator,
+        /// Any of `pause`, `pause_deposits` or `pause_withdrawals`
+        Pause,
+        /// Any of `unpause`, `unpause_deposits` or `unpause_withdrawals`
+        Unpause,
+        TransferOwnership,
+        /// A change to any setting in `Config`
+        UpdateConfig,
+    }
+
+    /// One entry of the admin log
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct AdminLogEntry {
+        pub actor: H160,
+        pub action: AdminActionKind,
+        /// Account the change applied to, if any
+        pub target: Option<H160>,
+        pub timestamp: u64,
+    }
+
+    /// An admin action waiting for its delay to pass
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct PendingAction {
+        pub id: u64,
+        pub action: AdminAction,
+        pub execute_after: u64,
+    }
+
+    /// A payment held in escrow until it is released or refunded
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct HeldPayment {
+        pub id: u64,
+        pub payer: H160,
+        pub amount: U256,
+    }
+
+    /// Funding goal that must be reached by `deadline` for pledges to be released
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct Campaign {
+        pub goal: U256,
+        pub deadline: u64,
+    }
+
+    /// Progress of the campaign, returned by `get_campaign_status`
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct CampaignStatus {
+        pub goal: U256,
+        pub deadline: u64,
+        /// Pledged and not yet released or refunded
+        pub raised: U256,
+        pub finalized: bool,
+        pub refunds_open: bool,
+    }
+
+    /// Running totals for one asset, returned by `get_asset_stats`
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[derive(Debug, Clone, Default, PartialEq, Eq)]
+    pub struct AssetStats {
+        pub total_received: U256,
+        /// Credited to beneficiaries
+        pub total_distributed: U256,
+        /// Credited and not yet withdrawn, paid out or forfeited
+        pub total_pending: U256,
+        pub total_withdrawn: U256,
+    }
+
+    /// Snapshot of the contract's accounting, returned by `get_stats_v2`
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct ContractStats {
+        pub total_received: U256,
+        pub total_distributed: U256,
+        pub total_pending: U256,
+        pub total_withdrawn: U256,
+        pub contract_balance: U256,
+        pub beneficiary_count: u32,
+        pub payment_count: u64,
+        pub last_payment_at: Option<u64>,
+        pub last_distribution_at: Option<u64>,
+        pub largest_payment: U256,
+        pub paused: bool,
+    }
+
+    /// How a payment was allocated, returned by `receive_payment_detailed`
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct DistributionSummary {
+        pub gross: U256,
+        /// Protocol fee taken before the split
+        pub fee: U256,
+        /// Non-zero credits, in list order; at most `max_beneficiaries` entries
+        pub credited: Vec<(H160, U256)>,
+        /// Not credited to anyone yet: unallocated, pooled for a manual
+        /// distribution, or held by escrow or a campaign
+        pub undistributed: U256,
+    }
+
+    /// Result of `check_invariants`: the figures compared and whether each invariant holds
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct InvariantReport {
+        pub total_received: U256,
+        pub total_distributed: U256,
+        /// Sum of current beneficiaries' pending balances
+        pub total_pending: U256,
+        /// Sum of current beneficiaries' lifetime withdrawals
+        pub total_withdrawn: U256,
+        /// Undistributed pool plus the distribution pool
+        pub undistributed: U256,
+        pub fee_accrued: U256,
+        /// Credited funds moved back to the undistributed pool on removal
+        pub total_forfeited: U256,
+        /// Fees and sweeps paid out of funds never credited to a beneficiary
+        pub pool_outflows: U256,
+        pub contract_balance: U256,
+        /// `total_received + total_forfeited == total_distributed + undistributed + fee_accrued + pool_outflows`
+        pub ledger_balanced: bool,
+        /// The contract holds at least everything it owes or keeps in a pool
+        pub solvent: bool,
+    }
+
+    impl Config {
+        /// This config with every setting `patch` specifies replaced
+        fn patched(self, patch: ConfigUpdate) -> Self {
+            Self {
+                min_payment: patch.min_payment.unwrap_or(self.min_payment),
+                fee_bps: patch.fee_bps.unwrap_or(self.fee_bps),
+                fee_recipient: patch.fee_recipient.unwrap_or(self.fee_recipient),
+                keeper_fee_bps: patch.keeper_fee_bps.unwrap_or(self.keeper_fee_bps),
+                distribution_mode: patch.distribution_mode.unwrap_or(self.distribution_mode),
+                max_beneficiaries: patch.max_beneficiaries.unwrap_or(self.max_beneficiaries),
+                withdrawal_cooldown_ms: patch.withdrawal_cooldown_ms.unwrap_or(self.withdrawal_cooldown_ms),
+                withdrawal_limit: patch.withdrawal_limit.unwrap_or(self.withdrawal_limit),
+                strict_allocation: patch.strict_allocation.unwrap_or(self.strict_allocation),
+                max_history: patch.max_history.unwrap_or(self.max_history),
+                distribution_threshold: patch.distribution_threshold.unwrap_or(self.distribution_threshold),
+            }
+        }
+    }
+
+    impl DistributionSummary {
+        /// A payment kept aside by escrow or a campaign, not yet accepted
+        fn held(amount: U256) -> Self {
+            Self {
+                gross: amount,
+                fee: U256::zero(),
+                credited: Vec::new(),
+                undistributed: amount,
+            }
+        }
+    }
+
+    impl InvariantReport {
+        /// Whether every invariant holds
+        pub fn holds(&self) -> bool {
+            self.ledger_balanced && self.solvent
+        }
+    }
+
+    /// A manager's permissions and how long they hold them
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct ManagerAppointment {
+        pub permissions: ManagerPermissions,
+        /// The appointment lapses at this timestamp; `None` never expires
+        pub expires_at: Option<u64>,
+    }
+
+    impl ManagerAppointment {
+        /// Whether the appointment still holds at `now`
+        fn is_active(&self, now: u64) -> bool {
+            !matches!(self.expires_at, Some(expires_at) if expires_at <= now)
+        }
+    }
+
+    /// What a manager is allowed to do
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+    pub struct ManagerPermissions {
+        pub can_add_beneficiary: bool,
+        pub can_remove_beneficiary: bool,
+        pub can_pause: bool,
+        pub can_trigger_distribution: bool,
+    }
+
+    impl ManagerPermissions {
+        /// Every manager permission
+        pub fn all() -> Self {
+            Self {
+                can_add_beneficiary: true,
+                can_remove_beneficiary: true,
+                can_pause: true,
+                can_trigger_distribution: true,
+            }
+        }
+
+        fn allows(&self, permission: Permission) -> bool {
+            match permission {
+                Permission::AddBeneficiary => self.can_add_beneficiary,
+                Permission::RemoveBeneficiary => self.can_remove_beneficiary,
+                Permission::Pause => self.can_pause,
+                Permission::TriggerDistribution => self.can_trigger_distribution,
+            }
+        }
+    }
+
+    /// A single manager permission, checked by `ensure_permission`
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum Permission {
+        AddBeneficiary,
+        RemoveBeneficiary,
+        Pause,
+        TriggerDistribution,
+    }
+
+    /// When received funds are split across beneficiaries
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum DistributionMode {
+        /// Split every payment as it is received
+        Immediate,
+        /// Accumulate payments until `distribute` is called
+        Manual,
+    }
+
+    /// How beneficiary shares are expressed
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum ShareModel {
+        /// Basis points out of `MAX_SHARE_BPS`; unallocated points go to the undistributed pool
+        Percentage,
+        /// Arbitrary weights; each beneficiary gets `weight / total_weight` of every payment
+        Weighted,
+    }
+
     #[ink(storage)]
     pub struct SplitPayment {
         /// Contract owner (has admin privileges)
         owner: H160,
+        /// Display name telling this splitter apart from others
+        name: Lazy<Vec<u8>>,
+        /// Free-form description shown next to the name
+        description: Lazy<Vec<u8>>,
+        /// Proposed new owner waiting to accept ownership
+        pending_owner: Option<H160>,
         /// List of authorized managers (can add/remove beneficiaries)
-        managers: Mapping<H160, bool>,
-        /// List of beneficiaries and their share percentages
-        beneficiaries: StorageVec<Beneficiary>,
-        /// Total share percentage allocated (should not exceed 100)
-        total_shares: u8,
+        managers: Mapping<H160, ManagerAppointment>,
+        /// Enumerable list of the accounts in `managers`
+        manager_list: Lazy<Vec<H160>>,
+        /// Accounts that may only trigger distributions and push payouts
+        operators: Mapping<H160, bool>,
+        /// Enumerable list of the accounts in `operators`
+        operator_list: Lazy<Vec<H160>>,
+        /// Beneficiary records keyed by account, so withdrawals and lookups
+        /// only read and write the one affected record
+        beneficiary_data: Mapping<H160, Beneficiary>,
+        /// Beneficiary accounts in insertion order, used for iteration
+        ///
+        /// Lists sit in their own `Lazy` cells, so messages touching a single
+        /// record (withdrawals, lookups) never decode them.
+        beneficiary_accounts: Lazy<Vec<H160>>,
+        /// Owner-tunable settings, see `Config`
+        config: Config,
+        /// `(pending, total_withdrawn)` per beneficiary, kept apart from the
+        /// share configuration so a withdrawal touches exactly one small cell
+        balances: Mapping<H160, (U256, U256)>,
+        /// Lifetime amount credited to each beneficiary by distributions
+        credited: Mapping<H160, U256>,
+        /// Lifetime amount tipped directly to each beneficiary
+        tips: Mapping<H160, U256>,
+        /// Display name of each beneficiary that has one, UTF-8
+        labels: Mapping<H160, Vec<u8>>,
+        /// `(amount, unlocks_at)` owed to removed beneficiaries whose funds were locked
+        parked: Mapping<H160, (U256, u64)>,
+        /// Sum of all parked amounts
+        total_parked: U256,
+        /// Removal payouts whose transfer failed, waiting for `claim_escrow`
+        claimable: Mapping<H160, U256>,
+        /// Sum of all claimable amounts
+        total_claimable: U256,
+        /// Push payouts whose transfer failed, waiting for `retry_failed_payout`
+        failed_payouts: Mapping<H160, U256>,
+        /// Accounts with a failed payout, in order of first failure
+        failed_payout_accounts: Lazy<Vec<H160>>,
+        /// Sum of all failed payouts
+        total_failed_payouts: U256,
+        /// How long `request_remove_beneficiary` waits before removal can be finalized
+        removal_delay_ms: u64,
+        /// `(effective_at, forfeit_unvested)` for each requested removal
+        pending_removals: Mapping<H160, (u64, bool)>,
+        /// Accounts with a pending removal, in request order
+        pending_removal_accounts: Lazy<Vec<H160>>,
+        /// Whether beneficiaries can only join through `invite_beneficiary` and `accept_invitation`
+        require_acceptance: bool,
+        /// Share offered to each invited account
+        invitations: Mapping<H160, u16>,
+        /// Accounts with a pending invitation, in invitation order
+        invitation_accounts: Lazy<Vec<H160>>,
+        /// Total basis points allocated (should not exceed `MAX_SHARE_BPS`)
+        total_shares: u16,
+        /// Fixed at construction; `Percentage` unless built with `new_with_share_model`
+        share_model: ShareModel,
+        /// Sum of beneficiary weights under `ShareModel::Weighted`
+        total_weight: u64,
         /// Mapping from beneficiary to approvals granted to other accounts
         approvals: Mapping<(H160, H160), Approval>,
+        /// Spenders each beneficiary currently has an approval for, so they
+        /// can be listed and revoked without off-chain bookkeeping
+        spenders_of: Mapping<H160, Vec<H160>>,
         /// Mapping from account to total allowance they can spend on behalf of others
         allowances: Mapping<H160, U256>,
-        /// Contract pause state (emergency stop)
-        paused: bool,
+        /// Next nonce each beneficiary must sign for `approve_with_signature`
+        approval_nonces: Mapping<H160, u64>,
+        /// Next nonce each beneficiary must sign for `withdraw_with_signature`
+        withdrawal_nonces: Mapping<H160, u64>,
+        /// Whether incoming payments and distributions are stopped
+        deposits_paused: bool,
+        /// Whether withdrawals and payouts are stopped
+        withdrawals_paused: bool,
+        /// Whether `pause` leaves withdrawals open
+        withdrawals_allowed_when_paused: bool,
         /// Total funds received by the contract
         total_received: U256,
         /// Total funds distributed
         total_distributed: U256,
+        /// Unallocated portion of received funds (unassigned shares and rounding dust)
+        undistributed_pool: U256,
+        /// Credited funds moved back to the undistributed pool when a removal forfeits them
+        total_forfeited: U256,
+        /// Protocol fees, keeper fees and sweeps paid out of funds never credited to a beneficiary
+        pool_outflows: U256,
+        /// Received funds waiting for a manual `distribute` call
+        distribution_pool: U256,
+        /// Chunked distribution under way, if any
+        distribution_progress: Option<DistributionProgress>,
+        /// Protocol fees collected and not yet withdrawn
+        fee_accrued: U256,
+        /// Payments before this timestamp are refused
+        accepting_from: Option<u64>,
+        /// Payments after this timestamp are refused
+        accepting_until: Option<u64>,
+        /// `(withdrawn_in_window, window_start)` per beneficiary
+        withdrawal_windows: Mapping<H160, (U256, u64)>,
+        /// Timestamp of each beneficiary's most recent withdrawal
+        last_withdrawal_at: Mapping<H160, u64>,
+        /// Number of withdrawals and payouts each beneficiary has received
+        withdrawal_counts: Mapping<H160, u32>,
+        /// Recent payments by id
+        payments: Mapping<u64, PaymentRecord>,
+        /// Number of payments ever recorded (next payment id)
+        payment_count: u64,
+        /// Timestamp of the most recently recorded payment
+        last_payment_at: Option<u64>,
+        /// Every distribution by id
+        distributions: Mapping<u64, DistributionRecord>,
+        /// Bumped on every change to the beneficiary list or shares
+        config_version: u64,
+        /// `(account, share_bps)` of every beneficiary at each config version
+        config_history: Mapping<u64, Vec<(H160, u16)>>,
+        /// Lowest config version that may still be stored
+        config_history_start: u64,
+        /// Number of distributions ever made (next distribution id)
+        distribution_count: u64,
+        /// Timestamp of the most recent distribution to beneficiaries
+        last_distribution_at: Option<u64>,
+        /// Largest single payment ever recorded
+        largest_payment: U256,
+        /// Lowest payment id that may still be stored
+        payment_history_start: u64,
+        /// Idempotency keys of payments made through `receive_payment_idempotent`
+        payment_keys: Mapping<[u8; 32], ()>,
+        /// Total paid in by each payer
+        contributions: Mapping<H160, U256>,
+        /// Every account that has paid in, in order of first payment
+        contributors: Lazy<Vec<H160>>,
+        /// Number of distinct payers
+        contributor_count: u32,
+        /// Whether only allowed payers may call `receive_payment`
+        payer_whitelist_enabled: bool,
+        /// Accounts allowed to pay in while the whitelist is enabled
+        allowed_payers: Mapping<H160, bool>,
+        /// Accounts barred from paying in, becoming beneficiaries, spending
+        /// approvals or moving their pending balance
+        blacklist: Mapping<H160, bool>,
+        /// Set once by `finalize_shares`; the beneficiary list can never change again
+        shares_locked: bool,
+        /// Set once by `retire`; no new funds are accepted and withdrawals ignore pauses
+        retired: bool,
+        /// Held while a message that transfers funds or calls out is running;
+        /// kept in its own cell so a reentrant call sees it before the
+        /// outer message has written the rest of the storage back
+        reentrancy_lock: Lazy<bool>,
+        /// Whether `receive_payment` holds payments instead of accepting them
+        escrow_enabled: bool,
+        /// Payments waiting for `release_payment` or `refund_payment`, by id
+        held_payments: Mapping<u64, HeldPayment>,
+        /// Number of payments ever held (next held payment id)
+        held_count: u64,
+        /// Sum of all held payments
+        held_total: U256,
+        /// Set at construction by `new_with_campaign`
+        campaign: Option<Campaign>,
+        /// Amount each payer has pledged to the campaign and not had refunded
+        pledges: Mapping<H160, U256>,
+        /// Sum of all pledges
+        campaign_raised: U256,
+        /// Whether `finalize_campaign` has run
+        campaign_finalized: bool,
+        /// Set when the campaign missed its goal; payers may then `refund`
+        refunds_open: bool,
+        /// Pending PSP22 balance per `(token, beneficiary)`
+        token_pending: Mapping<(H160, H160), U256>,
+        /// Total of each PSP22 token ever received
+        token_received: Mapping<H160, U256>,
+        /// Portion of each PSP22 token not credited to any beneficiary
+        token_undistributed: Mapping<H160, U256>,
+        /// PSP22 tokens `receive_token_payment` accepts
+        accepted_tokens: Mapping<H160, bool>,
+        /// Enumerable list of the tokens in `accepted_tokens`
+        accepted_token_list: Lazy<Vec<H160>>,
+        /// How long a proposed admin action waits before it can be executed (0 = no timelock)
+        admin_delay_ms: u64,
+        /// Proposed admin actions by id
+        pending_actions: Mapping<u64, PendingAction>,
+        /// Ids in `pending_actions`, in proposal order
+        pending_action_ids: Lazy<Vec<u64>>,
+        /// Number of admin actions ever proposed (next action id)
+        action_count: u64,
+        /// Accounts that jointly approve admin actions; empty unless a council is configured
+        council: Lazy<Vec<H160>>,
+        /// Confirmations an admin transaction needs before it executes
+        threshold: u8,
+        /// `(action, confirmation_count)` for each admin transaction awaiting confirmations
+        admin_txs: Mapping<u64, (AdminAction, u8)>,
+        /// Whether a council member confirmed an admin transaction
+        confirmations: Mapping<(u64, H160), bool>,
+        /// Number of admin transactions ever submitted (next transaction id)
+        admin_tx_count: u64,
+        /// Running totals per asset; `None` is the native currency
+        asset_stats: Mapping<Option<H160>, AssetStats>,
+        /// Every asset that has ever flowed through the contract, in order of first use
+        assets: Lazy<Vec<Option<H160>>>,
+        /// Splitters created through `create_child`, in creation order
+        children: Lazy<Vec<H160>>,
+        /// Ring buffer of the last `ADMIN_LOG_CAPACITY` admin changes, by slot
+        admin_log: Mapping<u32, AdminLogEntry>,
+        /// Slot the next admin log entry is written to
+        log_head: u32,
+        /// Number of slots in use, at most `ADMIN_LOG_CAPACITY`
+        log_len: u32,
     }
 
     /// Events emitted by the contract
@@ -79,20 +1022,90 @@ mod split_payment {
     pub struct FundsReceived {
         #[ink(topic)]
         from: H160,
+        /// Gross amount paid in
+        amount: U256,
+        /// Protocol fee carved out of `amount`
+        fee: U256,
+        /// What is left for beneficiaries after the fee
+        net_amount: U256,
+        memo: Option<Vec<u8>>,
+    }
+
+    #[ink(event)]
+    pub struct TipReceived {
+        #[ink(topic)]
+        from: H160,
+        #[ink(topic)]
+        beneficiary: H160,
+        amount: U256,
+    }
+
+    #[ink(event)]
+    pub struct TokenPaymentReceived {
+        #[ink(topic)]
+        token: H160,
+        #[ink(topic)]
+        from: H160,
+        amount: U256,
+    }
+
+    #[ink(event)]
+    pub struct TokenWithdrawal {
+        #[ink(topic)]
+        token: H160,
+        #[ink(topic)]
+        beneficiary: H160,
+        destination: H160,
         amount: U256,
     }
 
     #[ink(event)]
     pub struct FundsDistributed {
+        #[ink(topic)]
+        distribution_id: u64,
+        /// Share table the funds were split by, see `get_config_at`
+        config_version: u64,
         total_amount: U256,
         beneficiary_count: u32,
+        /// Bounty paid to whoever triggered a manual distribution
+        keeper_fee: U256,
+        /// Protocol fee taken from the payment before it was split; zero for
+        /// manual distributions, whose fees were taken on receipt
+        protocol_fee: U256,
+    }
+
+    #[ink(event)]
+    pub struct DistributionStarted {
+        #[ink(topic)]
+        distribution_id: u64,
+        amount: U256,
+        beneficiary_count: u32,
+    }
+
+    /// One beneficiary's credit from a distribution, emitted before its `FundsDistributed`
+    #[ink(event)]
+    pub struct ShareCredited {
+        #[ink(topic)]
+        distribution_id: u64,
+        #[ink(topic)]
+        beneficiary: H160,
+        amount: U256,
+    }
+
+    #[ink(event)]
+    pub struct BeneficiaryLabelChanged {
+        #[ink(topic)]
+        beneficiary: H160,
+        label: Option<Vec<u8>>,
     }
 
     #[ink(event)]
     pub struct BeneficiaryAdded {
         #[ink(topic)]
         beneficiary: H160,
-        share_percentage: u8,
+        share_bps: u16,
+        weight: u64,
+        kind: ShareKind,
         #[ink(topic)]
         added_by: H160,
     }
@@ -103,532 +1116,10226 @@ mod split_payment {
         beneficiary: H160,
         #[ink(topic)]
         removed_by: H160,
+        /// Unvested balance moved to the undistributed pool instead of paid out
+        forfeited: U256,
+        /// Basis points that became unallocated and can be reassigned
+        freed_share_bps: u16,
     }
 
     #[ink(event)]
-    pub struct ApprovalGranted {
+    pub struct BeneficiaryShareUpdated {
         #[ink(topic)]
-        owner: H160,
+        beneficiary: H160,
+        old_share_bps: u16,
+        new_share_bps: u16,
+    }
+
+    #[ink(event)]
+    pub struct BeneficiariesReset {
+        beneficiary_count: u32,
+        total_shares: u16,
         #[ink(topic)]
-        spender: H160,
-        amount: U256,
-        expires_at: Option<u64>,
+        reset_by: H160,
     }
 
     #[ink(event)]
-    pub struct ApprovalRevoked {
+    pub struct BeneficiaryAccountRotated {
         #[ink(topic)]
-        owner: H160,
+        old: H160,
         #[ink(topic)]
-        spender: H160,
+        new: H160,
     }
 
     #[ink(event)]
-    pub struct WithdrawalByApproval {
+    pub struct PendingTransferred {
         #[ink(topic)]
-        beneficiary: H160,
+        from: H160,
         #[ink(topic)]
-        spender: H160,
+        to: H160,
         amount: U256,
     }
 
     #[ink(event)]
-    pub struct ManagerAdded {
+    pub struct BeneficiaryInvited {
         #[ink(topic)]
-        manager: H160,
+        account: H160,
+        share_bps: u16,
         #[ink(topic)]
-        added_by: H160,
+        invited_by: H160,
     }
 
     #[ink(event)]
-    pub struct ManagerRemoved {
+    pub struct InvitationDeclined {
         #[ink(topic)]
-        manager: H160,
+        account: H160,
+    }
+
+    #[ink(event)]
+    pub struct InvitationCancelled {
         #[ink(topic)]
-        removed_by: H160,
+        account: H160,
+        #[ink(topic)]
+        cancelled_by: H160,
     }
 
     #[ink(event)]
-    pub struct ContractPaused {
+    pub struct RemovalRequested {
         #[ink(topic)]
-        by: H160,
+        beneficiary: H160,
+        effective_at: u64,
+        #[ink(topic)]
+        requested_by: H160,
     }
 
     #[ink(event)]
-    pub struct ContractUnpaused {
+    pub struct RemovalCancelled {
         #[ink(topic)]
-        by: H160,
+        beneficiary: H160,
+        #[ink(topic)]
+        cancelled_by: H160,
     }
 
-    impl SplitPayment {
-        /// Constructor - creates a new split payment contract
-        #[ink(constructor)]
-        pub fn new() -> Self {
-            let owner = Self::env().caller();
-            
-            Self {
-                owner,
-                managers: Mapping::default(),
-                beneficiaries: Vec::new(),
-                total_shares: 0,
-                approvals: Mapping::default(),
-                allowances: Mapping::default(),
-                paused: false,
-                total_received: 0,
-                total_distributed: 0,
-            }
-        }
+    #[ink(event)]
+    pub struct RemovalDelayChanged {
+        delay_ms: u64,
+    }
 
-        /// Payable function to receive funds
-        #[ink(message)]
-        #[ink(payable)]
-        pub fn receive_payment(&mut self) -> Result<()> {
-            self.ensure_not_paused()?;
-            
-            let amount = self.env().transferred_value();
-            let caller = self.env().caller();
-            
-            self.total_received = self.total_received.saturating_add(amount);
-            
-            // Distribute the received funds immediately
-            self.distribute_funds(amount)?;
-            
-            self.env().emit_event(FundsReceived {
-                from: caller,
-                amount,
+    #[ink(event)]
+    pub struct TierSet {
+        #[ink(topic)]
+        beneficiary: H160,
+        priority: u8,
+        cap_per_distribution: Option<U256>,
+    }
+
+    #[ink(event)]
+    pub struct LifetimeCapSet {
+        #[ink(topic)]
+        beneficiary: H160,
+        lifetime_cap: Option<U256>,
+    }
+
+    #[ink(event)]
+    pub struct CapReached {
+        #[ink(topic)]
+        beneficiary: H160,
+        lifetime_cap: U256,
+    }
+
+    #[ink(event)]
+    pub struct LockSet {
+        #[ink(topic)]
+        beneficiary: H160,
+        locked_until: Option<u64>,
+    }
+
+    #[ink(event)]
+    pub struct FundsParked {
+        #[ink(topic)]
+        account: H160,
+        amount: U256,
+        unlocks_at: u64,
+    }
+
+    #[ink(event)]
+    pub struct ParkedFundsClaimed {
+        #[ink(topic)]
+        account: H160,
+        amount: U256,
+    }
+
+    #[ink(event)]
+    pub struct PayoutEscrowed {
+        #[ink(topic)]
+        account: H160,
+        amount: U256,
+    }
+
+    #[ink(event)]
+    pub struct EscrowClaimed {
+        #[ink(topic)]
+        account: H160,
+        amount: U256,
+    }
+
+    #[ink(event)]
+    pub struct VestingScheduleSet {
+        #[ink(topic)]
+        beneficiary: H160,
+        schedule: Option<VestingSchedule>,
+    }
+
+    #[ink(event)]
+    pub struct PayoutAddressChanged {
+        #[ink(topic)]
+        beneficiary: H160,
+        payout_address: Option<H160>,
+    }
+
+    #[ink(event)]
+    pub struct BeneficiaryFreezeChanged {
+        #[ink(topic)]
+        beneficiary: H160,
+        frozen: bool,
+    }
+
+    #[ink(event)]
+    pub struct ApprovalGranted {
+        #[ink(topic)]
+        owner: H160,
+        #[ink(topic)]
+        spender: H160,
+        amount: U256,
+        expires_at: Option<u64>,
+    }
+
+    #[ink(event)]
+    pub struct ApprovalRevoked {
+        #[ink(topic)]
+        owner: H160,
+        #[ink(topic)]
+        spender: H160,
+    }
+
+    #[ink(event)]
+    pub struct WithdrawalByApproval {
+        #[ink(topic)]
+        beneficiary: H160,
+        #[ink(topic)]
+        spender: H160,
+        amount: U256,
+        /// Either the spender or the beneficiary's payout destination
+        recipient: H160,
+    }
+
+    /// Emitted after the `Withdrawal` of a `withdraw_with_signature` call
+    #[ink(event)]
+    pub struct WithdrawalRelayed {
+        #[ink(topic)]
+        beneficiary: H160,
+        #[ink(topic)]
+        relayer: H160,
+        relayer_fee: U256,
+        nonce: u64,
+    }
+
+    #[ink(event)]
+    pub struct Withdrawal {
+        #[ink(topic)]
+        beneficiary: H160,
+        #[ink(topic)]
+        destination: H160,
+        amount: U256,
+        remaining_pending: U256,
+        /// Account that sent the withdrawal; differs from `beneficiary` for `claim_for`
+        triggered_by: H160,
+    }
+
+    #[ink(event)]
+    pub struct ManagerAdded {
+        #[ink(topic)]
+        manager: H160,
+        #[ink(topic)]
+        added_by: H160,
+        permissions: ManagerPermissions,
+        expires_at: Option<u64>,
+    }
+
+    #[ink(event)]
+    pub struct ManagerPermissionsUpdated {
+        #[ink(topic)]
+        manager: H160,
+        permissions: ManagerPermissions,
+    }
+
+    #[ink(event)]
+    pub struct ManagerRemoved {
+        #[ink(topic)]
+        manager: H160,
+        #[ink(topic)]
+        removed_by: H160,
+    }
+
+    #[ink(event)]
+    pub struct BlacklistUpdated {
+        #[ink(topic)]
+        account: H160,
+        blacklisted: bool,
+    }
+
+    #[ink(event)]
+    pub struct OperatorAdded {
+        #[ink(topic)]
+        operator: H160,
+        #[ink(topic)]
+        added_by: H160,
+    }
+
+    #[ink(event)]
+    pub struct OperatorRemoved {
+        #[ink(topic)]
+        operator: H160,
+        #[ink(topic)]
+        removed_by: H160,
+    }
+
+    #[ink(event)]
+    pub struct ContractPaused {
+        #[ink(topic)]
+        by: H160,
+    }
+
+    #[ink(event)]
+    pub struct InvariantViolated {
+        ledger_balanced: bool,
+        solvent: bool,
+    }
+
+    #[ink(event)]
+    pub struct ContractUnpaused {
+        #[ink(topic)]
+        by: H160,
+    }
+
+    #[ink(event)]
+    pub struct PausePolicyChanged {
+        withdrawals_allowed_when_paused: bool,
+    }
+
+    #[ink(event)]
+    pub struct DepositsPaused {
+        #[ink(topic)]
+        by: H160,
+    }
+
+    #[ink(event)]
+    pub struct DepositsUnpaused {
+        #[ink(topic)]
+        by: H160,
+    }
+
+    #[ink(event)]
+    pub struct WithdrawalsPaused {
+        #[ink(topic)]
+        by: H160,
+    }
+
+    #[ink(event)]
+    pub struct WithdrawalsUnpaused {
+        #[ink(topic)]
+        by: H160,
+    }
+
+    #[ink(event)]
+    pub struct OwnershipProposed {
+        #[ink(topic)]
+        current_owner: H160,
+        #[ink(topic)]
+        proposed_owner: H160,
+    }
+
+    #[ink(event)]
+    pub struct OwnershipProposalCancelled {
+        #[ink(topic)]
+        cancelled: H160,
+    }
+
+    #[ink(event)]
+    pub struct OwnershipRenounced {
+        #[ink(topic)]
+        previous_owner: H160,
+    }
+
+    #[ink(event)]
+    pub struct OwnershipTransferred {
+        #[ink(topic)]
+        previous_owner: H160,
+        #[ink(topic)]
+        new_owner: H160,
+    }
+
+    #[ink(event)]
+    pub struct ActionProposed {
+        #[ink(topic)]
+        id: u64,
+        action: AdminAction,
+        execute_after: u64,
+    }
+
+    #[ink(event)]
+    pub struct ActionExecuted {
+        #[ink(topic)]
+        id: u64,
+        action: AdminAction,
+    }
+
+    #[ink(event)]
+    pub struct ActionCancelled {
+        #[ink(topic)]
+        id: u64,
+    }
+
+    #[ink(event)]
+    pub struct AdminDelayIncreased {
+        admin_delay_ms: u64,
+    }
+
+    #[ink(event)]
+    pub struct CouncilConfigured {
+        members: Vec<H160>,
+        threshold: u8,
+    }
+
+    #[ink(event)]
+    pub struct AdminTxSubmitted {
+        #[ink(topic)]
+        id: u64,
+        action: AdminAction,
+        #[ink(topic)]
+        submitted_by: H160,
+    }
+
+    #[ink(event)]
+    pub struct AdminTxConfirmed {
+        #[ink(topic)]
+        id: u64,
+        #[ink(topic)]
+        by: H160,
+        confirmations: u8,
+    }
+
+    #[ink(event)]
+    pub struct AdminTxConfirmationRevoked {
+        #[ink(topic)]
+        id: u64,
+        #[ink(topic)]
+        by: H160,
+        confirmations: u8,
+    }
+
+    #[ink(event)]
+    pub struct AdminTxExecuted {
+        #[ink(topic)]
+        id: u64,
+        action: AdminAction,
+    }
+
+    #[ink(event)]
+    pub struct ContractRetired {
+        total_received: U256,
+    }
+
+    #[ink(event)]
+    pub struct ContractTerminated {
+        #[ink(topic)]
+        owner: H160,
+        /// Balance left after settlement, sent to the owner
+        residual: U256,
+    }
+
+    #[ink(event)]
+    pub struct CodeUpgraded {
+        old_hash: H256,
+        new_hash: H256,
+    }
+
+    #[ink(event)]
+    pub struct ChildCreated {
+        #[ink(topic)]
+        child: H160,
+        #[ink(topic)]
+        owner: H160,
+    }
+
+    #[ink(event)]
+    pub struct MetadataChanged {
+        name: Vec<u8>,
+        description: Vec<u8>,
+    }
+
+    #[ink(event)]
+    pub struct ConfigChanged {
+        config: Config,
+    }
+
+    #[ink(event)]
+    pub struct MaxBeneficiariesChanged {
+        max_beneficiaries: u32,
+    }
+
+    #[ink(event)]
+    pub struct WithdrawalLimitChanged {
+        /// `(amount, window_ms)`, or `None` when the limit is lifted
+        limit: Option<(U256, u64)>,
+    }
+
+    #[ink(event)]
+    pub struct WithdrawalCooldownChanged {
+        cooldown_ms: u64,
+    }
+
+    #[ink(event)]
+    pub struct MinPaymentChanged {
+        min_payment: U256,
+    }
+
+    #[ink(event)]
+    pub struct AcceptanceWindowChanged {
+        accepting_from: Option<u64>,
+        accepting_until: Option<u64>,
+    }
+
+    #[ink(event)]
+    pub struct MaxHistoryChanged {
+        max_history: u64,
+    }
+
+    #[ink(event)]
+    pub struct Pledged {
+        #[ink(topic)]
+        payer: H160,
+        amount: U256,
+    }
+
+    #[ink(event)]
+    pub struct CampaignFinalized {
+        raised: U256,
+        goal_met: bool,
+    }
+
+    #[ink(event)]
+    pub struct PledgeRefunded {
+        #[ink(topic)]
+        payer: H160,
+        amount: U256,
+    }
+
+    #[ink(event)]
+    pub struct EscrowToggled {
+        enabled: bool,
+    }
+
+    #[ink(event)]
+    pub struct PaymentHeld {
+        #[ink(topic)]
+        id: u64,
+        #[ink(topic)]
+        payer: H160,
+        amount: U256,
+        memo: Option<Vec<u8>>,
+    }
+
+    #[ink(event)]
+    pub struct PaymentReleased {
+        #[ink(topic)]
+        id: u64,
+        amount: U256,
+        #[ink(topic)]
+        released_by: H160,
+    }
+
+    #[ink(event)]
+    pub struct PaymentRefunded {
+        #[ink(topic)]
+        id: u64,
+        #[ink(topic)]
+        payer: H160,
+        amount: U256,
+    }
+
+    #[ink(event)]
+    pub struct StrictAllocationToggled {
+        enabled: bool,
+    }
+
+    #[ink(event)]
+    pub struct SharesFinalized {
+        #[ink(topic)]
+        finalized_by: H160,
+    }
+
+    #[ink(event)]
+    pub struct PayerWhitelistToggled {
+        enabled: bool,
+    }
+
+    #[ink(event)]
+    pub struct PayerAllowed {
+        #[ink(topic)]
+        payer: H160,
+        #[ink(topic)]
+        added_by: H160,
+    }
+
+    #[ink(event)]
+    pub struct TokenAccepted {
+        #[ink(topic)]
+        token: H160,
+    }
+
+    #[ink(event)]
+    pub struct TokenDelisted {
+        #[ink(topic)]
+        token: H160,
+    }
+
+    #[ink(event)]
+    pub struct PayerDisallowed {
+        #[ink(topic)]
+        payer: H160,
+        #[ink(topic)]
+        removed_by: H160,
+    }
+
+    #[ink(event)]
+    pub struct FundsRescued {
+        #[ink(topic)]
+        to: H160,
+        amount: U256,
+    }
+
+    #[ink(event)]
+    pub struct KeeperFeeChanged {
+        keeper_fee_bps: u16,
+    }
+
+    #[ink(event)]
+    pub struct ProtocolFeeChanged {
+        fee_bps: u16,
+    }
+
+    #[ink(event)]
+    pub struct FeeRecipientChanged {
+        #[ink(topic)]
+        fee_recipient: H160,
+    }
+
+    #[ink(event)]
+    pub struct FeesWithdrawn {
+        #[ink(topic)]
+        fee_recipient: H160,
+        amount: U256,
+    }
+
+    #[ink(event)]
+    pub struct DistributionThresholdChanged {
+        threshold: U256,
+    }
+
+    #[ink(event)]
+    pub struct DistributionModeChanged {
+        mode: DistributionMode,
+        #[ink(topic)]
+        changed_by: H160,
+    }
+
+    #[ink(event)]
+    pub struct Payout {
+        #[ink(topic)]
+        beneficiary: H160,
+        amount: U256,
+    }
+
+    #[ink(event)]
+    pub struct ForwardAsPaymentChanged {
+        #[ink(topic)]
+        beneficiary: H160,
+        enabled: bool,
+    }
+
+    #[ink(event)]
+    pub struct NotifyOnPayoutChanged {
+        #[ink(topic)]
+        beneficiary: H160,
+        enabled: bool,
+    }
+
+    #[ink(event)]
+    pub struct CallbackFailed {
+        #[ink(topic)]
+        beneficiary: H160,
+        destination: H160,
+        amount: U256,
+    }
+
+    #[ink(event)]
+    pub struct PayoutFailed {
+        #[ink(topic)]
+        beneficiary: H160,
+        amount: U256,
+        /// `error_code` of the error the transfer failed with
+        reason_code: u8,
+    }
+
+    #[ink(event)]
+    pub struct UndistributedSwept {
+        #[ink(topic)]
+        to: H160,
+        amount: U256,
+    }
+
+    impl SplitPayment {
+        /// Constructor - creates a new split payment contract owned by the caller
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            Self::init(Self::env().caller())
+        }
+
+        /// Default state with `owner` as owner and fee recipient
+        fn init(owner: H160) -> Self {
+            Self {
+                owner,
+                name: Lazy::new(),
+                description: Lazy::new(),
+                pending_owner: None,
+                managers: Mapping::default(),
+                manager_list: Lazy::new(),
+                operators: Mapping::default(),
+                operator_list: Lazy::new(),
+                beneficiary_data: Mapping::default(),
+                beneficiary_accounts: Lazy::new(),
+                config: Config {
+                    min_payment: U256::zero(),
+                    fee_bps: 0,
+                    fee_recipient: owner,
+                    keeper_fee_bps: 0,
+                    distribution_mode: DistributionMode::Immediate,
+                    max_beneficiaries: DEFAULT_MAX_BENEFICIARIES,
+                    withdrawal_cooldown_ms: 0,
+                    withdrawal_limit: None,
+                    strict_allocation: false,
+                    max_history: 0,
+                    distribution_threshold: U256::zero(),
+                },
+                balances: Mapping::default(),
+                credited: Mapping::default(),
+                tips: Mapping::default(),
+                labels: Mapping::default(),
+                parked: Mapping::default(),
+                total_parked: U256::zero(),
+                claimable: Mapping::default(),
+                total_claimable: U256::zero(),
+                failed_payouts: Mapping::default(),
+                failed_payout_accounts: Lazy::new(),
+                total_failed_payouts: U256::zero(),
+                removal_delay_ms: 0,
+                pending_removals: Mapping::default(),
+                pending_removal_accounts: Lazy::new(),
+                require_acceptance: false,
+                invitations: Mapping::default(),
+                invitation_accounts: Lazy::new(),
+                total_shares: 0,
+                share_model: ShareModel::Percentage,
+                total_weight: 0,
+                approvals: Mapping::default(),
+                spenders_of: Mapping::default(),
+                allowances: Mapping::default(),
+                approval_nonces: Mapping::default(),
+                withdrawal_nonces: Mapping::default(),
+                deposits_paused: false,
+                withdrawals_paused: false,
+                withdrawals_allowed_when_paused: true,
+                total_received: U256::zero(),
+                total_distributed: U256::zero(),
+                undistributed_pool: U256::zero(),
+                total_forfeited: U256::zero(),
+                pool_outflows: U256::zero(),
+                distribution_pool: U256::zero(),
+                distribution_progress: None,
+                fee_accrued: U256::zero(),
+                accepting_from: None,
+                accepting_until: None,
+                withdrawal_windows: Mapping::default(),
+                last_withdrawal_at: Mapping::default(),
+                withdrawal_counts: Mapping::default(),
+                payments: Mapping::default(),
+                payment_count: 0,
+                last_payment_at: None,
+                distributions: Mapping::default(),
+                config_version: 0,
+                config_history: Mapping::default(),
+                config_history_start: 0,
+                distribution_count: 0,
+                last_distribution_at: None,
+                largest_payment: U256::zero(),
+                payment_history_start: 0,
+                payment_keys: Mapping::default(),
+                contributions: Mapping::default(),
+                contributors: Lazy::new(),
+                contributor_count: 0,
+                payer_whitelist_enabled: false,
+                shares_locked: false,
+                retired: false,
+                reentrancy_lock: Lazy::new(),
+                allowed_payers: Mapping::default(),
+                blacklist: Mapping::default(),
+                escrow_enabled: false,
+                held_payments: Mapping::default(),
+                held_count: 0,
+                held_total: U256::zero(),
+                campaign: None,
+                pledges: Mapping::default(),
+                campaign_raised: U256::zero(),
+                campaign_finalized: false,
+                refunds_open: false,
+                token_pending: Mapping::default(),
+                token_received: Mapping::default(),
+                token_undistributed: Mapping::default(),
+                accepted_tokens: Mapping::default(),
+                accepted_token_list: Lazy::new(),
+                admin_delay_ms: 0,
+                pending_actions: Mapping::default(),
+                pending_action_ids: Lazy::new(),
+                action_count: 0,
+                council: Lazy::new(),
+                threshold: 0,
+                admin_txs: Mapping::default(),
+                confirmations: Mapping::default(),
+                admin_tx_count: 0,
+                asset_stats: Mapping::default(),
+                assets: Lazy::new(),
+                children: Lazy::new(),
+                admin_log: Mapping::default(),
+                log_head: 0,
+                log_len: 0,
+            }
+        }
+
+        /// Constructor - creates a split payment contract with a name and description
+        ///
+        /// Fails if the name is longer than `MAX_NAME_LEN` bytes or the
+        /// description longer than `MAX_DESCRIPTION_LEN` bytes.
+        #[ink(constructor)]
+        pub fn new_named(name: Vec<u8>, description: Vec<u8>) -> Result<Self> {
+            let mut contract = Self::new();
+            contract.store_metadata(name, description)?;
+            Ok(contract)
+        }
+
+        /// Constructor - creates a split payment contract with a custom beneficiary limit
+        #[ink(constructor)]
+        pub fn new_with_max_beneficiaries(max_beneficiaries: u32) -> Self {
+            let mut contract = Self::new();
+            contract.config.max_beneficiaries = max_beneficiaries;
+            contract
+        }
+
+        /// Constructor - creates a split payment contract using the given share model
+        #[ink(constructor)]
+        pub fn new_with_share_model(share_model: ShareModel) -> Self {
+            let mut contract = Self::new();
+            contract.share_model = share_model;
+            contract
+        }
+
+        /// Constructor - creates a split payment contract, optionally in strict allocation mode
+        #[ink(constructor)]
+        pub fn new_with_strict_allocation(strict_allocation: bool) -> Self {
+            let mut contract = Self::new();
+            contract.config.strict_allocation = strict_allocation;
+            contract
+        }
+
+        /// Constructor - creates a split payment contract owned by `owner` instead of the caller
+        ///
+        /// Lets factories and deployment services instantiate on someone
+        /// else's behalf; the instantiator gets no rights. `owner` also becomes
+        /// the fee recipient. Passing the caller is the same as `new`.
+        #[ink(constructor)]
+        pub fn new_with_owner(owner: H160) -> Result<Self> {
+            Self::ensure_not_zero(owner)?;
+            Ok(Self::init(owner))
+        }
+
+        /// Constructor - `new_with_owner` with an initial set of beneficiaries
+        ///
+        /// Fails under the same conditions as `new_with_owner` and `new_with_beneficiaries`.
+        #[ink(constructor)]
+        pub fn new_with_owner_and_beneficiaries(owner: H160, beneficiaries: Vec<(H160, u16)>) -> Result<Self> {
+            let mut contract = Self::new_with_owner(owner)?;
+            
+            for (account, share_bps) in beneficiaries {
+                contract.insert_beneficiary(account, share_bps)?;
+            }
+            
+            Ok(contract)
+        }
+
+        /// Constructor - creates a split payment contract with an initial set of beneficiaries
+        ///
+        /// Fails if any account is the zero address or listed twice, or if the
+        /// shares do not fit within `MAX_SHARE_BPS`.
+        #[ink(constructor)]
+        pub fn new_with_beneficiaries(beneficiaries: Vec<(H160, u16)>) -> Result<Self> {
+            let mut contract = Self::new();
+            
+            for (account, share_bps) in beneficiaries {
+                contract.insert_beneficiary(account, share_bps)?;
+            }
+            
+            Ok(contract)
+        }
+
+        /// Constructor - creates a split payment contract whose destructive admin actions are timelocked
+        ///
+        /// With a non-zero delay, removing beneficiaries, changing shares,
+        /// upgrading and transferring ownership must go through
+        /// `propose_action` and wait `admin_delay_ms` before `execute_action`.
+        #[ink(constructor)]
+        pub fn new_with_admin_delay(admin_delay_ms: u64) -> Self {
+            let mut contract = Self::new();
+            contract.admin_delay_ms = admin_delay_ms;
+            contract
+        }
+
+        /// Constructor - creates a split payment contract where beneficiaries must consent to join
+        ///
+        /// With `require_acceptance`, the `add_*` messages are disabled and
+        /// accounts join only by accepting an `invite_beneficiary` invitation.
+        #[ink(constructor)]
+        pub fn new_with_acceptance(require_acceptance: bool) -> Self {
+            let mut contract = Self::new();
+            contract.require_acceptance = require_acceptance;
+            contract
+        }
+
+        /// Constructor - creates a split payment contract whose destructive admin actions need m-of-n approval
+        ///
+        /// See `set_council`.
+        #[ink(constructor)]
+        pub fn new_with_council(members: Vec<H160>, threshold: u8) -> Result<Self> {
+            let mut contract = Self::new();
+            contract.configure_council(members, threshold)?;
+            Ok(contract)
+        }
+
+        /// Constructor - creates a split payment contract that raises funds towards a goal
+        ///
+        /// Payments are pledged rather than split until the deadline. If the
+        /// goal is met `finalize_campaign` splits them; otherwise every payer
+        /// can `refund` their pledge.
+        #[ink(constructor)]
+        pub fn new_with_campaign(goal: U256, deadline: u64) -> Self {
+            let mut contract = Self::new();
+            contract.campaign = Some(Campaign { goal, deadline });
+            contract
+        }
+
+        /// Payable function to receive funds
+        ///
+        /// Payments below `min_payment` fail with `PaymentTooSmall`. Returning
+        /// an error reverts the call, so the transferred value goes back to
+        /// the caller automatically.
+        #[ink(message)]
+        #[ink(payable)]
+        pub fn receive_payment(&mut self) -> Result<()> {
+            self.process_payment(None).map(|_| ())
+        }
+
+        /// Payable function to receive funds, returning how they were allocated
+        ///
+        /// Behaves like `receive_payment`, for calling contracts that need
+        /// the allocation without parsing events.
+        #[ink(message)]
+        #[ink(payable)]
+        pub fn receive_payment_detailed(&mut self) -> Result<DistributionSummary> {
+            self.process_payment(None)
+        }
+
+        /// Payable function to receive funds with an opaque reference attached
+        ///
+        /// Behaves like `receive_payment`; the memo (at most `MAX_MEMO_LEN`
+        /// bytes) is carried in the `FundsReceived` event, e.g. an invoice id.
+        #[ink(message)]
+        #[ink(payable)]
+        pub fn receive_payment_with_memo(&mut self, memo: Vec<u8>) -> Result<()> {
+            if memo.len() > MAX_MEMO_LEN {
+                return Err(Error::MemoTooLong);
+            }
+            
+            self.process_payment(Some(memo)).map(|_| ())
+        }
+
+        /// Payable function to receive funds at most once per `key`
+        ///
+        /// Behaves like `receive_payment` the first time a key is seen. A
+        /// repeat fails with `DuplicatePayment`, which reverts the call, so
+        /// the retried value goes back to the caller and nothing is split.
+        #[ink(message)]
+        #[ink(payable)]
+        pub fn receive_payment_idempotent(&mut self, key: [u8; 32]) -> Result<()> {
+            if self.payment_keys.contains(key) {
+                return Err(Error::DuplicatePayment);
+            }
+            
+            self.payment_keys.insert(key, &());
+            self.process_payment(None).map(|_| ())
+        }
+
+        /// Payable function crediting the whole transferred value to one beneficiary
+        ///
+        /// Bypasses the split, the protocol fee and any caps. Counts toward
+        /// `total_received` and the beneficiary's `total_tips`.
+        #[ink(message)]
+        #[ink(payable)]
+        pub fn tip(&mut self, beneficiary: H160) -> Result<()> {
+            self.ensure_not_retired()?;
+            self.ensure_deposits_open()?;
+            self.ensure_accepting()?;
+            self.ensure_not_blacklisted(self.env().caller())?;
+            
+            let amount = self.env().transferred_value();
+            if amount.is_zero() {
+                return Err(Error::ZeroAmount);
+            }
+            
+            if !self.beneficiary_data.contains(beneficiary) {
+                return Err(Error::BeneficiaryNotFound);
+            }
+            
+            let (pending, withdrawn) = self.balances.get(beneficiary).unwrap_or_default();
+            let pending = pending.checked_add(amount).ok_or(Error::ArithmeticOverflow)?;
+            self.balances.insert(beneficiary, &(pending, withdrawn));
+            
+            let tips = self.tips.get(beneficiary).unwrap_or_default();
+            self.tips.insert(beneficiary, &tips.checked_add(amount).ok_or(Error::ArithmeticOverflow)?);
+            
+            self.total_received = self.total_received
+                .checked_add(amount)
+                .ok_or(Error::ArithmeticOverflow)?;
+            self.total_distributed = self.total_distributed
+                .checked_add(amount)
+                .ok_or(Error::ArithmeticOverflow)?;
+            self.update_asset_stats(None, |stats| {
+                stats.total_received = stats.total_received
+                    .checked_add(amount)
+                    .ok_or(Error::ArithmeticOverflow)?;
+                stats.total_distributed = stats.total_distributed
+                    .checked_add(amount)
+                    .ok_or(Error::ArithmeticOverflow)?;
+                stats.total_pending = stats.total_pending
+                    .checked_add(amount)
+                    .ok_or(Error::ArithmeticOverflow)?;
+                Ok(())
+            })?;
+            
+            self.env().emit_event(TipReceived {
+                from: self.env().caller(),
+                beneficiary,
+                amount,
+            });
+            
+            Ok(())
+        }
+
+        /// Pull `amount` of a PSP22 token from the caller and split it across beneficiaries
+        ///
+        /// The caller must have approved this contract for `amount` on the
+        /// token. Tokens are split by share only: flat amounts, caps and the
+        /// protocol fee are denominated in the native currency and don't apply.
+        #[ink(message)]
+        pub fn receive_token_payment(&mut self, token: H160, amount: U256) -> Result<()> {
+            self.non_reentrant(|this| {
+                this.ensure_not_retired()?;
+                this.ensure_deposits_open()?;
+                this.ensure_accepting()?;
+                Self::ensure_not_zero(token)?;
+                
+                if !this.is_accepted_token(token) {
+                    return Err(Error::TokenNotAccepted);
+                }
+                
+                if amount.is_zero() {
+                    return Err(Error::ZeroAmount);
+                }
+                
+                let caller = this.env().caller();
+                this.ensure_not_blacklisted(caller)?;
+                this.psp22_transfer_from(token, caller, this.env().address(), amount)?;
+                
+                let received = this.token_received.get(token).unwrap_or_default();
+                this.token_received.insert(token, &received.checked_add(amount).ok_or(Error::ArithmeticOverflow)?);
+                
+                let beneficiaries = this.load_beneficiaries();
+                let units = beneficiaries
+                    .iter()
+                    .map(|b| this.share_units(b))
+                    .collect::<Vec<_>>();
+                let shares = Self::split_by_units(&units, this.share_denominator(), amount);
+                let mut credited = U256::zero();
+                
+                for (beneficiary, share_amount) in beneficiaries.iter().zip(shares) {
+                    if share_amount.is_zero() {
+                        continue;
+                    }
+                    let key = (token, beneficiary.account);
+                    let pending = this.token_pending.get(key).unwrap_or_default();
+                    this.token_pending.insert(key, &pending.checked_add(share_amount).ok_or(Error::ArithmeticOverflow)?);
+                    credited = credited.checked_add(share_amount).ok_or(Error::ArithmeticOverflow)?;
+                }
+                
+                this.update_asset_stats(Some(token), |stats| {
+                    stats.total_received = stats.total_received
+                        .checked_add(amount)
+                        .ok_or(Error::ArithmeticOverflow)?;
+                    stats.total_distributed = stats.total_distributed
+                        .checked_add(credited)
+                        .ok_or(Error::ArithmeticOverflow)?;
+                    stats.total_pending = stats.total_pending
+                        .checked_add(credited)
+                        .ok_or(Error::ArithmeticOverflow)?;
+                    Ok(())
+                })?;
+                
+                let leftover = amount.checked_sub(credited).ok_or(Error::ArithmeticOverflow)?;
+                let undistributed = this.token_undistributed.get(token).unwrap_or_default();
+                this.token_undistributed.insert(
+                    token,
+                    &undistributed.checked_add(leftover).ok_or(Error::ArithmeticOverflow)?,
+                );
+                
+                this.env().emit_event(TokenPaymentReceived {
+                    token,
+                    from: caller,
+                    amount,
+                });
+                
+                Ok(())
+            })
+        }
+
+        /// Treat balance that arrived without `receive_payment` as a payment
+        ///
+        /// Anyone may call this. The surplus over pending balances and pools is
+        /// counted as received and split (or pooled) like a normal payment, with
+        /// the contract's own address as the payer. Returns the amount synced.
+        #[ink(message)]
+        pub fn sync_balance(&mut self) -> Result<U256> {
+            self.ensure_not_retired()?;
+            self.ensure_deposits_open()?;
+            
+            let amount = self.untracked_balance();
+            if amount.is_zero() {
+                return Err(Error::NoFundsAvailable);
+            }
+            
+            let summary = self.accept_funds(amount)?;
+            
+            self.env().emit_event(FundsReceived {
+                from: self.env().address(),
+                amount,
+                fee: summary.fee,
+                net_amount: amount.saturating_sub(summary.fee),
+                memo: None,
+            });
+            
+            Ok(amount)
+        }
+
+        /// Split the accumulated distribution pool across the current beneficiaries
+        ///
+        /// Anyone may call this. Callers other than the owner and managers are
+        /// paid `keeper_fee_bps` of the pool, and the rest is split. Returns
+        /// the amount that was distributed, fee included.
+        #[ink(message)]
+        pub fn distribute(&mut self) -> Result<U256> {
+            self.non_reentrant(|this| {
+                this.ensure_deposits_open()?;
+                
+                let amount = this.distribution_pool;
+                if amount.is_zero() {
+                    return Err(Error::NoFundsAvailable);
+                }
+                
+                this.distribution_pool = U256::zero();
+                
+                let caller = this.env().caller();
+                let keeper_fee = if caller == this.owner || this.is_manager(caller) || this.is_operator(caller) {
+                    U256::zero()
+                } else {
+                    amount
+                        .saturating_mul(U256::from(this.config.keeper_fee_bps))
+                        / U256::from(MAX_SHARE_BPS)
+                };
+                
+                if !keeper_fee.is_zero() {
+                    this.pool_outflows = this.pool_outflows
+                        .checked_add(keeper_fee)
+                        .ok_or(Error::ArithmeticOverflow)?;
+                    this.env().transfer(caller, keeper_fee)
+                        .map_err(|_| Error::TransferFailed)?;
+                }
+                
+                this.distribute_funds(amount.saturating_sub(keeper_fee), keeper_fee, U256::zero())?;
+                
+                Ok(amount)
+            })
+        }
+
+        /// Take the distribution pool for a split spread over several calls (only owner, distribution managers or operators)
+        ///
+        /// For beneficiary lists too long to credit in one block. The pool
+        /// amount and share denominator are fixed here, and the beneficiary
+        /// list cannot change until `continue_distribution` has reached its
+        /// end. Only purely proportional lists can be split this way; flat
+        /// amounts and caps need `distribute`. Returns the amount taken.
+        #[ink(message)]
+        pub fn start_distribution(&mut self) -> Result<U256> {
+            self.ensure_deposits_open()?;
+            self.ensure_can_trigger()?;
+            self.ensure_not_distributing()?;
+            
+            let amount = self.distribution_pool;
+            if amount.is_zero() {
+                return Err(Error::NoFundsAvailable);
+            }
+            
+            // A read per beneficiary, far cheaper than crediting them
+            let unsupported = self.beneficiary_accounts
+                .get_or_default()
+                .into_iter()
+                .filter_map(|account| self.beneficiary_data.get(account))
+                .any(|b| {
+                    b.kind != ShareKind::Proportional
+                        || b.cap_per_distribution.is_some()
+                        || b.lifetime_cap.is_some()
+                });
+            if unsupported {
+                return Err(Error::ChunkingUnsupported);
+            }
+            
+            self.distribution_pool = U256::zero();
+            
+            let distribution_id = self.distribution_count;
+            self.distribution_count = distribution_id.saturating_add(1);
+            let beneficiary_count = self.beneficiary_count();
+            self.distribution_progress = Some(DistributionProgress {
+                distribution_id,
+                amount,
+                denominator: self.share_denominator(),
+                cursor: 0,
+                units_before: 0,
+                beneficiary_count,
+                credited: U256::zero(),
+            });
+            
+            self.env().emit_event(DistributionStarted {
+                distribution_id,
+                amount,
+                beneficiary_count,
+            });
+            
+            Ok(amount)
+        }
+
+        /// Credit up to `max_steps` more beneficiaries of the distribution under way
+        ///
+        /// Anyone may call this. Each beneficiary is credited the difference
+        /// between the floors of the running share totals before and after
+        /// them, so the split as a whole credits exactly what `distribute`
+        /// would; a single beneficiary may get one unit more or less than
+        /// under largest-remainder rounding. Once the list is done
+        /// `FundsDistributed` is emitted and anything not credited (unassigned
+        /// shares) goes to the undistributed pool. Returns how many
+        /// beneficiaries are still to be credited.
+        #[ink(message)]
+        pub fn continue_distribution(&mut self, max_steps: u32) -> Result<u32> {
+            self.ensure_deposits_open()?;
+            
+            let mut progress = self.distribution_progress
+                .clone()
+                .ok_or(Error::NoFundsAvailable)?;
+            
+            let accounts = self.beneficiary_accounts.get_or_default();
+            let end = progress.cursor.saturating_add(max_steps).min(progress.beneficiary_count);
+            let steps = end.saturating_sub(progress.cursor) as usize;
+            let mut credited = U256::zero();
+            
+            for account in accounts.iter().skip(progress.cursor as usize).take(steps) {
+                let beneficiary = self.load_beneficiary(*account)
+                    .ok_or(Error::BeneficiaryNotFound)?;
+                let share_amount = self.chunk_share(&mut progress, &beneficiary);
+                
+                self.credit_share(progress.distribution_id, &beneficiary, share_amount)?;
+                credited = credited.checked_add(share_amount).ok_or(Error::ArithmeticOverflow)?;
+            }
+            
+            progress.cursor = end;
+            progress.credited = progress.credited
+                .checked_add(credited)
+                .ok_or(Error::ArithmeticOverflow)?;
+            self.total_distributed = self.total_distributed
+                .checked_add(credited)
+                .ok_or(Error::ArithmeticOverflow)?;
+            self.update_asset_stats(None, |stats| {
+                stats.total_distributed = stats.total_distributed
+                    .checked_add(credited)
+                    .ok_or(Error::ArithmeticOverflow)?;
+                stats.total_pending = stats.total_pending
+                    .checked_add(credited)
+                    .ok_or(Error::ArithmeticOverflow)?;
+                Ok(())
+            })?;
+            
+            let remaining = progress.beneficiary_count.saturating_sub(end);
+            if remaining > 0 {
+                self.distribution_progress = Some(progress);
+                return Ok(remaining);
+            }
+            
+            self.distribution_progress = None;
+            self.undistributed_pool = progress.amount
+                .checked_sub(progress.credited)
+                .and_then(|leftover| self.undistributed_pool.checked_add(leftover))
+                .ok_or(Error::ArithmeticOverflow)?;
+            self.last_distribution_at = Some(self.env().block_timestamp());
+            self.distributions.insert(progress.distribution_id, &DistributionRecord {
+                amount: progress.amount,
+                timestamp: self.env().block_timestamp(),
+                beneficiary_count: progress.beneficiary_count,
+                total_shares_at_time: self.total_shares,
+                config_version: self.config_version,
+            });
+            
+            self.env().emit_event(FundsDistributed {
+                distribution_id: progress.distribution_id,
+                config_version: self.config_version,
+                total_amount: progress.credited,
+                beneficiary_count: progress.beneficiary_count,
+                keeper_fee: U256::zero(),
+                protocol_fee: U256::zero(),
+            });
+            
+            Ok(0)
+        }
+
+        /// Close the campaign once its deadline has passed
+        ///
+        /// Anyone may call this. If the goal was met all pledges are accepted
+        /// and split (or pooled) like a single payment; otherwise refunds open.
+        #[ink(message)]
+        pub fn finalize_campaign(&mut self) -> Result<()> {
+            let campaign = self.campaign.ok_or(Error::CampaignNotActive)?;
+            if self.campaign_finalized {
+                return Err(Error::CampaignNotActive);
+            }
+            
+            if self.env().block_timestamp() < campaign.deadline {
+                return Err(Error::CampaignNotEnded);
+            }
+            
+            self.campaign_finalized = true;
+            
+            let raised = self.campaign_raised;
+            let goal_met = raised >= campaign.goal;
+            if goal_met {
+                self.campaign_raised = U256::zero();
+                if !raised.is_zero() {
+                    self.accept_funds(raised)?;
+                }
+            } else {
+                self.refunds_open = true;
+            }
+            
+            self.env().emit_event(CampaignFinalized {
+                raised,
+                goal_met,
+            });
+            
+            Ok(())
+        }
+
+        /// Reclaim the caller's pledge after the campaign missed its goal
+        #[ink(message)]
+        pub fn refund(&mut self) -> Result<U256> {
+            self.non_reentrant(|this| {
+                if !this.refunds_open {
+                    return Err(Error::RefundsNotOpen);
+                }
+                
+                let caller = this.env().caller();
+                let amount = this.pledges
+                    .take(caller)
+                    .ok_or(Error::NoFundsAvailable)?;
+                this.campaign_raised = this.campaign_raised.saturating_sub(amount);
+                
+                this.env().transfer(caller, amount)
+                    .map_err(|_| Error::TransferFailed)?;
+                
+                this.env().emit_event(PledgeRefunded {
+                    payer: caller,
+                    amount,
+                });
+                
+                Ok(amount)
+            })
+        }
+
+        /// Accept a held payment and split it by the current shares (only owner or managers)
+        ///
+        /// From here on it is treated like a payment received now: the
+        /// protocol fee applies and it is recorded in the payment history.
+        #[ink(message)]
+        pub fn release_payment(&mut self, id: u64) -> Result<()> {
+            self.ensure_deposits_open()?;
+            self.ensure_manager_or_owner()?;
+            
+            let held = self.held_payments
+                .take(id)
+                .ok_or(Error::HeldPaymentNotFound)?;
+            self.held_total = self.held_total.saturating_sub(held.amount);
+            
+            self.accept_funds(held.amount)?;
+            self.record_payment(held.payer, held.amount, None);
+            self.record_contribution(held.payer, held.amount)?;
+            
+            self.env().emit_event(PaymentReleased {
+                id,
+                amount: held.amount,
+                released_by: self.env().caller(),
+            });
+            
+            Ok(())
+        }
+
+        /// Send a held payment back to its payer (only owner or managers)
+        ///
+        /// If the transfer fails the call errors and the payment stays held.
+        #[ink(message)]
+        pub fn refund_payment(&mut self, id: u64) -> Result<()> {
+            self.non_reentrant(|this| {
+                this.ensure_manager_or_owner()?;
+                
+                let held = this.held_payments
+                    .get(id)
+                    .ok_or(Error::HeldPaymentNotFound)?;
+                
+                this.env().transfer(held.payer, held.amount)
+                    .map_err(|_| Error::TransferFailed)?;
+                
+                this.held_payments.remove(id);
+                this.held_total = this.held_total.saturating_sub(held.amount);
+                
+                this.env().emit_event(PaymentRefunded {
+                    id,
+                    payer: held.payer,
+                    amount: held.amount,
+                });
+                
+                Ok(())
+            })
+        }
+
+        /// Add a new beneficiary (only owner or managers allowed to add)
+        #[ink(message)]
+        pub fn add_beneficiary(&mut self, account: H160, share_bps: u16) -> Result<()> {
+            self.ensure_not_paused()?;
+            self.ensure_direct_add_allowed()?;
+            self.ensure_shares_unlocked()?;
+            self.ensure_permission(Permission::AddBeneficiary)?;
+            self.insert_beneficiary(account, share_bps)
+        }
+
+        /// Add a new beneficiary with a display label (only owner or managers allowed to add)
+        #[ink(message)]
+        pub fn add_beneficiary_with_label(&mut self, account: H160, share_bps: u16, label: Vec<u8>) -> Result<()> {
+            Self::ensure_valid_label(&label)?;
+            self.add_beneficiary(account, share_bps)?;
+            self.store_label(account, Some(label))
+        }
+
+        /// Set or clear a beneficiary's display label (beneficiary, owner or managers allowed to add)
+        ///
+        /// Labels are UTF-8 and at most `MAX_LABEL_LEN` bytes.
+        #[ink(message)]
+        pub fn set_beneficiary_label(&mut self, account: H160, label: Option<Vec<u8>>) -> Result<()> {
+            if self.env().caller() != account {
+                self.ensure_permission(Permission::AddBeneficiary)?;
+            }
+            if !self.beneficiary_data.contains(account) {
+                return Err(Error::BeneficiaryNotFound);
+            }
+            
+            self.store_label(account, label)
+        }
+
+        /// Add a beneficiary by weight under `ShareModel::Weighted` (only owner or managers allowed to add)
+        #[ink(message)]
+        pub fn add_weighted_beneficiary(&mut self, account: H160, weight: u64) -> Result<()> {
+            self.ensure_not_paused()?;
+            self.ensure_direct_add_allowed()?;
+            self.ensure_shares_unlocked()?;
+            self.ensure_permission(Permission::AddBeneficiary)?;
+            
+            if self.share_model != ShareModel::Weighted {
+                return Err(Error::WrongShareModel);
+            }
+            
+            Self::ensure_not_zero(account)?;
+            self.ensure_not_blacklisted(account)?;
+            
+            if weight == 0 {
+                return Err(Error::InvalidWeight);
+            }
+            
+            if self.beneficiary_data.contains(account) {
+                return Err(Error::BeneficiaryAlreadyExists);
+            }
+            
+            if self.beneficiary_count() >= self.config.max_beneficiaries {
+                return Err(Error::TooManyBeneficiaries);
+            }
+            
+            self.store_new_beneficiary(account, 0, weight, ShareKind::Proportional);
+            self.record_config();
+            
+            Ok(())
+        }
+
+        /// Add a beneficiary credited a flat `amount` out of every distribution (only owner or managers allowed to add)
+        ///
+        /// Flat amounts are credited before the proportional split, in list
+        /// order; if a distribution is smaller than their sum, the later ones
+        /// get what is left. Works under either share model.
+        #[ink(message)]
+        pub fn add_fixed_beneficiary(&mut self, account: H160, amount: U256) -> Result<()> {
+            self.ensure_not_paused()?;
+            self.ensure_direct_add_allowed()?;
+            self.ensure_shares_unlocked()?;
+            self.ensure_permission(Permission::AddBeneficiary)?;
+            
+            Self::ensure_not_zero(account)?;
+            self.ensure_not_blacklisted(account)?;
+            
+            if amount.is_zero() {
+                return Err(Error::ZeroAmount);
+            }
+            
+            if self.beneficiary_data.contains(account) {
+                return Err(Error::BeneficiaryAlreadyExists);
+            }
+            
+            if self.beneficiary_count() >= self.config.max_beneficiaries {
+                return Err(Error::TooManyBeneficiaries);
+            }
+            
+            self.store_new_beneficiary(account, 0, 0, ShareKind::Fixed(amount));
+            self.record_config();
+            
+            Ok(())
+        }
+
+        /// Add a beneficiary whose earnings unlock on a vesting schedule (only owner)
+        #[ink(message)]
+        pub fn add_beneficiary_with_vesting(
+            &mut self,
+            account: H160,
+            share_bps: u16,
+            schedule: VestingSchedule,
+        ) -> Result<()> {
+            self.ensure_not_paused()?;
+            self.ensure_direct_add_allowed()?;
+            self.ensure_shares_unlocked()?;
+            self.ensure_owner()?;
+            self.insert_beneficiary(account, share_bps)?;
+            self.set_vesting(account, Some(schedule))
+        }
+
+        /// Set or clear a beneficiary's vesting schedule (only owner)
+        ///
+        /// The schedule applies to everything the beneficiary has ever been
+        /// credited, including balances accrued before it was set.
+        #[ink(message)]
+        pub fn set_vesting(&mut self, account: H160, schedule: Option<VestingSchedule>) -> Result<()> {
+            self.ensure_owner()?;
+            
+            let mut beneficiary = self.beneficiary_data
+                .get(account)
+                .ok_or(Error::BeneficiaryNotFound)?;
+            
+            beneficiary.vesting = schedule;
+            self.beneficiary_data.insert(account, &beneficiary);
+            
+            self.env().emit_event(VestingScheduleSet {
+                beneficiary: account,
+                schedule,
+            });
+            
+            Ok(())
+        }
+
+        /// Add a beneficiary who stops receiving once `lifetime_cap` has been credited (only owner)
+        #[ink(message)]
+        pub fn add_beneficiary_with_cap(&mut self, account: H160, share_bps: u16, lifetime_cap: U256) -> Result<()> {
+            self.ensure_not_paused()?;
+            self.ensure_direct_add_allowed()?;
+            self.ensure_shares_unlocked()?;
+            self.ensure_owner()?;
+            self.insert_beneficiary(account, share_bps)?;
+            self.set_lifetime_cap(account, Some(lifetime_cap))
+        }
+
+        /// Set or clear the total a beneficiary can ever be credited (only owner)
+        ///
+        /// Once reached, the beneficiary's part of each distribution goes to
+        /// the proportional beneficiaries still below their cap, or to the
+        /// undistributed pool if there are none. Amounts credited before the
+        /// cap was set count toward it.
+        #[ink(message)]
+        pub fn set_lifetime_cap(&mut self, account: H160, lifetime_cap: Option<U256>) -> Result<()> {
+            self.ensure_owner()?;
+            self.ensure_not_distributing()?;
+            
+            if lifetime_cap.is_some_and(|cap| cap.is_zero()) {
+                return Err(Error::ZeroAmount);
+            }
+            
+            let mut beneficiary = self.beneficiary_data
+                .get(account)
+                .ok_or(Error::BeneficiaryNotFound)?;
+            
+            beneficiary.lifetime_cap = lifetime_cap;
+            self.beneficiary_data.insert(account, &beneficiary);
+            
+            self.env().emit_event(LifetimeCapSet {
+                beneficiary: account,
+                lifetime_cap,
+            });
+            
+            Ok(())
+        }
+
+        /// Put a beneficiary in a capped payout tier, or back in the proportional split (only owner or managers)
+        ///
+        /// Each distribution first pays capped beneficiaries up to their cap,
+        /// in ascending `priority` (list order breaks ties). Whatever is left
+        /// is split among the uncapped beneficiaries by share, with the capped
+        /// beneficiaries' shares spread over them pro rata. `priority` has no
+        /// effect on uncapped beneficiaries, and caps are ignored for
+        /// `ShareKind::Fixed` beneficiaries.
+        #[ink(message)]
+        pub fn set_tier(
+            &mut self,
+            account: H160,
+            priority: u8,
+            cap_per_distribution: Option<U256>,
+        ) -> Result<()> {
+            self.ensure_not_paused()?;
+            self.ensure_shares_unlocked()?;
+            self.ensure_manager_or_owner()?;
+            
+            if cap_per_distribution.is_some_and(|cap| cap.is_zero()) {
+                return Err(Error::ZeroAmount);
+            }
+            
+            let mut beneficiary = self.beneficiary_data
+                .get(account)
+                .ok_or(Error::BeneficiaryNotFound)?;
+            
+            if cap_per_distribution.is_some() {
+                let other_uncapped = self.beneficiary_accounts
+                    .get_or_default()
+                    .iter()
+                    .filter(|other| **other != account)
+                    .filter_map(|other| self.beneficiary_data.get(other))
+                    .any(|other| other.cap_per_distribution.is_none() && other.kind == ShareKind::Proportional);
+                if !other_uncapped {
+                    return Err(Error::NoUncappedBeneficiary);
+                }
+            }
+            
+            beneficiary.priority = priority;
+            beneficiary.cap_per_distribution = cap_per_distribution;
+            self.beneficiary_data.insert(account, &beneficiary);
+            
+            self.env().emit_event(TierSet {
+                beneficiary: account,
+                priority,
+                cap_per_distribution,
+            });
+            
+            Ok(())
+        }
+
+        /// Lock a beneficiary's funds until a timestamp, or clear the lock (only owner or managers)
+        #[ink(message)]
+        pub fn set_lock(&mut self, account: H160, until: Option<u64>) -> Result<()> {
+            self.ensure_manager_or_owner()?;
+            
+            let mut beneficiary = self.beneficiary_data
+                .get(account)
+                .ok_or(Error::BeneficiaryNotFound)?;
+            
+            beneficiary.locked_until = until;
+            self.beneficiary_data.insert(account, &beneficiary);
+            
+            self.env().emit_event(LockSet {
+                beneficiary: account,
+                locked_until: until,
+            });
+            
+            Ok(())
+        }
+
+        /// Offer an account a share; it only counts once accepted (only owner or managers allowed to add)
+        ///
+        /// The share is checked against the unallocated basis points again on
+        /// acceptance, since other beneficiaries may have been added meanwhile.
+        #[ink(message)]
+        pub fn invite_beneficiary(&mut self, account: H160, share_bps: u16) -> Result<()> {
+            self.ensure_not_paused()?;
+            self.ensure_shares_unlocked()?;
+            self.ensure_permission(Permission::AddBeneficiary)?;
+            
+            if self.share_model != ShareModel::Percentage {
+                return Err(Error::WrongShareModel);
+            }
+            
+            Self::ensure_not_zero(account)?;
+            self.ensure_not_blacklisted(account)?;
+            
+            if share_bps == 0 || share_bps > MAX_SHARE_BPS {
+                return Err(Error::InvalidShare {
+                    requested: share_bps,
+                    available: MAX_SHARE_BPS.saturating_sub(self.total_shares),
+                });
+            }
+            
+            if self.beneficiary_data.contains(account) {
+                return Err(Error::BeneficiaryAlreadyExists);
+            }
+            if self.invitations.contains(account) {
+                return Err(Error::AlreadyInvited);
+            }
+            
+            self.invitations.insert(account, &share_bps);
+            let mut invitation_accounts = self.invitation_accounts.get_or_default();
+            invitation_accounts.push(account);
+            self.invitation_accounts.set(&invitation_accounts);
+            
+            self.env().emit_event(BeneficiaryInvited {
+                account,
+                share_bps,
+                invited_by: self.env().caller(),
+            });
+            
+            Ok(())
+        }
+
+        /// Join as a beneficiary with the share offered to the caller
+        #[ink(message)]
+        pub fn accept_invitation(&mut self) -> Result<()> {
+            self.ensure_not_paused()?;
+            self.ensure_shares_unlocked()?;
+            let caller = self.env().caller();
+            
+            let share_bps = self.take_invitation(caller)?;
+            self.insert_beneficiary(caller, share_bps)
+        }
+
+        /// Turn down the invitation sent to the caller
+        #[ink(message)]
+        pub fn decline_invitation(&mut self) -> Result<()> {
+            let caller = self.env().caller();
+            self.take_invitation(caller)?;
+            
+            self.env().emit_event(InvitationDeclined {
+                account: caller,
+            });
+            
+            Ok(())
+        }
+
+        /// Withdraw a pending invitation (only owner or managers allowed to add)
+        #[ink(message)]
+        pub fn cancel_invitation(&mut self, account: H160) -> Result<()> {
+            self.ensure_permission(Permission::AddBeneficiary)?;
+            self.take_invitation(account)?;
+            
+            self.env().emit_event(InvitationCancelled {
+                account,
+                cancelled_by: self.env().caller(),
+            });
+            
+            Ok(())
+        }
+
+        /// Remove a beneficiary (only owner or managers allowed to remove)
+        ///
+        /// Their pending balance is paid out to the account. With
+        /// `forfeit_unvested`, only the vested part is paid and the rest goes
+        /// to the undistributed pool; otherwise everything is paid. If the
+        /// beneficiary is locked, the payout is parked for `claim_parked`
+        /// instead of transferred.
+        #[ink(message)]
+        pub fn remove_beneficiary(&mut self, account: H160, forfeit_unvested: bool) -> Result<()> {
+            self.non_reentrant(|this| {
+                this.ensure_not_paused()?;
+                this.ensure_direct_admin_allowed()?;
+                this.ensure_shares_unlocked()?;
+                this.ensure_permission(Permission::RemoveBeneficiary)?;
+                
+                this.remove_beneficiary_now(account, forfeit_unvested)
+            })
+        }
+
+        /// Remove a beneficiary and hand their share to the others (only owner or managers allowed to remove)
+        ///
+        /// Pending balances are settled as in `remove_beneficiary`. The
+        /// remaining shares are scaled up proportionally so `total_shares`
+        /// stays where it was, with largest-remainder rounding. Under
+        /// `ShareModel::Weighted` this happens implicitly, so only the removal
+        /// is performed.
+        #[ink(message)]
+        pub fn remove_beneficiary_and_redistribute(&mut self, account: H160) -> Result<()> {
+            self.non_reentrant(|this| {
+                this.ensure_not_paused()?;
+                this.ensure_direct_admin_allowed()?;
+                this.ensure_shares_unlocked()?;
+                this.ensure_permission(Permission::RemoveBeneficiary)?;
+                
+                let previous_total = this.total_shares;
+                this.remove_beneficiary_now(account, false)?;
+                this.rescale_shares(previous_total);
+                
+                Ok(())
+            })
+        }
+
+        /// Replace the whole beneficiary list in one step (only owner)
+        ///
+        /// The new list is validated up front. Accounts kept from the old list
+        /// keep their balances and settings; dropped accounts are settled as in
+        /// `remove_beneficiary`. The list order becomes the order of `entries`.
+        #[ink(message)]
+        pub fn set_beneficiaries(&mut self, entries: Vec<(H160, u16)>) -> Result<()> {
+            self.non_reentrant(|this| {
+                this.ensure_not_paused()?;
+                this.ensure_direct_admin_allowed()?;
+                this.ensure_shares_unlocked()?;
+                this.ensure_owner()?;
+                
+                if this.share_model != ShareModel::Percentage {
+                    return Err(Error::WrongShareModel);
+                }
+                
+                if entries.len() as u32 > this.config.max_beneficiaries {
+                    return Err(Error::TooManyBeneficiaries);
+                }
+                
+                let mut new_total = 0u16;
+                for (index, (account, share_bps)) in entries.iter().enumerate() {
+                    Self::ensure_not_zero(*account)?;
+                    
+                    let available = MAX_SHARE_BPS.saturating_sub(new_total);
+                    if *share_bps == 0 || *share_bps > available {
+                        return Err(Error::InvalidShare {
+                            requested: *share_bps,
+                            available,
+                        });
+                    }
+                    
+                    if entries[..index].iter().any(|(other, _)| other == account) {
+                        return Err(Error::BeneficiaryAlreadyExists);
+                    }
+                    
+                    if this.require_acceptance && !this.beneficiary_data.contains(*account) {
+                        return Err(Error::AcceptanceRequired);
+                    }
+                    
+                    new_total = new_total.saturating_add(*share_bps);
+                }
+                
+                for account in this.beneficiary_accounts.get_or_default() {
+                    if !entries.iter().any(|(kept, _)| *kept == account) {
+                        this.remove_beneficiary_now(account, false)?;
+                    }
+                }
+                
+                this.beneficiary_accounts.set(&Vec::new());
+                this.total_shares = 0;
+                
+                for (account, share_bps) in entries {
+                    let Some(mut beneficiary) = this.beneficiary_data.get(account) else {
+                        this.store_new_beneficiary(account, share_bps, 0, ShareKind::Proportional);
+                        continue;
+                    };
+                    
+                    let old_share_bps = beneficiary.share_bps;
+                    if old_share_bps != share_bps {
+                        beneficiary.share_bps = share_bps;
+                        this.beneficiary_data.insert(account, &beneficiary);
+                        
+                        this.env().emit_event(BeneficiaryShareUpdated {
+                            beneficiary: account,
+                            old_share_bps,
+                            new_share_bps: share_bps,
+                        });
+                    }
+                    
+                    let mut beneficiary_accounts = this.beneficiary_accounts.get_or_default();
+                    beneficiary_accounts.push(account);
+                    this.beneficiary_accounts.set(&beneficiary_accounts);
+                    this.total_shares = this.total_shares.saturating_add(share_bps);
+                }
+                
+                this.record_config();
+                
+                this.env().emit_event(BeneficiariesReset {
+                    beneficiary_count: this.beneficiary_count(),
+                    total_shares: this.total_shares,
+                    reset_by: this.env().caller(),
+                });
+                
+                Ok(())
+            })
+        }
+
+        /// Leave the split, taking the unlocked pending balance (beneficiary)
+        ///
+        /// Unvested funds are forfeited to the undistributed pool and locked
+        /// funds are parked, exactly as for an owner removal.
+        #[ink(message)]
+        pub fn exit(&mut self) -> Result<()> {
+            self.non_reentrant(|this| {
+                this.ensure_not_paused()?;
+                this.ensure_shares_unlocked()?;
+                let caller = this.env().caller();
+                
+                let beneficiary = this.beneficiary_data
+                    .get(caller)
+                    .ok_or(Error::Unauthorized)?;
+                if beneficiary.frozen {
+                    return Err(Error::BeneficiaryFrozen);
+                }
+                
+                this.remove_beneficiary_now(caller, true)
+            })
+        }
+
+        /// Schedule a beneficiary's removal after the removal delay (only owner or managers allowed to remove)
+        ///
+        /// Until `finalize_removal` runs the beneficiary keeps accruing and
+        /// can withdraw as usual.
+        #[ink(message)]
+        pub fn request_remove_beneficiary(&mut self, account: H160, forfeit_unvested: bool) -> Result<()> {
+            self.ensure_not_paused()?;
+            self.ensure_shares_unlocked()?;
+            self.ensure_permission(Permission::RemoveBeneficiary)?;
+            
+            if !self.beneficiary_data.contains(account) {
+                return Err(Error::BeneficiaryNotFound);
+            }
+            if self.pending_removals.contains(account) {
+                return Err(Error::RemovalAlreadyRequested);
+            }
+            
+            let effective_at = self.env().block_timestamp().saturating_add(self.removal_delay_ms);
+            self.pending_removals.insert(account, &(effective_at, forfeit_unvested));
+            let mut pending_removal_accounts = self.pending_removal_accounts.get_or_default();
+            pending_removal_accounts.push(account);
+            self.pending_removal_accounts.set(&pending_removal_accounts);
+            
+            self.env().emit_event(RemovalRequested {
+                beneficiary: account,
+                effective_at,
+                requested_by: self.env().caller(),
+            });
+            
+            Ok(())
+        }
+
+        /// Carry out a requested removal once its delay has passed
+        ///
+        /// Anyone may call this.
+        #[ink(message)]
+        pub fn finalize_removal(&mut self, account: H160) -> Result<()> {
+            self.non_reentrant(|this| {
+                this.ensure_not_paused()?;
+                this.ensure_shares_unlocked()?;
+                
+                let (effective_at, forfeit_unvested) = this.pending_removals
+                    .get(account)
+                    .ok_or(Error::RemovalNotRequested)?;
+                if this.env().block_timestamp() < effective_at {
+                    return Err(Error::RemovalNotDue { effective_at });
+                }
+                
+                this.remove_beneficiary_now(account, forfeit_unvested)
+            })
+        }
+
+        /// Cancel a requested removal (only owner)
+        #[ink(message)]
+        pub fn cancel_removal(&mut self, account: H160) -> Result<()> {
+            self.ensure_owner()?;
+            
+            if !self.clear_pending_removal(account) {
+                return Err(Error::RemovalNotRequested);
+            }
+            
+            self.env().emit_event(RemovalCancelled {
+                beneficiary: account,
+                cancelled_by: self.env().caller(),
+            });
+            
+            Ok(())
+        }
+
+        /// Set how long a requested removal waits before it can be finalized (only owner)
+        #[ink(message)]
+        pub fn set_removal_delay(&mut self, delay_ms: u64) -> Result<()> {
+            self.ensure_owner()?;
+            self.removal_delay_ms = delay_ms;
+            
+            self.env().emit_event(RemovalDelayChanged {
+                delay_ms,
+            });
+            
+            Ok(())
+        }
+
+        /// Collect funds parked when the caller was removed while locked
+        #[ink(message)]
+        pub fn claim_parked(&mut self) -> Result<U256> {
+            self.non_reentrant(|this| {
+                this.ensure_withdrawals_open()?;
+                let caller = this.env().caller();
+                
+                let (amount, unlocks_at) = this.parked.get(caller).unwrap_or_default();
+                if amount.is_zero() {
+                    return Err(Error::NoFundsAvailable);
+                }
+                if this.env().block_timestamp() < unlocks_at {
+                    return Err(Error::FundsLocked(unlocks_at));
+                }
+                this.ensure_solvent(amount)?;
+                
+                this.parked.remove(caller);
+                this.total_parked = this.total_parked.saturating_sub(amount);
+                
+                this.env().transfer(caller, amount)
+                    .map_err(|_| Error::TransferFailed)?;
+                
+                this.env().emit_event(ParkedFundsClaimed {
+                    account: caller,
+                    amount,
+                });
+                
+                Ok(amount)
+            })
+        }
+
+        /// Collect a removal payout whose transfer failed at the time
+        #[ink(message)]
+        pub fn claim_escrow(&mut self) -> Result<U256> {
+            self.non_reentrant(|this| {
+                this.ensure_withdrawals_open()?;
+                let caller = this.env().caller();
+                
+                let amount = this.claimable.get(caller).unwrap_or_default();
+                if amount.is_zero() {
+                    return Err(Error::NoFundsAvailable);
+                }
+                this.ensure_solvent(amount)?;
+                
+                this.claimable.remove(caller);
+                this.total_claimable = this.total_claimable.saturating_sub(amount);
+                
+                this.env().transfer(caller, amount)
+                    .map_err(|_| Error::TransferFailed)?;
+                
+                this.env().emit_event(EscrowClaimed {
+                    account: caller,
+                    amount,
+                });
+                
+                Ok(amount)
+            })
+        }
+
+        /// Try again to send a push payout whose transfer failed
+        ///
+        /// Anyone may call this. The funds go to the account's current payout
+        /// destination, or to the account itself once it is no longer a
+        /// beneficiary. Frozen beneficiaries are skipped until unfrozen.
+        /// Returns the amount sent.
+        #[ink(message)]
+        pub fn retry_failed_payout(&mut self, account: H160) -> Result<U256> {
+            self.non_reentrant(|this| {
+                this.ensure_withdrawals_open()?;
+                this.retry_payout(account)
+            })
+        }
+
+        /// Retry the first `max` failed payouts, in order of failure
+        ///
+        /// Anyone may call this. Payouts that fail again stay queued. Returns
+        /// the number of payouts sent.
+        #[ink(message)]
+        pub fn retry_all_failed(&mut self, max: u32) -> Result<u32> {
+            self.non_reentrant(|this| {
+                this.ensure_withdrawals_open()?;
+                
+                let mut paid = 0u32;
+                
+                for account in this.failed_payout_accounts.get_or_default().into_iter().take(max as usize) {
+                    if this.retry_payout(account).is_ok() {
+                        paid = paid.saturating_add(1);
+                    }
+                }
+                
+                Ok(paid)
+            })
+        }
+
+        /// Grant approval for another account to withdraw on behalf of a beneficiary
+        #[ink(message)]
+        pub fn approve(&mut self, spender: H160, amount: U256, expires_at: Option<u64>) -> Result<()> {
+            let caller = self.ensure_can_approve(spender)?;
+            
+            if amount.is_zero() {
+                return Err(Error::ZeroAmount);
+            }
+            
+            self.store_approval(caller, spender, amount, expires_at);
+            
+            Ok(())
+        }
+
+        /// Store an approval signed off-chain by the beneficiary `owner`
+        ///
+        /// Anyone may submit it. `nonce` must be `owner`'s next approval nonce,
+        /// which is then consumed.
+        ///
+        /// `signature` is a 65-byte recoverable secp256k1 ECDSA signature
+        /// (`r`, `s`, then the recovery id `v`) over the raw 32-byte
+        /// `approval_digest`, with no EIP-191 prefix. It is accepted when the
+        /// recovered key's Ethereum-style address (the last 20 bytes of the
+        /// Keccak-256 of the uncompressed public key) equals `owner`. The
+        /// digest is the Keccak-256 of the SCALE-encoded tuple
+        /// `(APPROVAL_TAG, contract_address, owner, spender, amount,
+        /// expires_at, nonce)`, so a signature is bound to this contract and
+        /// cannot be replayed for withdrawals.
+        #[ink(message)]
+        pub fn approve_with_signature(
+            &mut self,
+            owner: H160,
+            spender: H160,
+            amount: U256,
+            expires_at: Option<u64>,
+            nonce: u64,
+            signature: [u8; 65],
+        ) -> Result<()> {
+            self.ensure_approver(owner, spender)?;
+            
+            if amount.is_zero() {
+                return Err(Error::ZeroAmount);
+            }
+            
+            let expected = self.approval_nonces.get(owner).unwrap_or_default();
+            if nonce != expected {
+                return Err(Error::InvalidNonce { expected });
+            }
+            
+            let digest = self.approval_digest(owner, spender, amount, expires_at, nonce);
+            if self.recover_signer(&digest, &signature)? != owner {
+                return Err(Error::InvalidSignature);
+            }
+            
+            self.approval_nonces.insert(owner, &nonce.saturating_add(1));
+            self.store_approval(owner, spender, amount, expires_at);
+            
+            Ok(())
+        }
+
+        /// Get the hash a beneficiary signs for `approve_with_signature`
+        ///
+        /// Keccak-256 of the SCALE-encoded `(APPROVAL_TAG, contract_address,
+        /// owner, spender, amount, expires_at, nonce)`.
+        #[ink(message)]
+        pub fn approval_digest(
+            &self,
+            owner: H160,
+            spender: H160,
+            amount: U256,
+            expires_at: Option<u64>,
+            nonce: u64,
+        ) -> [u8; 32] {
+            self.env().hash_encoded::<ink::env::hash::Keccak256, _>(
+                &(APPROVAL_TAG, self.env().address(), owner, spender, amount, expires_at, nonce),
+            )
+        }
+
+        /// Get the nonce `owner` must sign next for `approve_with_signature`
+        #[ink(message)]
+        pub fn get_approval_nonce(&self, owner: H160) -> u64 {
+            self.approval_nonces.get(owner).unwrap_or_default()
+        }
+
+        /// Raise an existing allowance by `delta`, keeping its expiry (beneficiary)
+        ///
+        /// Unlike `approve`, this cannot be front-run into a double spend.
+        #[ink(message)]
+        pub fn increase_allowance(&mut self, spender: H160, delta: U256) -> Result<()> {
+            let caller = self.ensure_can_approve(spender)?;
+            
+            if delta.is_zero() {
+                return Err(Error::ZeroAmount);
+            }
+            
+            let current = self.approvals.get((caller, spender));
+            let amount = current.as_ref()
+                .map(|a| a.amount)
+                .unwrap_or_default()
+                .saturating_add(delta);
+            let expires_at = current.and_then(|a| a.expires_at);
+            
+            self.store_approval(caller, spender, amount, expires_at);
+            
+            Ok(())
+        }
+
+        /// Lower an existing allowance by `delta`, keeping its expiry (beneficiary)
+        ///
+        /// Fails with `InsufficientAllowance` rather than saturating when `delta`
+        /// exceeds the current allowance; reaching zero removes the approval.
+        #[ink(message)]
+        pub fn decrease_allowance(&mut self, spender: H160, delta: U256) -> Result<()> {
+            let caller = self.ensure_can_approve(spender)?;
+            
+            if delta.is_zero() {
+                return Err(Error::ZeroAmount);
+            }
+            
+            let current = self.approvals.get((caller, spender));
+            let available = current.as_ref().map(|a| a.amount).unwrap_or_default();
+            if available < delta {
+                return Err(Error::InsufficientAllowance {
+                    requested: delta,
+                    available,
+                });
+            }
+            let expires_at = current.and_then(|a| a.expires_at);
+            
+            self.store_approval(caller, spender, available.saturating_sub(delta), expires_at);
+            
+            Ok(())
+        }
+
+        /// Set an allowance only if it currently equals `expected_current` (beneficiary)
+        ///
+        /// A `new_amount` of zero removes the approval.
+        #[ink(message)]
+        pub fn approve_with_expected(
+            &mut self,
+            spender: H160,
+            expected_current: U256,
+            new_amount: U256,
+            expires_at: Option<u64>,
+        ) -> Result<()> {
+            let caller = self.ensure_can_approve(spender)?;
+            
+            let current = self.get_approval(caller, spender);
+            if current != expected_current {
+                return Err(Error::AllowanceMismatch {
+                    expected: expected_current,
+                    current,
+                });
+            }
+            
+            self.store_approval(caller, spender, new_amount, expires_at);
+            
+            Ok(())
+        }
+
+        /// Revoke approval for a spender
+        #[ink(message)]
+        pub fn revoke_approval(&mut self, spender: H160) -> Result<()> {
+            let caller = self.env().caller();
+            
+            self.remove_approval(caller, spender);
+            
+            self.env().emit_event(ApprovalRevoked {
+                owner: caller,
+                spender,
+            });
+            
+            Ok(())
+        }
+
+        /// Delete an owner's expired approvals, returning how many were removed
+        ///
+        /// Anyone may call this; it only ever removes approvals that can no
+        /// longer be used.
+        #[ink(message)]
+        pub fn cleanup_expired_approvals(&mut self, owner: H160) -> Result<u32> {
+            let mut removed = 0u32;
+            
+            for spender in self.spenders_of.get(owner).unwrap_or_default() {
+                let expired = self.approvals.get((owner, spender))
+                    .is_some_and(|a| self.is_expired(&a));
+                if !expired {
+                    continue;
+                }
+                
+                self.remove_approval(owner, spender);
+                removed = removed.saturating_add(1);
+                
+                self.env().emit_event(ApprovalRevoked {
+                    owner,
+                    spender,
+                });
+            }
+            
+            Ok(removed)
+        }
+
+        /// Revoke every approval the caller has granted, returning how many were removed
+        #[ink(message)]
+        pub fn revoke_all_approvals(&mut self) -> Result<u32> {
+            let caller = self.env().caller();
+            Ok(self.revoke_approvals_of(caller))
+        }
+
+        /// Delete every approval granted by a beneficiary (only owner)
+        ///
+        /// Emergency brake for a compromised beneficiary key; pair with
+        /// `freeze_beneficiary` to stop new approvals being granted.
+        #[ink(message)]
+        pub fn force_revoke_approvals(&mut self, beneficiary: H160) -> Result<u32> {
+            self.ensure_owner()?;
+            Ok(self.revoke_approvals_of(beneficiary))
+        }
+
+        /// Withdraw funds on behalf of a beneficiary (using approval)
+        ///
+        /// The approval is spent either way; with `deliver_to_beneficiary` the
+        /// funds go to the beneficiary's payout destination instead of the
+        /// caller, letting a relayer pay fees for them.
+        #[ink(message)]
+        pub fn withdraw_from(&mut self, beneficiary: H160, amount: U256, deliver_to_beneficiary: bool) -> Result<()> {
+            self.non_reentrant(|this| {
+                this.ensure_withdrawals_open()?;
+                let caller = this.env().caller();
+                this.ensure_not_blacklisted(caller)?;
+                this.ensure_not_blacklisted(beneficiary)?;
+                
+                if amount.is_zero() {
+                    return Err(Error::ZeroAmount);
+                }
+                
+                // Get and validate approval. The failed call is rolled back, so an
+                // expired record stays until `cleanup_expired_approvals` runs.
+                let approval = this.approvals.get((beneficiary, caller))
+                    .ok_or(Error::InsufficientAllowance {
+                        requested: amount,
+                        available: U256::zero(),
+                    })?;
+                
+                if this.is_expired(&approval) {
+                    return Err(Error::ApprovalExpired);
+                }
+                
+                if approval.amount < amount {
+                    return Err(Error::InsufficientAllowance {
+                        requested: amount,
+                        available: approval.amount,
+                    });
+                }
+                
+                // Find beneficiary and check their pending balance
+                let data = this.beneficiary_data
+                    .get(beneficiary)
+                    .ok_or(Error::BeneficiaryNotFound)?;
+                if data.frozen {
+                    return Err(Error::BeneficiaryFrozen);
+                }
+                
+                let available = this.withdrawable(beneficiary);
+                if available < amount {
+                    return Err(Error::InsufficientBalance {
+                        requested: amount,
+                        available,
+                    });
+                }
+                
+                this.ensure_release_allowed(beneficiary, amount)?;
+                this.ensure_solvent(amount)?;
+                
+                let (pending, withdrawn) = this.balances.get(beneficiary).unwrap_or_default();
+                let pending = pending.checked_sub(amount).ok_or(Error::ArithmeticOverflow)?;
+                let withdrawn = withdrawn.checked_add(amount).ok_or(Error::ArithmeticOverflow)?;
+                
+                // Update the beneficiary balances
+                this.balances.insert(beneficiary, &(pending, withdrawn));
+                this.record_release(beneficiary, amount)?;
+                
+                // Update approval
+                let mut updated_approval = approval;
+                updated_approval.amount = updated_approval.amount.saturating_sub(amount);
+                
+                if updated_approval.amount.is_zero() {
+                    this.remove_approval(beneficiary, caller);
+                } else {
+                    this.approvals.insert((beneficiary, caller), &updated_approval);
+                }
+                
+                let recipient = if deliver_to_beneficiary {
+                    data.payout_destination()
+                } else {
+                    caller
+                };
+                
+                this.send_funds(recipient, amount, deliver_to_beneficiary && data.forward_as_payment)?;
+                
+                this.env().emit_event(WithdrawalByApproval {
+                    beneficiary,
+                    spender: caller,
+                    amount,
+                    recipient,
+                });
+                
+                Ok(())
+            })
+        }
+
+        /// Withdraw own funds (beneficiary)
+        #[ink(message)]
+        pub fn withdraw(&mut self, amount: U256) -> Result<()> {
+            self.non_reentrant(|this| {
+                this.ensure_withdrawals_open()?;
+                this.withdraw_own(None, amount)
+            })
+        }
+
+        /// Withdraw the whole unlocked pending balance (beneficiary)
+        ///
+        /// Returns the amount withdrawn.
+        #[ink(message)]
+        pub fn withdraw_all(&mut self) -> Result<U256> {
+            self.non_reentrant(|this| {
+                this.ensure_withdrawals_open()?;
+                let caller = this.env().caller();
+                
+                if !this.beneficiary_data.contains(caller) {
+                    return Err(Error::Unauthorized);
+                }
+                
+                let amount = this.withdrawable(caller);
+                
+                if amount.is_zero() {
+                    return Err(Error::NoFundsAvailable);
+                }
+                
+                this.withdraw_own(None, amount)?;
+                
+                Ok(amount)
+            })
+        }
+
+        /// Withdraw part of the caller's pending balance in a PSP22 token
+        ///
+        /// Still works after the caller has been removed as a beneficiary.
+        #[ink(message)]
+        pub fn withdraw_token(&mut self, token: H160, amount: U256) -> Result<()> {
+            self.non_reentrant(|this| {
+                this.ensure_withdrawals_open()?;
+                
+                if amount.is_zero() {
+                    return Err(Error::ZeroAmount);
+                }
+                
+                this.release_token(token, this.env().caller(), amount)
+            })
+        }
+
+        /// Withdraw the caller's whole pending balance in a PSP22 token
+        ///
+        /// Returns the amount withdrawn.
+        #[ink(message)]
+        pub fn withdraw_all_token(&mut self, token: H160) -> Result<U256> {
+            self.non_reentrant(|this| {
+                this.ensure_withdrawals_open()?;
+                let caller = this.env().caller();
+                
+                let amount = this.token_pending.get((token, caller)).unwrap_or_default();
+                if amount.is_zero() {
+                    return Err(Error::NoFundsAvailable);
+                }
+                
+                this.release_token(token, caller, amount)?;
+                
+                Ok(amount)
+            })
+        }
+
+        /// Withdraw for a beneficiary who signed the request off-chain
+        ///
+        /// Any relayer may submit it before `deadline`, and `nonce` must be the
+        /// beneficiary's next withdrawal nonce. `amount` is debited;
+        /// `relayer_fee` of it goes to the caller and the rest to `destination`.
+        ///
+        /// `signature` is a 65-byte recoverable secp256k1 ECDSA signature
+        /// (`r`, `s`, then the recovery id `v`) over the raw 32-byte
+        /// `withdrawal_digest`, with no EIP-191 prefix. It is accepted when the
+        /// recovered key's Ethereum-style address (the last 20 bytes of the
+        /// Keccak-256 of the uncompressed public key) equals `beneficiary`.
+        /// The digest is the Keccak-256 of the SCALE-encoded tuple
+        /// `(WITHDRAWAL_TAG, contract_address, beneficiary, amount,
+        /// destination, relayer_fee, deadline, nonce)`, so a relayer cannot
+        /// change the destination or its fee.
+        #[ink(message)]
+        #[allow(clippy::too_many_arguments)]
+        pub fn withdraw_with_signature(
+            &mut self,
+            beneficiary: H160,
+            amount: U256,
+            destination: H160,
+            relayer_fee: U256,
+            deadline: u64,
+            nonce: u64,
+            signature: [u8; 65],
+        ) -> Result<()> {
+            self.non_reentrant(|this| {
+                this.ensure_withdrawals_open()?;
+                Self::ensure_not_zero(destination)?;
+                
+                if amount.is_zero() {
+                    return Err(Error::ZeroAmount);
+                }
+                if relayer_fee > amount {
+                    return Err(Error::FeeTooHigh);
+                }
+                if this.env().block_timestamp() > deadline {
+                    return Err(Error::SignatureExpired);
+                }
+                
+                let expected = this.withdrawal_nonces.get(beneficiary).unwrap_or_default();
+                if nonce != expected {
+                    return Err(Error::InvalidNonce { expected });
+                }
+                
+                let digest = this.withdrawal_digest(beneficiary, amount, destination, relayer_fee, deadline, nonce);
+                if this.recover_signer(&digest, &signature)? != beneficiary {
+                    return Err(Error::InvalidSignature);
+                }
+                
+                let data = this.beneficiary_data
+                    .get(beneficiary)
+                    .ok_or(Error::Unauthorized)?;
+                if data.frozen {
+                    return Err(Error::BeneficiaryFrozen);
+                }
+                
+                this.withdrawal_nonces.insert(beneficiary, &nonce.saturating_add(1));
+                
+                let relayer = this.env().caller();
+                this.release_funds_with_fee(beneficiary, destination, amount, relayer, relayer_fee)?;
+                
+                this.env().emit_event(WithdrawalRelayed {
+                    beneficiary,
+                    relayer,
+                    relayer_fee,
+                    nonce,
+                });
+                
+                Ok(())
+            })
+        }
+
+        /// Get the hash a beneficiary signs for `withdraw_with_signature`
+        ///
+        /// Keccak-256 of the SCALE-encoded `(WITHDRAWAL_TAG, contract_address,
+        /// beneficiary, amount, destination, relayer_fee, deadline, nonce)`.
+        #[ink(message)]
+        pub fn withdrawal_digest(
+            &self,
+            beneficiary: H160,
+            amount: U256,
+            destination: H160,
+            relayer_fee: U256,
+            deadline: u64,
+            nonce: u64,
+        ) -> [u8; 32] {
+            self.env().hash_encoded::<ink::env::hash::Keccak256, _>(
+                &(WITHDRAWAL_TAG, self.env().address(), beneficiary, amount, destination, relayer_fee, deadline, nonce),
+            )
+        }
+
+        /// Get the nonce a beneficiary must sign next for `withdraw_with_signature`
+        #[ink(message)]
+        pub fn get_withdrawal_nonce(&self, beneficiary: H160) -> u64 {
+            self.withdrawal_nonces.get(beneficiary).unwrap_or_default()
+        }
+
+        /// Withdraw own funds to a different destination address (beneficiary)
+        #[ink(message)]
+        pub fn withdraw_to(&mut self, destination: H160, amount: U256) -> Result<()> {
+            self.non_reentrant(|this| {
+                this.ensure_withdrawals_open()?;
+                Self::ensure_not_zero(destination)?;
+                
+                this.withdraw_own(Some(destination), amount)
+            })
+        }
+
+        /// Move the caller's beneficiary record to a new account (beneficiary)
+        ///
+        /// Share, balances, history, settings, a pending removal, PSP22
+        /// balances and parked or escrowed funds all move with it, and the
+        /// list position is kept. Approvals granted by the old account are
+        /// revoked rather than migrated, since spenders trusted the old key.
+        #[ink(message)]
+        pub fn rotate_account(&mut self, new_account: H160) -> Result<()> {
+            self.ensure_not_paused()?;
+            self.ensure_not_distributing()?;
+            Self::ensure_not_zero(new_account)?;
+            let caller = self.env().caller();
+            self.ensure_not_blacklisted(caller)?;
+            self.ensure_not_blacklisted(new_account)?;
+            
+            let mut beneficiary = self.beneficiary_data
+                .get(caller)
+                .ok_or(Error::Unauthorized)?;
+            if beneficiary.frozen {
+                return Err(Error::BeneficiaryFrozen);
+            }
+            if self.beneficiary_data.contains(new_account) {
+                return Err(Error::BeneficiaryAlreadyExists);
+            }
+            
+            self.revoke_approvals_of(caller);
+            
+            beneficiary.account = new_account;
+            self.beneficiary_data.remove(caller);
+            self.beneficiary_data.insert(new_account, &beneficiary);
+            let mut beneficiary_accounts = self.beneficiary_accounts.get_or_default();
+            if let Some(slot) = beneficiary_accounts.iter_mut().find(|a| **a == caller) {
+                *slot = new_account;
+            }
+            self.beneficiary_accounts.set(&beneficiary_accounts);
+            
+            if let Some(balances) = self.balances.take(caller) {
+                self.balances.insert(new_account, &balances);
+            }
+            if let Some(credited) = self.credited.take(caller) {
+                self.credited.insert(new_account, &credited);
+            }
+            if let Some(tips) = self.tips.take(caller) {
+                self.tips.insert(new_account, &tips);
+            }
+            if let Some(label) = self.labels.take(caller) {
+                self.labels.insert(new_account, &label);
+            }
+            if let Some(window) = self.withdrawal_windows.take(caller) {
+                self.withdrawal_windows.insert(new_account, &window);
+            }
+            if let Some(last) = self.last_withdrawal_at.take(caller) {
+                self.last_withdrawal_at.insert(new_account, &last);
+            }
+            if let Some(count) = self.withdrawal_counts.take(caller) {
+                self.withdrawal_counts.insert(new_account, &count);
+            }
+            if let Some(removal) = self.pending_removals.take(caller) {
+                self.pending_removals.insert(new_account, &removal);
+                let mut pending_removal_accounts = self.pending_removal_accounts.get_or_default();
+                if let Some(slot) = pending_removal_accounts.iter_mut().find(|a| **a == caller) {
+                    *slot = new_account;
+                }
+                self.pending_removal_accounts.set(&pending_removal_accounts);
+            }
+            
+            if let Some((amount, unlocks_at)) = self.parked.take(caller) {
+                let (parked, parked_until) = self.parked.get(new_account).unwrap_or_default();
+                let parked = parked.checked_add(amount).ok_or(Error::ArithmeticOverflow)?;
+                self.parked.insert(new_account, &(parked, parked_until.max(unlocks_at)));
+            }
+            if let Some(amount) = self.claimable.take(caller) {
+                let claimable = self.claimable.get(new_account).unwrap_or_default();
+                self.claimable.insert(new_account, &claimable.checked_add(amount).ok_or(Error::ArithmeticOverflow)?);
+            }
+            if let Some(amount) = self.failed_payouts.take(caller) {
+                let failed = self.failed_payouts.get(new_account).unwrap_or_default();
+                self.failed_payouts.insert(new_account, &failed.checked_add(amount).ok_or(Error::ArithmeticOverflow)?);
+                
+                let mut failed_payout_accounts = self.failed_payout_accounts.get_or_default();
+                failed_payout_accounts.retain(|account| *account != caller);
+                if failed.is_zero() {
+                    failed_payout_accounts.push(new_account);
+                }
+                self.failed_payout_accounts.set(&failed_payout_accounts);
+            }
+            for token in self.assets.get_or_default().into_iter().flatten() {
+                if let Some(amount) = self.token_pending.take((token, caller)) {
+                    let pending = self.token_pending.get((token, new_account)).unwrap_or_default();
+                    self.token_pending.insert((token, new_account), &pending.checked_add(amount).ok_or(Error::ArithmeticOverflow)?);
+                }
+            }
+            
+            self.record_config();
+            
+            self.env().emit_event(BeneficiaryAccountRotated {
+                old: caller,
+                new: new_account,
+            });
+            
+            Ok(())
+        }
+
+        /// Move part of the caller's pending balance to another beneficiary (beneficiary)
+        ///
+        /// Nothing leaves the contract, so `total_withdrawn` is unchanged on
+        /// both sides. Only the caller's unlocked, vested balance can be moved,
+        /// and neither side may be frozen.
+        #[ink(message)]
+        pub fn transfer_pending(&mut self, to: H160, amount: U256) -> Result<()> {
+            self.ensure_withdrawals_open()?;
+            let caller = self.env().caller();
+            
+            if amount.is_zero() {
+                return Err(Error::ZeroAmount);
+            }
+            if to == caller {
+                return Err(Error::SameAccount);
+            }
+            self.ensure_not_blacklisted(caller)?;
+            self.ensure_not_blacklisted(to)?;
+            
+            let from_beneficiary = self.beneficiary_data
+                .get(caller)
+                .ok_or(Error::Unauthorized)?;
+            let to_beneficiary = self.beneficiary_data
+                .get(to)
+                .ok_or(Error::BeneficiaryNotFound)?;
+            if from_beneficiary.frozen || to_beneficiary.frozen {
+                return Err(Error::BeneficiaryFrozen);
+            }
+            
+            if let Some(until) = from_beneficiary.locked_until {
+                if self.env().block_timestamp() < until {
+                    return Err(Error::FundsLocked(until));
+                }
+            }
+            
+            let available = self.withdrawable(caller);
+            if amount > available {
+                return Err(Error::InsufficientBalance {
+                    requested: amount,
+                    available,
+                });
+            }
+            
+            let (from_pending, from_withdrawn) = self.balances.get(caller).unwrap_or_default();
+            let from_pending = from_pending.checked_sub(amount).ok_or(Error::ArithmeticOverflow)?;
+            let (to_pending, to_withdrawn) = self.balances.get(to).unwrap_or_default();
+            let to_pending = to_pending.checked_add(amount).ok_or(Error::ArithmeticOverflow)?;
+            self.balances.insert(caller, &(from_pending, from_withdrawn));
+            self.balances.insert(to, &(to_pending, to_withdrawn));
+            
+            self.env().emit_event(PendingTransferred {
+                from: caller,
+                to,
+                amount,
+            });
+            
+            Ok(())
+        }
+
+        /// Set or clear the standing payout address (beneficiary)
+        ///
+        /// While set, `withdraw`, `withdraw_all` and push payouts deliver to it
+        /// instead of the beneficiary account.
+        #[ink(message)]
+        pub fn set_payout_address(&mut self, payout_address: Option<H160>) -> Result<()> {
+            let caller = self.env().caller();
+            
+            if let Some(address) = payout_address {
+                Self::ensure_not_zero(address)?;
+            }
+            
+            let mut beneficiary = self.beneficiary_data
+                .get(caller)
+                .ok_or(Error::Unauthorized)?;
+            if beneficiary.frozen {
+                return Err(Error::BeneficiaryFrozen);
+            }
+            
+            beneficiary.payout_address = payout_address;
+            self.beneficiary_data.insert(caller, &beneficiary);
+            
+            self.env().emit_event(PayoutAddressChanged {
+                beneficiary: caller,
+                payout_address,
+            });
+            
+            Ok(())
+        }
+
+        /// Deliver a beneficiary's funds by calling `receive_payment` on their destination (only owner or managers)
+        ///
+        /// Meant for beneficiaries that are themselves splitters: a plain
+        /// transfer would leave the funds untracked there. Applies whenever
+        /// funds go to the beneficiary's payout destination, including on
+        /// removal; if the call fails the balance stays pending.
+        #[ink(message)]
+        pub fn set_forward_as_payment(&mut self, account: H160, enabled: bool) -> Result<()> {
+            self.ensure_manager_or_owner()?;
+            
+            let mut beneficiary = self.beneficiary_data
+                .get(account)
+                .ok_or(Error::BeneficiaryNotFound)?;
+            
+            beneficiary.forward_as_payment = enabled;
+            self.beneficiary_data.insert(account, &beneficiary);
+            
+            self.env().emit_event(ForwardAsPaymentChanged {
+                beneficiary: account,
+                enabled,
+            });
+            
+            Ok(())
+        }
+
+        /// Ask for an `on_split_payout(amount)` call after push payouts (beneficiary)
+        ///
+        /// Only has an effect when the payout destination is a contract. The
+        /// callback runs after the funds are delivered and gets at most
+        /// `NOTIFY_REF_TIME_LIMIT`; if it fails the payout still stands and
+        /// `CallbackFailed` is emitted.
+        #[ink(message)]
+        pub fn set_notify_on_payout(&mut self, enabled: bool) -> Result<()> {
+            let caller = self.env().caller();
+            
+            let mut beneficiary = self.beneficiary_data
+                .get(caller)
+                .ok_or(Error::Unauthorized)?;
+            
+            beneficiary.notify_on_payout = enabled;
+            self.beneficiary_data.insert(caller, &beneficiary);
+            
+            self.env().emit_event(NotifyOnPayoutChanged {
+                beneficiary: caller,
+                enabled,
+            });
+            
+            Ok(())
+        }
+
+        /// Freeze or unfreeze a beneficiary (only owner)
+        ///
+        /// A frozen beneficiary keeps receiving their share of distributions,
+        /// but cannot withdraw, approve spenders, change their payout address,
+        /// be withdrawn from or be paid out until unfrozen.
+        #[ink(message)]
+        pub fn freeze_beneficiary(&mut self, account: H160, frozen: bool) -> Result<()> {
+            self.ensure_owner()?;
+            
+            let mut beneficiary = self.beneficiary_data
+                .get(account)
+                .ok_or(Error::BeneficiaryNotFound)?;
+            
+            beneficiary.frozen = frozen;
+            self.beneficiary_data.insert(account, &beneficiary);
+            
+            self.env().emit_event(BeneficiaryFreezeChanged {
+                beneficiary: account,
+                frozen,
+            });
+            
+            Ok(())
+        }
+
+        /// Push every non-zero pending balance to its beneficiary (only owner, distribution managers or operators)
+        ///
+        /// A failed transfer does not revert the batch: that beneficiary is
+        /// skipped with a `PayoutFailed` event and the amount moves to the
+        /// retry queue (see `retry_failed_payout`). Returns the number of
+        /// successful payouts.
+        #[ink(message)]
+        pub fn payout_all(&mut self) -> Result<u32> {
+            self.non_reentrant(|this| {
+                this.ensure_withdrawals_open()?;
+                this.ensure_can_trigger()?;
+                
+                let mut paid = 0u32;
+                
+                for account in this.beneficiary_accounts.get_or_default() {
+                    if this.payout_beneficiary(account).is_ok() {
+                        paid = paid.saturating_add(1);
+                    }
+                }
+                
+                Ok(paid)
+            })
+        }
+
+        /// Push the pending balance of a single beneficiary (only owner, distribution managers or operators)
+        ///
+        /// A failed transfer reverts the call, so nothing is queued for retry.
+        #[ink(message)]
+        pub fn payout(&mut self, account: H160) -> Result<()> {
+            self.non_reentrant(|this| {
+                this.ensure_withdrawals_open()?;
+                this.ensure_can_trigger()?;
+                
+                if !this.beneficiary_data.contains(account) {
+                    return Err(Error::BeneficiaryNotFound);
+                }
+                
+                this.payout_beneficiary(account)?;
+                
+                Ok(())
+            })
+        }
+
+        /// Send a beneficiary's whole pending balance to their payout destination
+        ///
+        /// Anyone may call this; the funds never go to the caller. Returns the
+        /// amount claimed.
+        #[ink(message)]
+        pub fn claim_for(&mut self, beneficiary: H160) -> Result<U256> {
+            self.non_reentrant(|this| {
+                this.ensure_withdrawals_open()?;
+                this.claim_beneficiary(beneficiary)
+            })
+        }
+
+        /// `claim_for` over several beneficiaries, returning how many were paid
+        ///
+        /// Beneficiaries that cannot be claimed for (unknown, frozen, nothing
+        /// pending, failed transfer) are skipped instead of reverting the batch.
+        #[ink(message)]
+        pub fn claim_for_many(&mut self, beneficiaries: Vec<H160>) -> Result<u32> {
+            self.non_reentrant(|this| {
+                this.ensure_withdrawals_open()?;
+                
+                let mut claimed = 0u32;
+                
+                for beneficiary in beneficiaries {
+                    if this.claim_beneficiary(beneficiary).is_ok() {
+                        claimed = claimed.saturating_add(1);
+                    }
+                }
+                
+                Ok(claimed)
+            })
+        }
+
+        /// Let `operator` trigger distributions and push payouts, and nothing else (only owner)
+        ///
+        /// Operators do not pay the keeper fee on `distribute`.
+        #[ink(message)]
+        pub fn add_operator(&mut self, operator: H160) -> Result<()> {
+            self.ensure_owner()?;
+            Self::ensure_not_zero(operator)?;
+            
+            if !self.is_operator(operator) {
+                self.operators.insert(operator, &true);
+                let mut operator_list = self.operator_list.get_or_default();
+                operator_list.push(operator);
+                self.operator_list.set(&operator_list);
+            }
+            self.log_admin_action(AdminActionKind::AddOperator, Some(operator));
+            
+            self.env().emit_event(OperatorAdded {
+                operator,
+                added_by: self.env().caller(),
+            });
+            
+            Ok(())
+        }
+
+        /// Remove an operator (only owner)
+        #[ink(message)]
+        pub fn remove_operator(&mut self, operator: H160) -> Result<()> {
+            self.ensure_owner()?;
+            
+            if self.operators.take(operator).is_none() {
+                return Err(Error::OperatorNotFound);
+            }
+            let mut operator_list = self.operator_list.get_or_default();
+            operator_list.retain(|o| *o != operator);
+            self.operator_list.set(&operator_list);
+            self.log_admin_action(AdminActionKind::RemoveOperator, Some(operator));
+            
+            self.env().emit_event(OperatorRemoved {
+                operator,
+                removed_by: self.env().caller(),
+            });
+            
+            Ok(())
+        }
+
+        /// Add a manager with the given permissions, optionally until `expires_at` (only owner)
+        ///
+        /// Adding an existing manager replaces their permissions and expiry.
+        /// Expired managers lose their rights at once; `prune_expired_managers`
+        /// clears them out of storage.
+        #[ink(message)]
+        pub fn add_manager(&mut self, manager: H160, permissions: ManagerPermissions, expires_at: Option<u64>) -> Result<()> {
+            self.ensure_owner()?;
+            Self::ensure_not_zero(manager)?;
+            
+            if !self.managers.contains(manager) {
+                let mut manager_list = self.manager_list.get_or_default();
+                manager_list.push(manager);
+                self.manager_list.set(&manager_list);
+            }
+            self.managers.insert(manager, &ManagerAppointment { permissions, expires_at });
+            self.log_admin_action(AdminActionKind::AddManager, Some(manager));
+            
+            self.env().emit_event(ManagerAdded {
+                manager,
+                added_by: self.env().caller(),
+                permissions,
+                expires_at,
+            });
+            
+            Ok(())
+        }
+
+        /// Replace the permissions of an existing manager (only owner)
+        #[ink(message)]
+        pub fn set_manager_permissions(&mut self, manager: H160, permissions: ManagerPermissions) -> Result<()> {
+            self.ensure_owner()?;
+            
+            let mut appointment = self.managers
+                .get(manager)
+                .filter(|appointment| appointment.is_active(self.env().block_timestamp()))
+                .ok_or(Error::ManagerNotFound)?;
+            appointment.permissions = permissions;
+            self.managers.insert(manager, &appointment);
+            
+            self.env().emit_event(ManagerPermissionsUpdated {
+                manager,
+                permissions,
+            });
+            
+            Ok(())
+        }
+
+        /// Remove a manager (only owner)
+        #[ink(message)]
+        pub fn remove_manager(&mut self, manager: H160) -> Result<()> {
+            self.ensure_owner()?;
+            
+            let mut manager_list = self.manager_list.get_or_default();
+            let position = manager_list
+                .iter()
+                .position(|m| *m == manager)
+                .ok_or(Error::ManagerNotFound)?;
+            
+            manager_list.swap_remove(position);
+            self.manager_list.set(&manager_list);
+            self.managers.remove(manager);
+            self.log_admin_action(AdminActionKind::RemoveManager, Some(manager));
+            
+            self.env().emit_event(ManagerRemoved {
+                manager,
+                removed_by: self.env().caller(),
+            });
+            
+            Ok(())
+        }
+
+        /// Remove every manager whose appointment has expired, returning how many
+        ///
+        /// Anyone may call this; expired managers already have no rights.
+        #[ink(message)]
+        pub fn prune_expired_managers(&mut self) -> u32 {
+            let now = self.env().block_timestamp();
+            let mut manager_list = self.manager_list.get_or_default();
+            let mut pruned = 0u32;
+            
+            for manager in manager_list.clone() {
+                if self.managers.get(manager).is_some_and(|appointment| appointment.is_active(now)) {
+                    continue;
+                }
+                self.managers.remove(manager);
+                manager_list.retain(|m| *m != manager);
+                pruned = pruned.saturating_add(1);
+                self.log_admin_action(AdminActionKind::RemoveManager, Some(manager));
+                
+                self.env().emit_event(ManagerRemoved {
+                    manager,
+                    removed_by: self.env().caller(),
+                });
+            }
+            
+            if pruned > 0 {
+                self.manager_list.set(&manager_list);
+            }
+            pruned
+        }
+
+        /// Pause the contract (only owner or managers allowed to pause)
+        ///
+        /// Deposits and admin changes are always stopped. Withdrawals are only
+        /// stopped if the pause policy does not allow them while paused.
+        #[ink(message)]
+        pub fn pause(&mut self) -> Result<()> {
+            self.ensure_permission(Permission::Pause)?;
+            self.deposits_paused = true;
+            if !self.withdrawals_allowed_when_paused {
+                self.withdrawals_paused = true;
+            }
+            self.log_admin_action(AdminActionKind::Pause, None);
+            
+            self.env().emit_event(ContractPaused {
+                by: self.env().caller(),
+            });
+            
+            Ok(())
+        }
+
+        /// Unpause deposits and withdrawals (only owner)
+        #[ink(message)]
+        pub fn unpause(&mut self) -> Result<()> {
+            self.ensure_owner()?;
+            self.deposits_paused = false;
+            self.withdrawals_paused = false;
+            self.log_admin_action(AdminActionKind::Unpause, None);
+            
+            self.env().emit_event(ContractUnpaused {
+                by: self.env().caller(),
+            });
+            
+            Ok(())
+        }
+
+        /// Change the contract's name and description (only owner)
+        #[ink(message)]
+        pub fn set_metadata(&mut self, name: Vec<u8>, description: Vec<u8>) -> Result<()> {
+            self.ensure_owner()?;
+            self.store_metadata(name.clone(), description.clone())?;
+            
+            self.env().emit_event(MetadataChanged {
+                name,
+                description,
+            });
+            
+            Ok(())
+        }
+
+        /// Deploy a new splitter running this contract's code (only owner)
+        ///
+        /// The child is created with `new_with_owner_and_beneficiaries`, so
+        /// `owner` rather than this contract administers it. Children get
+        /// distinct, predictable addresses: the salt is derived from this
+        /// contract's address and the number of children created so far.
+        #[ink(message)]
+        pub fn create_child(&mut self, beneficiaries: Vec<(H160, u16)>, owner: H160) -> Result<H160> {
+            self.ensure_owner()?;
+            Self::ensure_not_zero(owner)?;
+            
+            let mut children = self.children.get_or_default();
+            let code_hash = self.env().own_code_hash()
+                .map_err(|_| Error::InstantiationFailed)?;
+            let salt = self.env().hash_encoded::<ink::env::hash::Keccak256, _>(
+                &(self.env().address(), children.len() as u32),
+            );
+            
+            let result = build_create::<SplitPaymentRef>()
+                .code_hash(code_hash)
+                .endowment(U256::zero())
+                .exec_input(
+                    ExecutionInput::new(Selector::new(ink::selector_bytes!("new_with_owner_and_beneficiaries")))
+                        .push_arg(owner)
+                        .push_arg(beneficiaries),
+                )
+                .salt_bytes(Some(salt))
+                .returns::<Result<SplitPaymentRef>>()
+                .try_instantiate();
+            // The child's own error (e.g. a bad share table) is passed through
+            let child = match result {
+                Ok(Ok(child)) => child?.to_addr(),
+                _ => return Err(Error::InstantiationFailed),
+            };
+            
+            children.push(child);
+            self.children.set(&children);
+            
+            self.env().emit_event(ChildCreated {
+                child,
+                owner,
+            });
+            
+            Ok(child)
+        }
+
+        /// Change any subset of the settings in `Config` at once (only owner)
+        ///
+        /// Fields left `None` in `patch` keep their current value. The result
+        /// is validated as a whole, so either every change applies or none does.
+        #[ink(message)]
+        pub fn update_config(&mut self, patch: ConfigUpdate) -> Result<()> {
+            self.ensure_owner()?;
+            self.apply_config(self.config.clone().patched(patch))?;
+            
+            self.env().emit_event(ConfigChanged {
+                config: self.config.clone(),
+            });
+            
+            Ok(())
+        }
+
+        /// Change the beneficiary limit (only owner)
+        ///
+        /// The limit cannot be set below the current number of beneficiaries.
+        #[ink(message)]
+        pub fn set_max_beneficiaries(&mut self, max_beneficiaries: u32) -> Result<()> {
+            self.ensure_owner()?;
+            
+            self.apply_config(Config { max_beneficiaries, ..self.config.clone() })?;
+            
+            self.env().emit_event(MaxBeneficiariesChanged {
+                max_beneficiaries,
+            });
+            
+            Ok(())
+        }
+
+        /// Cap how much each beneficiary can withdraw per window, or lift the cap (only owner)
+        ///
+        /// `limit` is `(amount, window_ms)`. The cap applies to every path
+        /// that moves a beneficiary's funds out, including approvals and
+        /// push payouts.
+        #[ink(message)]
+        pub fn set_withdrawal_limit(&mut self, limit: Option<(U256, u64)>) -> Result<()> {
+            self.ensure_owner()?;
+            
+            self.apply_config(Config { withdrawal_limit: limit, ..self.config.clone() })?;
+            
+            self.env().emit_event(WithdrawalLimitChanged {
+                limit,
+            });
+            
+            Ok(())
+        }
+
+        /// Set the minimum time between withdrawals per beneficiary, 0 to disable (only owner)
+        #[ink(message)]
+        pub fn set_withdrawal_cooldown(&mut self, cooldown_ms: u64) -> Result<()> {
+            self.ensure_owner()?;
+            self.apply_config(Config { withdrawal_cooldown_ms: cooldown_ms, ..self.config.clone() })?;
+            
+            self.env().emit_event(WithdrawalCooldownChanged {
+                cooldown_ms,
+            });
+            
+            Ok(())
+        }
+
+        /// Set the smallest payment `receive_payment` accepts (only owner)
+        #[ink(message)]
+        pub fn set_min_payment(&mut self, min_payment: U256) -> Result<()> {
+            self.ensure_owner()?;
+            self.apply_config(Config { min_payment, ..self.config.clone() })?;
+            
+            self.env().emit_event(MinPaymentChanged {
+                min_payment,
+            });
+            
+            Ok(())
+        }
+
+        /// Refuse payments before `accepting_from`, or accept them again with `None` (only owner)
+        #[ink(message)]
+        pub fn set_accepting_from(&mut self, accepting_from: Option<u64>) -> Result<()> {
+            self.ensure_owner()?;
+            self.set_acceptance_window(accepting_from, self.accepting_until)
+        }
+
+        /// Refuse payments after `accepting_until`, or accept them again with `None` (only owner)
+        #[ink(message)]
+        pub fn set_accepting_until(&mut self, accepting_until: Option<u64>) -> Result<()> {
+            self.ensure_owner()?;
+            self.set_acceptance_window(self.accepting_from, accepting_until)
+        }
+
+        /// Refuse payments unless shares are fully allocated, or lift the requirement (only owner)
+        #[ink(message)]
+        pub fn set_strict_allocation(&mut self, enabled: bool) -> Result<()> {
+            self.ensure_owner()?;
+            self.apply_config(Config { strict_allocation: enabled, ..self.config.clone() })?;
+            
+            self.env().emit_event(StrictAllocationToggled {
+                enabled,
+            });
+            
+            Ok(())
+        }
+
+        /// Hold incoming payments in escrow, or go back to accepting them directly (only owner)
+        ///
+        /// Payments already held stay held until released or refunded.
+        #[ink(message)]
+        pub fn set_escrow_enabled(&mut self, enabled: bool) -> Result<()> {
+            self.ensure_owner()?;
+            self.escrow_enabled = enabled;
+            
+            self.env().emit_event(EscrowToggled {
+                enabled,
+            });
+            
+            Ok(())
+        }
+
+        /// Permanently freeze the beneficiary list once it is fully allocated (only owner)
+        ///
+        /// Afterwards no beneficiary can be added, removed, rescaled or exit,
+        /// so payers know the split cannot change under them.
+        #[ink(message)]
+        pub fn finalize_shares(&mut self) -> Result<()> {
+            self.ensure_owner()?;
+            self.ensure_shares_unlocked()?;
+            
+            if !self.is_fully_allocated() {
+                return Err(Error::SharesNotFullyAllocated);
+            }
+            
+            self.shares_locked = true;
+            
+            self.env().emit_event(SharesFinalized {
+                finalized_by: self.env().caller(),
+            });
+            
+            Ok(())
+        }
+
+        /// Block or unblock an account (only owner)
+        ///
+        /// A blacklisted account cannot pay in, be added or invited as a
+        /// beneficiary, be approved as a spender or spend an approval. A
+        /// blacklisted beneficiary keeps their pending balance and shares, but
+        /// nothing can leave it until they are unblocked.
+        #[ink(message)]
+        pub fn set_blacklisted(&mut self, account: H160, blacklisted: bool) -> Result<()> {
+            self.ensure_owner()?;
+            
+            if blacklisted {
+                self.blacklist.insert(account, &true);
+            } else {
+                self.blacklist.remove(account);
+            }
+            
+            self.env().emit_event(BlacklistUpdated {
+                account,
+                blacklisted,
+            });
+            
+            Ok(())
+        }
+
+        /// Restrict `receive_payment` to allowed payers, or lift the restriction (only owner)
+        #[ink(message)]
+        pub fn set_payer_whitelist_enabled(&mut self, enabled: bool) -> Result<()> {
+            self.ensure_owner()?;
+            self.payer_whitelist_enabled = enabled;
+            
+            self.env().emit_event(PayerWhitelistToggled {
+                enabled,
+            });
+            
+            Ok(())
+        }
+
+        /// Allow an account to pay in while the whitelist is enabled (only owner or managers)
+        #[ink(message)]
+        pub fn add_allowed_payer(&mut self, payer: H160) -> Result<()> {
+            self.ensure_manager_or_owner()?;
+            self.allowed_payers.insert(payer, &true);
+            
+            self.env().emit_event(PayerAllowed {
+                payer,
+                added_by: self.env().caller(),
+            });
+            
+            Ok(())
+        }
+
+        /// Remove an account from the payer whitelist (only owner or managers)
+        #[ink(message)]
+        pub fn remove_allowed_payer(&mut self, payer: H160) -> Result<()> {
+            self.ensure_manager_or_owner()?;
+            self.allowed_payers.remove(payer);
+            
+            self.env().emit_event(PayerDisallowed {
+                payer,
+                removed_by: self.env().caller(),
+            });
+            
+            Ok(())
+        }
+
+        /// Accept payments in a PSP22 token (only owner)
+        #[ink(message)]
+        pub fn add_accepted_token(&mut self, token: H160) -> Result<()> {
+            self.ensure_owner()?;
+            Self::ensure_not_zero(token)?;
+            
+            if !self.is_accepted_token(token) {
+                self.accepted_tokens.insert(token, &true);
+                let mut accepted_token_list = self.accepted_token_list.get_or_default();
+                accepted_token_list.push(token);
+                self.accepted_token_list.set(&accepted_token_list);
+            }
+            
+            self.env().emit_event(TokenAccepted {
+                token,
+            });
+            
+            Ok(())
+        }
+
+        /// Stop accepting payments in a PSP22 token (only owner)
+        ///
+        /// Balances already credited in the token can still be withdrawn.
+        #[ink(message)]
+        pub fn remove_accepted_token(&mut self, token: H160) -> Result<()> {
+            self.ensure_owner()?;
+            
+            if self.accepted_tokens.take(token).is_some() {
+                let mut accepted_token_list = self.accepted_token_list.get_or_default();
+                accepted_token_list.retain(|t| *t != token);
+                self.accepted_token_list.set(&accepted_token_list);
+            }
+            
+            self.env().emit_event(TokenDelisted {
+                token,
+            });
+            
+            Ok(())
+        }
+
+        /// Set how many payment records are kept (only owner)
+        ///
+        /// `0` keeps every record. Lowering the limit drops the excess records
+        /// a few at a time with each new payment (see `MAX_PRUNE_PER_PAYMENT`);
+        /// `prune_payments` clears a large backlog faster.
+        #[ink(message)]
+        pub fn set_max_history(&mut self, max_history: u64) -> Result<()> {
+            self.ensure_owner()?;
+            self.apply_config(Config { max_history, ..self.config.clone() })?;
+            
+            self.env().emit_event(MaxHistoryChanged {
+                max_history,
+            });
+            
+            Ok(())
+        }
+
+        /// Delete payment records with an id below `before_id` (only owner)
+        ///
+        /// At most `MAX_PRUNE_BATCH` records are deleted per call; call again
+        /// to continue.
+        #[ink(message)]
+        pub fn prune_payments(&mut self, before_id: u64) -> Result<()> {
+            self.ensure_owner()?;
+            
+            self.prune_payment_records(before_id.min(self.payment_count), MAX_PRUNE_BATCH);
+            
+            Ok(())
+        }
+
+        /// Delete share table snapshots below `before_version` (only owner)
+        ///
+        /// The current version is always kept.
+        #[ink(message)]
+        pub fn prune_config_history(&mut self, before_version: u64) -> Result<()> {
+            self.ensure_owner()?;
+            
+            let end = before_version.min(self.config_version);
+            while self.config_history_start < end {
+                self.config_history.remove(self.config_history_start);
+                self.config_history_start += 1;
+            }
+            
+            Ok(())
+        }
+
+        /// Forget idempotency keys to reclaim their storage (only owner)
+        ///
+        /// A pruned key is accepted again by `receive_payment_idempotent`.
+        #[ink(message)]
+        pub fn prune_payment_keys(&mut self, keys: Vec<[u8; 32]>) -> Result<()> {
+            self.ensure_owner()?;
+            
+            for key in keys {
+                self.payment_keys.remove(key);
+            }
+            
+            Ok(())
+        }
+
+        /// Choose whether `pause` leaves withdrawals open (only owner)
+        ///
+        /// Takes effect on the next `pause`; the current pause state is untouched.
+        #[ink(message)]
+        pub fn set_pause_policy(&mut self, withdrawals_allowed_when_paused: bool) -> Result<()> {
+            self.ensure_owner()?;
+            self.withdrawals_allowed_when_paused = withdrawals_allowed_when_paused;
+            
+            self.env().emit_event(PausePolicyChanged {
+                withdrawals_allowed_when_paused,
+            });
+            
+            Ok(())
+        }
+
+        /// Stop incoming payments and distributions (only owner or managers allowed to pause)
+        #[ink(message)]
+        pub fn pause_deposits(&mut self) -> Result<()> {
+            self.ensure_permission(Permission::Pause)?;
+            self.deposits_paused = true;
+            self.log_admin_action(AdminActionKind::Pause, None);
+            
+            self.env().emit_event(DepositsPaused {
+                by: self.env().caller(),
+            });
+            
+            Ok(())
+        }
+
+        /// Resume incoming payments and distributions (only owner)
+        #[ink(message)]
+        pub fn unpause_deposits(&mut self) -> Result<()> {
+            self.ensure_owner()?;
+            self.deposits_paused = false;
+            self.log_admin_action(AdminActionKind::Unpause, None);
+            
+            self.env().emit_event(DepositsUnpaused {
+                by: self.env().caller(),
+            });
+            
+            Ok(())
+        }
+
+        /// Stop withdrawals and payouts (only owner or managers allowed to pause)
+        #[ink(message)]
+        pub fn pause_withdrawals(&mut self) -> Result<()> {
+            self.ensure_permission(Permission::Pause)?;
+            self.withdrawals_paused = true;
+            self.log_admin_action(AdminActionKind::Pause, None);
+            
+            self.env().emit_event(WithdrawalsPaused {
+                by: self.env().caller(),
+            });
+            
+            Ok(())
+        }
+
+        /// Resume withdrawals and payouts (only owner)
+        #[ink(message)]
+        pub fn unpause_withdrawals(&mut self) -> Result<()> {
+            self.ensure_owner()?;
+            self.withdrawals_paused = false;
+            self.log_admin_action(AdminActionKind::Unpause, None);
+            
+            self.env().emit_event(WithdrawalsUnpaused {
+                by: self.env().caller(),
+            });
+            
+            Ok(())
+        }
+
+        /// Choose between immediate and manual distribution (only owner)
+        ///
+        /// Funds already waiting in the distribution pool stay there until
+        /// `distribute` is called, even after switching back to `Immediate`.
+        #[ink(message)]
+        pub fn set_distribution_mode(&mut self, mode: DistributionMode) -> Result<()> {
+            self.ensure_owner()?;
+            self.apply_config(Config { distribution_mode: mode, ..self.config.clone() })?;
+            
+            self.env().emit_event(DistributionModeChanged {
+                mode,
+                changed_by: self.env().caller(),
+            });
+            
+            Ok(())
+        }
+
+        /// Batch payments until the distribution pool reaches `threshold` (only owner)
+        ///
+        /// Under `Immediate` mode payments collect in the distribution pool and
+        /// the whole pool is split once it reaches the threshold; `distribute`
+        /// flushes it earlier. A threshold of 0 splits every payment on receipt.
+        #[ink(message)]
+        pub fn set_distribution_threshold(&mut self, threshold: U256) -> Result<()> {
+            self.ensure_owner()?;
+            self.apply_config(Config { distribution_threshold: threshold, ..self.config.clone() })?;
+            
+            self.env().emit_event(DistributionThresholdChanged {
+                threshold,
+            });
+            
+            Ok(())
+        }
+
+        /// Set the bounty paid to third parties who trigger `distribute` (only owner)
+        #[ink(message)]
+        pub fn set_keeper_fee_bps(&mut self, keeper_fee_bps: u16) -> Result<()> {
+            self.ensure_owner()?;
+            
+            self.apply_config(Config { keeper_fee_bps, ..self.config.clone() })?;
+            
+            self.env().emit_event(KeeperFeeChanged {
+                keeper_fee_bps,
+            });
+            
+            Ok(())
+        }
+
+        /// Set the protocol fee taken from every incoming payment (only owner)
+        ///
+        /// Only affects payments received from now on; fees already accrued
+        /// are kept as they are.
+        #[ink(message)]
+        pub fn set_fee_bps(&mut self, fee_bps: u16) -> Result<()> {
+            self.ensure_owner()?;
+            
+            self.apply_config(Config { fee_bps, ..self.config.clone() })?;
+            
+            self.env().emit_event(ProtocolFeeChanged {
+                fee_bps,
+            });
+            
+            Ok(())
+        }
+
+        /// Set the account that can withdraw accrued protocol fees (only owner)
+        #[ink(message)]
+        pub fn set_fee_recipient(&mut self, fee_recipient: H160) -> Result<()> {
+            self.ensure_owner()?;
+            self.apply_config(Config { fee_recipient, ..self.config.clone() })?;
+            
+            self.env().emit_event(FeeRecipientChanged {
+                fee_recipient,
+            });
+            
+            Ok(())
+        }
+
+        /// Transfer ownership in a single step (only current owner)
+        ///
+        /// Prefer `propose_owner` / `accept_ownership`, which cannot hand the
+        /// contract to an address nobody controls.
+        #[ink(message)]
+        pub fn transfer_ownership(&mut self, new_owner: H160) -> Result<()> {
+            self.ensure_owner()?;
+            self.ensure_direct_admin_allowed()?;
+            Self::ensure_not_zero(new_owner)?;
+            
+            self.set_owner(new_owner);
+            
+            Ok(())
+        }
+
+        /// Propose a new owner, who must call `accept_ownership` (only current owner)
+        #[ink(message)]
+        pub fn propose_owner(&mut self, new_owner: H160) -> Result<()> {
+            self.ensure_owner()?;
+            self.ensure_direct_admin_allowed()?;
+            Self::ensure_not_zero(new_owner)?;
+            
+            self.pending_owner = Some(new_owner);
+            
+            self.env().emit_event(OwnershipProposed {
+                current_owner: self.owner,
+                proposed_owner: new_owner,
+            });
+            
+            Ok(())
+        }
+
+        /// Accept a pending ownership proposal (only the proposed owner)
+        #[ink(message)]
+        pub fn accept_ownership(&mut self) -> Result<()> {
+            let caller = self.env().caller();
+            
+            if self.pending_owner != Some(caller) {
+                return Err(Error::Unauthorized);
+            }
+            
+            let previous_owner = self.owner;
+            self.owner = caller;
+            self.pending_owner = None;
+            
+            self.env().emit_event(OwnershipTransferred {
+                previous_owner,
+                new_owner: caller,
+            });
+            
+            Ok(())
+        }
+
+        /// Give up ownership for good (only current owner)
+        ///
+        /// Only allowed while the contract is unpaused and fully allocated,
+        /// since nobody will be able to fix the configuration afterwards;
+        /// otherwise fails with `SharesNotFullyAllocated`.
+        /// Owner and manager messages are disabled from then on; payments and
+        /// withdrawals keep working.
+        #[ink(message)]
+        pub fn renounce_ownership(&mut self) -> Result<()> {
+            self.ensure_owner()?;
+            self.ensure_not_paused()?;
+            
+            if !self.is_fully_allocated() {
+                return Err(Error::SharesNotFullyAllocated);
+            }
+            
+            let previous_owner = self.owner;
+            self.owner = H160::zero();
+            self.pending_owner = None;
+            
+            self.env().emit_event(OwnershipRenounced {
+                previous_owner,
+            });
+            
+            Ok(())
+        }
+
+        /// Withdraw a pending ownership proposal (only current owner)
+        ///
+        /// Fails with `NoPendingOwner` when nothing has been proposed.
+        #[ink(message)]
+        pub fn cancel_ownership_proposal(&mut self) -> Result<()> {
+            self.ensure_owner()?;
+            
+            let cancelled = self.pending_owner.take().ok_or(Error::NoPendingOwner)?;
+            
+            self.env().emit_event(OwnershipProposalCancelled {
+                cancelled,
+            });
+            
+            Ok(())
+        }
+
+        /// Transfer all accrued protocol fees to the caller (only fee recipient)
+        #[ink(message)]
+        pub fn withdraw_fees(&mut self) -> Result<U256> {
+            self.non_reentrant(|this| {
+                this.ensure_withdrawals_open()?;
+                
+                let caller = this.env().caller();
+                if caller != this.config.fee_recipient {
+                    return Err(Error::Unauthorized);
+                }
+                
+                let amount = this.fee_accrued;
+                if amount.is_zero() {
+                    return Err(Error::NoFundsAvailable);
+                }
+                
+                this.fee_accrued = U256::zero();
+                this.pool_outflows = this.pool_outflows
+                    .checked_add(amount)
+                    .ok_or(Error::ArithmeticOverflow)?;
+                
+                this.env().transfer(caller, amount)
+                    .map_err(|_| Error::TransferFailed)?;
+                
+                this.env().emit_event(FeesWithdrawn {
+                    fee_recipient: caller,
+                    amount,
+                });
+                
+                Ok(amount)
+            })
+        }
+
+        /// Transfer the undistributed pool out of the contract (only owner)
+        #[ink(message)]
+        pub fn sweep_undistributed(&mut self, to: H160) -> Result<()> {
+            self.non_reentrant(|this| {
+                this.ensure_owner()?;
+                Self::ensure_not_zero(to)?;
+
+                let amount = this.undistributed_pool;
+                if amount.is_zero() {
+                    return Err(Error::NoFundsAvailable);
+                }
+
+                this.undistributed_pool = U256::zero();
+                this.pool_outflows = this.pool_outflows
+                    .checked_add(amount)
+                    .ok_or(Error::ArithmeticOverflow)?;
+
+                this.env().transfer(to, amount)
+                    .map_err(|_| Error::TransferFailed)?;
+
+                this.env().emit_event(UndistributedSwept {
+                    to,
+                    amount,
+                });
+
+                Ok(())
+            })
+        }
+
+        /// Transfer out any balance not owed to beneficiaries or held in a pool (only owner)
+        ///
+        /// Recovers funds sent to the contract without `receive_payment`.
+        /// Pending balances, the undistributed pool and the distribution pool
+        /// are never touched. Returns the amount rescued.
+        #[ink(message)]
+        pub fn rescue_funds(&mut self, to: H160) -> Result<U256> {
+            self.non_reentrant(|this| {
+                this.ensure_owner()?;
+                Self::ensure_not_zero(to)?;
+                
+                let amount = this.untracked_balance();
+                if amount.is_zero() {
+                    return Err(Error::NoFundsAvailable);
+                }
+                
+                this.env().transfer(to, amount)
+                    .map_err(|_| Error::TransferFailed)?;
+                
+                this.env().emit_event(FundsRescued {
+                    to,
+                    amount,
+                });
+                
+                Ok(amount)
+            })
+        }
+
+        // Query functions
+
+        /// Replace the contract's code, keeping its address and storage (only owner)
+        ///
+        /// Deposits must be paused so no distribution runs mid-upgrade. The new
+        /// code must keep the storage layout compatible: existing fields stay
+        /// in the same order with the same types, and new fields may only be
+        /// appended. Anything else corrupts the stored state.
+        #[ink(message)]
+        pub fn upgrade(&mut self, code_hash: H256) -> Result<()> {
+            self.ensure_owner()?;
+            self.ensure_direct_admin_allowed()?;
+            self.upgrade_code(code_hash)
+        }
+
+        /// Queue a destructive admin action behind the admin delay (only owner)
+        ///
+        /// Returns the action id. Anyone can inspect the queue with
+        /// `get_pending_actions` before it becomes executable.
+        #[ink(message)]
+        pub fn propose_action(&mut self, action: AdminAction) -> Result<u64> {
+            self.ensure_owner()?;
+            self.ensure_no_council()?;
+            
+            let id = self.action_count;
+            let execute_after = self.env().block_timestamp().saturating_add(self.admin_delay_ms);
+            
+            self.pending_actions.insert(id, &PendingAction {
+                id,
+                action,
+                execute_after,
+            });
+            let mut pending_action_ids = self.pending_action_ids.get_or_default();
+            pending_action_ids.push(id);
+            self.pending_action_ids.set(&pending_action_ids);
+            self.action_count = id.saturating_add(1);
+            
+            self.env().emit_event(ActionProposed {
+                id,
+                action,
+                execute_after,
+            });
+            
+            Ok(id)
+        }
+
+        /// Carry out a proposed admin action once its delay has passed (only owner)
+        ///
+        /// The action is checked as if called directly at execution time; if it
+        /// fails, it stays queued.
+        #[ink(message)]
+        pub fn execute_action(&mut self, id: u64) -> Result<()> {
+            self.non_reentrant(|this| {
+                this.ensure_owner()?;
+                this.ensure_no_council()?;
+                
+                let pending = this.pending_actions
+                    .get(id)
+                    .ok_or(Error::ActionNotFound)?;
+                
+                if this.env().block_timestamp() < pending.execute_after {
+                    return Err(Error::ActionNotReady {
+                        execute_after: pending.execute_after,
+                    });
+                }
+                
+                this.remove_pending_action(id);
+                
+                this.perform_action(pending.action)?;
+                
+                this.env().emit_event(ActionExecuted {
+                    id,
+                    action: pending.action,
+                });
+                
+                Ok(())
+            })
+        }
+
+        /// Drop a proposed admin action (only owner)
+        #[ink(message)]
+        pub fn cancel_action(&mut self, id: u64) -> Result<()> {
+            self.ensure_owner()?;
+            
+            if !self.pending_actions.contains(id) {
+                return Err(Error::ActionNotFound);
+            }
+            
+            self.remove_pending_action(id);
+            
+            self.env().emit_event(ActionCancelled {
+                id,
+            });
+            
+            Ok(())
+        }
+
+        /// Lengthen the admin delay; it can never be shortened (only owner)
+        #[ink(message)]
+        pub fn increase_admin_delay(&mut self, admin_delay_ms: u64) -> Result<()> {
+            self.ensure_owner()?;
+            
+            if admin_delay_ms < self.admin_delay_ms {
+                return Err(Error::AdminDelayTooShort);
+            }
+            
+            self.admin_delay_ms = admin_delay_ms;
+            
+            self.env().emit_event(AdminDelayIncreased {
+                admin_delay_ms,
+            });
+            
+            Ok(())
+        }
+
+        /// Hand destructive admin actions to an m-of-n council (only owner)
+        ///
+        /// Afterwards removing beneficiaries, changing shares, upgrading and
+        /// transferring ownership only happen through `submit_admin_tx` once
+        /// `threshold` members have confirmed. Other owner settings are
+        /// unaffected. Can only be done once.
+        #[ink(message)]
+        pub fn set_council(&mut self, members: Vec<H160>, threshold: u8) -> Result<()> {
+            self.ensure_owner()?;
+            self.ensure_no_council()?;
+            self.configure_council(members, threshold)
+        }
+
+        /// Propose an admin action to the council, confirming it as the submitter (council member)
+        ///
+        /// Executes immediately if that already meets the threshold. Returns
+        /// the transaction id.
+        #[ink(message)]
+        pub fn submit_admin_tx(&mut self, action: AdminAction) -> Result<u64> {
+            self.ensure_council_member()?;
+            
+            let id = self.admin_tx_count;
+            self.admin_txs.insert(id, &(action, 0));
+            self.admin_tx_count = id.saturating_add(1);
+            
+            self.env().emit_event(AdminTxSubmitted {
+                id,
+                action,
+                submitted_by: self.env().caller(),
+            });
+            
+            self.confirm_admin_tx(id)?;
+            
+            Ok(id)
+        }
+
+        /// Confirm an admin transaction, executing it once the threshold is met (council member)
+        ///
+        /// If execution fails the whole call reverts, so the confirmation is
+        /// not recorded either.
+        #[ink(message)]
+        pub fn confirm_admin_tx(&mut self, id: u64) -> Result<()> {
+            self.non_reentrant(|this| {
+                let caller = this.ensure_council_member()?;
+                
+                let (action, count) = this.admin_txs
+                    .get(id)
+                    .ok_or(Error::ActionNotFound)?;
+                if this.confirmations.contains((id, caller)) {
+                    return Err(Error::AlreadyConfirmed);
+                }
+                
+                let count = count.saturating_add(1);
+                this.confirmations.insert((id, caller), &true);
+                this.admin_txs.insert(id, &(action, count));
+                
+                this.env().emit_event(AdminTxConfirmed {
+                    id,
+                    by: caller,
+                    confirmations: count,
+                });
+                
+                if count >= this.threshold {
+                    this.admin_txs.remove(id);
+                    for member in this.council.get_or_default() {
+                        this.confirmations.remove((id, member));
+                    }
+                    
+                    this.perform_action(action)?;
+                    
+                    this.env().emit_event(AdminTxExecuted {
+                        id,
+                        action,
+                    });
+                }
+                
+                Ok(())
+            })
+        }
+
+        /// Withdraw a confirmation from an admin transaction that has not executed yet (council member)
+        #[ink(message)]
+        pub fn revoke_confirmation(&mut self, id: u64) -> Result<()> {
+            let caller = self.ensure_council_member()?;
+            
+            let (action, count) = self.admin_txs
+                .get(id)
+                .ok_or(Error::ActionNotFound)?;
+            if self.confirmations.take((id, caller)).is_none() {
+                return Err(Error::NotConfirmed);
+            }
+            
+            let count = count.saturating_sub(1);
+            self.admin_txs.insert(id, &(action, count));
+            
+            self.env().emit_event(AdminTxConfirmationRevoked {
+                id,
+                by: caller,
+                confirmations: count,
+            });
+            
+            Ok(())
+        }
+
+        /// Get an admin transaction awaiting confirmations as `(action, confirmation_count)`
+        #[ink(message)]
+        pub fn get_admin_tx(&self, id: u64) -> Option<(AdminAction, u8)> {
+            self.admin_txs.get(id)
+        }
+
+        /// Check if a council member confirmed an admin transaction
+        #[ink(message)]
+        pub fn is_confirmed(&self, id: u64, member: H160) -> bool {
+            self.confirmations.contains((id, member))
+        }
+
+        /// Get the council members and threshold; empty when no council is configured
+        #[ink(message)]
+        pub fn get_council(&self) -> (Vec<H160>, u8) {
+            (self.council.get_or_default(), self.threshold)
+        }
+
+        /// Get every queued admin action, in proposal order
+        #[ink(message)]
+        pub fn get_pending_actions(&self) -> Vec<PendingAction> {
+            self.pending_action_ids
+                .get_or_default()
+                .iter()
+                .filter_map(|id| self.pending_actions.get(id))
+                .collect()
+        }
+
+        /// Get how long proposed admin actions wait before they can be executed
+        #[ink(message)]
+        pub fn get_admin_delay(&self) -> u64 {
+            self.admin_delay_ms
+        }
+
+        /// Stop accepting new funds for good, leaving withdrawals open (only owner)
+        ///
+        /// Irreversible. Payments, tips, token payments and `sync_balance`
+        /// fail with `ContractRetired`; funds already received can still be
+        /// distributed, and withdrawals and payouts work even while paused.
+        #[ink(message)]
+        pub fn retire(&mut self) -> Result<()> {
+            self.ensure_owner()?;
+            self.ensure_not_retired()?;
+            
+            self.retired = true;
+            
+            self.env().emit_event(ContractRetired {
+                total_received: self.total_received,
+            });
+            
+            Ok(())
+        }
+
+        /// Check if the contract has been retired
+        #[ink(message)]
+        pub fn is_retired(&self) -> bool {
+            self.retired
+        }
+
+        /// Pay everyone what they are owed and remove the contract (only owner)
+        ///
+        /// Deposits must be paused. Every pending balance is sent to its
+        /// beneficiary's payout destination, ignoring locks, vesting,
+        /// cooldowns and limits, and accrued fees go to the fee recipient.
+        /// Whatever is left goes to the owner, so funds waiting in the
+        /// distribution pool or in a chunked distribution must be split first.
+        /// If any payment fails (including to a frozen beneficiary) the call
+        /// fails with the accounts that could not be paid and nothing
+        /// changes, so it can be retried.
+        #[ink(message)]
+        pub fn terminate(&mut self) -> Result<()> {
+            self.non_reentrant(|this| {
+                this.ensure_owner()?;
+                
+                if !this.deposits_paused {
+                    return Err(Error::ContractNotPaused);
+                }
+                this.ensure_not_distributing()?;
+                
+                let tokens_pending = this.assets
+                    .get_or_default()
+                    .iter()
+                    .filter(|asset| asset.is_some())
+                    .any(|asset| !this.get_asset_stats(*asset).total_pending.is_zero());
+                if tokens_pending
+                    || !this.total_parked.is_zero()
+                    || !this.total_claimable.is_zero()
+                    || !this.total_failed_payouts.is_zero()
+                    || !this.distribution_pool.is_zero()
+                    || !this.held_total.is_zero()
+                    || !this.campaign_raised.is_zero()
+                {
+                    return Err(Error::FundsOutstanding);
+                }
+                
+                let mut failed = Vec::new();
+                for beneficiary in this.load_beneficiaries() {
+                    let amount = beneficiary.pending_balance;
+                    if amount.is_zero() {
+                        continue;
+                    }
+                    
+                    let destination = beneficiary.payout_destination();
+                    if beneficiary.frozen
+                        || this.is_blacklisted(beneficiary.account)
+                        || this.send_funds(destination, amount, beneficiary.forward_as_payment).is_err()
+                    {
+                        failed.push(beneficiary.account);
+                        continue;
+                    }
+                    
+                    let withdrawn = beneficiary.total_withdrawn
+                        .checked_add(amount)
+                        .ok_or(Error::ArithmeticOverflow)?;
+                    this.balances.insert(beneficiary.account, &(U256::zero(), withdrawn));
+                    this.record_release(beneficiary.account, amount)?;
+                    
+                    this.env().emit_event(Payout {
+                        beneficiary: beneficiary.account,
+                        amount,
+                    });
+                }
+                
+                if !this.fee_accrued.is_zero() {
+                    if this.send_funds(this.config.fee_recipient, this.fee_accrued, false).is_err() {
+                        failed.push(this.config.fee_recipient);
+                    } else {
+                        this.pool_outflows = this.pool_outflows
+                            .checked_add(this.fee_accrued)
+                            .ok_or(Error::ArithmeticOverflow)?;
+                        this.fee_accrued = U256::zero();
+                    }
+                }
+                
+                if !failed.is_empty() {
+                    return Err(Error::SettlementFailed { failed });
+                }
+                
+                this.env().emit_event(ContractTerminated {
+                    owner: this.owner,
+                    residual: this.env().balance(),
+                });
+                
+                this.env().terminate_contract(this.owner)
+            })
+        }
+
+        /// Get the version of the contract logic that is live
+        #[ink(message)]
+        pub fn get_code_version(&self) -> u32 {
+            CODE_VERSION
+        }
+
+        /// Get contract owner
+        #[ink(message)]
+        pub fn get_owner(&self) -> H160 {
+            self.owner
+        }
+
+        /// Get the proposed owner, if an ownership transfer is pending
+        #[ink(message)]
+        pub fn get_pending_owner(&self) -> Option<H160> {
+            self.pending_owner
+        }
+
+        /// Check if account is a manager
+        #[ink(message)]
+        pub fn is_manager(&self, account: H160) -> bool {
+            self.active_manager(account).is_some()
+        }
+
+        /// Get the permissions of a manager
+        #[ink(message)]
+        pub fn get_manager_permissions(&self, account: H160) -> Option<ManagerPermissions> {
+            self.active_manager(account)
+        }
+
+        /// Get a manager's expiry; `None` for non-managers and for appointments without one
+        #[ink(message)]
+        pub fn get_manager_expiry(&self, account: H160) -> Option<u64> {
+            self.managers.get(account).and_then(|appointment| appointment.expires_at)
+        }
+
+        /// Get all managers
+        #[ink(message)]
+        pub fn get_managers(&self) -> Vec<H160> {
+            self.manager_list.get_or_default()
+        }
+
+        /// Check if account is an operator
+        #[ink(message)]
+        pub fn is_operator(&self, account: H160) -> bool {
+            self.operators.contains(account)
+        }
+
+        /// Get all operators
+        #[ink(message)]
+        pub fn get_operators(&self) -> Vec<H160> {
+            self.operator_list.get_or_default()
+        }
+
+        /// Get the number of managers
+        #[ink(message)]
+        pub fn get_manager_count(&self) -> u32 {
+            self.manager_list.get_or_default().len() as u32
+        }
+
+        /// Get all beneficiaries
+        #[ink(message)]
+        pub fn get_beneficiaries(&self) -> Vec<Beneficiary> {
+            self.load_beneficiaries()
+        }
+
+        /// Check if an account is a beneficiary
+        #[ink(message)]
+        pub fn is_beneficiary(&self, account: H160) -> bool {
+            self.beneficiary_data.contains(account)
+        }
+
+        /// Get the current config version
+        #[ink(message)]
+        pub fn get_config_version(&self) -> u64 {
+            self.config_version
+        }
+
+        /// Get the `(account, share_bps)` table at a config version
+        ///
+        /// `None` for pruned versions and for version 0, the empty table
+        /// before any beneficiary was added.
+        #[ink(message)]
+        pub fn get_config_at(&self, version: u64) -> Option<Vec<(H160, u16)>> {
+            self.config_history.get(version)
+        }
+
+        /// Get a distribution by id
+        #[ink(message)]
+        pub fn get_distribution(&self, id: u64) -> Option<DistributionRecord> {
+            self.distributions.get(id)
+        }
+
+        /// Get the chunked distribution under way, if any
+        #[ink(message)]
+        pub fn get_distribution_progress(&self) -> Option<DistributionProgress> {
+            self.distribution_progress.clone()
+        }
+
+        /// Get the number of distributions made so far
+        #[ink(message)]
+        pub fn get_distribution_count(&self) -> u64 {
+            self.distribution_count
+        }
+
+        /// Get the pending balance of an account (0 if not a beneficiary)
+        #[ink(message)]
+        pub fn get_pending_balance(&self, account: H160) -> U256 {
+            self.balances.get(account).map(|(pending, _)| pending).unwrap_or_default()
+        }
+
+        /// Get `(account, share_bps)` for every pending invitation
+        #[ink(message)]
+        pub fn get_pending_invitations(&self) -> Vec<(H160, u16)> {
+            self.invitation_accounts
+                .get_or_default()
+                .iter()
+                .filter_map(|account| self.invitations.get(account).map(|share_bps| (*account, share_bps)))
+                .collect()
+        }
+
+        /// Check whether beneficiaries must accept an invitation to join
+        #[ink(message)]
+        pub fn requires_acceptance(&self) -> bool {
+            self.require_acceptance
+        }
+
+        /// Get `(account, effective_at)` for every pending removal
+        #[ink(message)]
+        pub fn get_pending_removals(&self) -> Vec<(H160, u64)> {
+            self.pending_removal_accounts
+                .get_or_default()
+                .iter()
+                .filter_map(|account| {
+                    self.pending_removals.get(account).map(|(effective_at, _)| (*account, effective_at))
+                })
+                .collect()
+        }
+
+        /// Get the delay between requesting and finalizing a removal, in milliseconds
+        #[ink(message)]
+        pub fn get_removal_delay(&self) -> u64 {
+            self.removal_delay_ms
+        }
+
+        /// Get `(withdrawal_count, last_withdrawal_at)` for an account
+        ///
+        /// Counts every withdrawal, approved withdrawal and push payout.
+        #[ink(message)]
+        pub fn get_withdrawal_stats(&self, account: H160) -> (u32, Option<u64>) {
+            (
+                self.withdrawal_counts.get(account).unwrap_or_default(),
+                self.last_withdrawal_at.get(account),
+            )
+        }
+
+        /// Get `(amount, unlocks_at)` parked for a removed beneficiary
+        #[ink(message)]
+        pub fn get_parked(&self, account: H160) -> (U256, u64) {
+            self.parked.get(account).unwrap_or_default()
+        }
+
+        /// Get the removal payout waiting for an account in `claim_escrow`
+        #[ink(message)]
+        pub fn get_escrowed(&self, account: H160) -> U256 {
+            self.claimable.get(account).unwrap_or_default()
+        }
+
+        /// Get every `(account, amount)` waiting in the failed payout queue, in order of failure
+        #[ink(message)]
+        pub fn get_failed_payouts(&self) -> Vec<(H160, U256)> {
+            self.failed_payout_accounts
+                .get_or_default()
+                .into_iter()
+                .map(|account| (account, self.failed_payouts.get(account).unwrap_or_default()))
+                .collect()
+        }
+
+        /// Get how much of an account's pending balance can be withdrawn right now
+        #[ink(message)]
+        pub fn get_withdrawable(&self, account: H160) -> U256 {
+            self.withdrawable(account)
+        }
+
+        /// Get the caller's pending balance
+        #[ink(message)]
+        pub fn get_my_pending_balance(&self) -> U256 {
+            self.get_pending_balance(self.env().caller())
+        }
+
+        /// Get the sum of all pending balances
+        #[ink(message)]
+        pub fn get_total_pending(&self) -> U256 {
+            self.total_pending()
+        }
+
+        /// Get the number of beneficiaries
+        #[ink(message)]
+        pub fn get_beneficiary_count(&self) -> u32 {
+            self.beneficiary_count()
+        }
+
+        /// Get up to `limit` (capped at `MAX_PAGE_SIZE`) beneficiaries starting at `offset`
+        ///
+        /// Out-of-range offsets return an empty list.
+        #[ink(message)]
+        pub fn get_beneficiaries_paged(&self, offset: u32, limit: u32) -> Vec<Beneficiary> {
+            self.get_beneficiary_accounts_paged(offset, limit)
+                .into_iter()
+                .filter_map(|account| self.load_beneficiary(account))
+                .collect()
+        }
+
+        /// Get up to `limit` (capped at `MAX_PAGE_SIZE`) beneficiary accounts starting at `offset`
+        #[ink(message)]
+        pub fn get_beneficiary_accounts_paged(&self, offset: u32, limit: u32) -> Vec<H160> {
+            self.beneficiary_accounts
+                .get_or_default()
+                .iter()
+                .skip(offset as usize)
+                .take(limit.min(MAX_PAGE_SIZE) as usize)
+                .copied()
+                .collect()
+        }
+
+        /// Get beneficiary info
+        #[ink(message)]
+        pub fn get_beneficiary(&self, account: H160) -> Option<Beneficiary> {
+            self.load_beneficiary(account)
+        }
+
+        /// Get every approval an owner has outstanding
+        #[ink(message)]
+        pub fn get_approvals_of(&self, owner: H160) -> Vec<Approval> {
+            self.spenders_of.get(owner)
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(|spender| self.approvals.get((owner, spender)))
+                .collect()
+        }
+
+        /// Get approval amount (zero once the approval has expired)
+        #[ink(message)]
+        pub fn get_approval(&self, owner: H160, spender: H160) -> U256 {
+            self.live_approval(owner, spender)
+                .map(|a| a.amount)
+                .unwrap_or_default()
+        }
+
+        /// Get the stored approval, including its expiry, even if it has lapsed
+        #[ink(message)]
+        pub fn get_approval_details(&self, owner: H160, spender: H160) -> Option<Approval> {
+            self.approvals.get((owner, spender))
+        }
+
+        /// Check whether an approval exists and has not expired
+        #[ink(message)]
+        pub fn is_approval_valid(&self, owner: H160, spender: H160) -> bool {
+            self.live_approval(owner, spender).is_some()
+        }
+
+        /// Get the contract's `(name, description)`
+        #[ink(message)]
+        pub fn get_metadata(&self) -> (Vec<u8>, Vec<u8>) {
+            (self.name.get_or_default(), self.description.get_or_default())
+        }
+
+        /// Get every splitter created through `create_child`, oldest first
+        #[ink(message)]
+        pub fn get_children(&self) -> Vec<H160> {
+            self.children.get_or_default()
+        }
+
+        /// Get every owner-tunable setting
+        #[ink(message)]
+        pub fn get_config(&self) -> Config {
+            self.config.clone()
+        }
+
+        /// Get up to `limit` (capped at `MAX_PAGE_SIZE`) admin log entries, oldest first
+        ///
+        /// Only the last `ADMIN_LOG_CAPACITY` changes are kept; `offset` counts
+        /// from the oldest one still stored.
+        #[ink(message)]
+        pub fn get_admin_log(&self, offset: u32, limit: u32) -> Vec<AdminLogEntry> {
+            let oldest = if self.log_len < ADMIN_LOG_CAPACITY { 0 } else { self.log_head };
+            
+            (offset..self.log_len)
+                .take(limit.min(MAX_PAGE_SIZE) as usize)
+                .filter_map(|index| self.admin_log.get((oldest + index) % ADMIN_LOG_CAPACITY))
+                .collect()
+        }
+
+        /// Get the number of entries in the admin log
+        #[ink(message)]
+        pub fn get_admin_log_len(&self) -> u32 {
+            self.log_len
+        }
+
+        /// Get the maximum number of beneficiaries
+        #[ink(message)]
+        pub fn get_max_beneficiaries(&self) -> u32 {
+            self.config.max_beneficiaries
+        }
+
+        /// Get total shares allocated, in basis points
+        #[ink(message)]
+        pub fn get_total_shares(&self) -> u16 {
+            self.total_shares
+        }
+
+        /// Get the sum of all shares in the contract's share model
+        ///
+        /// Basis points under `Percentage`, weights under `Weighted`. Prefer
+        /// this over `get_total_shares`, which is always 0 for weighted splits.
+        #[ink(message)]
+        pub fn get_total_weight(&self) -> u64 {
+            self.allocated_units()
+        }
+
+        /// Get the share model chosen at construction
+        #[ink(message)]
+        pub fn get_share_model(&self) -> ShareModel {
+            self.share_model
+        }
+
+        /// Get an account's pending balance in a PSP22 token
+        #[ink(message)]
+        pub fn get_token_pending(&self, token: H160, account: H160) -> U256 {
+            self.token_pending.get((token, account)).unwrap_or_default()
+        }
+
+        /// Get the total of a PSP22 token ever received
+        #[ink(message)]
+        pub fn get_token_received(&self, token: H160) -> U256 {
+            self.token_received.get(token).unwrap_or_default()
+        }
+
+        /// Get the portion of a PSP22 token not credited to any beneficiary
+        #[ink(message)]
+        pub fn get_token_undistributed(&self, token: H160) -> U256 {
+            self.token_undistributed.get(token).unwrap_or_default()
+        }
+
+        /// Get running totals for an asset; `None` is the native currency
+        #[ink(message)]
+        pub fn get_asset_stats(&self, token: Option<H160>) -> AssetStats {
+            self.asset_stats.get(token).unwrap_or_default()
+        }
+
+        /// Get every asset that has ever flowed through the contract; `None` is the native currency
+        #[ink(message)]
+        pub fn get_assets(&self) -> Vec<Option<H160>> {
+            self.assets.get_or_default()
+        }
+
+        /// Get funds received but not credited to any beneficiary
+        #[ink(message)]
+        pub fn get_undistributed(&self) -> U256 {
+            self.undistributed_pool
+        }
+
+        /// Get funds waiting in the distribution pool for the next split
+        #[ink(message)]
+        pub fn get_pool_balance(&self) -> U256 {
+            self.distribution_pool
+        }
+
+        /// Show how `amount` would be split right now, without changing state
+        ///
+        /// Returns each beneficiary's credit and the leftover that would go to
+        /// the undistributed pool. Shares the math of a real distribution.
+        #[ink(message)]
+        pub fn preview_distribution(&self, amount: U256) -> (Vec<(H160, U256)>, U256) {
+            let beneficiaries = self.load_beneficiaries();
+            let shares = self.split_amount(&beneficiaries, amount);
+            let credited = shares
+                .iter()
+                .fold(U256::zero(), |acc, share| acc.saturating_add(*share));
+            
+            let allocation = beneficiaries
+                .iter()
+                .map(|b| b.account)
+                .zip(shares)
+                .collect();
+            
+            (allocation, amount.saturating_sub(credited))
+        }
+
+        /// Get the current distribution mode
+        #[ink(message)]
+        pub fn get_distribution_mode(&self) -> DistributionMode {
+            self.config.distribution_mode
+        }
+
+        /// Get the pool size that triggers a split under `Immediate` mode; 0 splits every payment
+        #[ink(message)]
+        pub fn get_distribution_threshold(&self) -> U256 {
+            self.config.distribution_threshold
+        }
+
+        /// Get the keeper fee for manual distributions, in basis points
+        #[ink(message)]
+        pub fn get_keeper_fee_bps(&self) -> u16 {
+            self.config.keeper_fee_bps
+        }
+
+        /// Get the protocol fee taken from incoming payments, in basis points
+        #[ink(message)]
+        pub fn get_fee_bps(&self) -> u16 {
+            self.config.fee_bps
+        }
+
+        /// Get the account that can withdraw protocol fees
+        #[ink(message)]
+        pub fn get_fee_recipient(&self) -> H160 {
+            self.config.fee_recipient
+        }
+
+        /// Get protocol fees collected and not yet withdrawn
+        #[ink(message)]
+        pub fn get_fee_accrued(&self) -> U256 {
+            self.fee_accrued
+        }
+
+        /// Check if deposits or withdrawals are paused
+        #[ink(message)]
+        pub fn is_paused(&self) -> bool {
+            self.deposits_paused || self.withdrawals_paused
+        }
+
+        /// Get the smallest accepted payment
+        #[ink(message)]
+        pub fn get_min_payment(&self) -> U256 {
+            self.config.min_payment
+        }
+
+        /// Get the acceptance window as `(accepting_from, accepting_until)`, both inclusive
+        #[ink(message)]
+        pub fn get_acceptance_window(&self) -> (Option<u64>, Option<u64>) {
+            (self.accepting_from, self.accepting_until)
+        }
+
+        /// Get the withdrawal limit as `(amount, window_ms)`, if any
+        #[ink(message)]
+        pub fn get_withdrawal_limit(&self) -> Option<(U256, u64)> {
+            self.config.withdrawal_limit
+        }
+
+        /// Get the minimum time between withdrawals, in milliseconds
+        #[ink(message)]
+        pub fn get_withdrawal_cooldown(&self) -> u64 {
+            self.config.withdrawal_cooldown_ms
+        }
+
+        /// Get `(withdrawn_in_window, window_start)` for a beneficiary
+        #[ink(message)]
+        pub fn get_withdrawal_window(&self, account: H160) -> (U256, u64) {
+            self.withdrawal_windows.get(account).unwrap_or_default()
+        }
+
+        /// Check whether a payment with this idempotency key was processed
+        #[ink(message)]
+        pub fn is_payment_processed(&self, key: [u8; 32]) -> bool {
+            self.payment_keys.contains(key)
+        }
+
+        /// Get a recorded payment by id
+        #[ink(message)]
+        pub fn get_payment(&self, id: u64) -> Option<PaymentRecord> {
+            self.payments.get(id)
+        }
+
+        /// Get the number of payments ever recorded
+        #[ink(message)]
+        pub fn get_payment_count(&self) -> u64 {
+            self.payment_count
+        }
+
+        /// Get up to `limit` (capped at `MAX_PAGE_SIZE`) payment records starting at id `offset`
+        ///
+        /// Pruned records are skipped.
+        #[ink(message)]
+        pub fn get_payments(&self, offset: u64, limit: u32) -> Vec<PaymentRecord> {
+            let limit = u64::from(limit.min(MAX_PAGE_SIZE));
+            let end = offset.saturating_add(limit).min(self.payment_count);
+            
+            (offset..end)
+                .filter_map(|id| self.payments.get(id))
+                .collect()
+        }
+
+        /// Check if an account is blacklisted
+        #[ink(message)]
+        pub fn is_blacklisted(&self, account: H160) -> bool {
+            self.blacklist.get(account).unwrap_or(false)
+        }
+
+        /// Check if an account is on the payer whitelist
+        #[ink(message)]
+        pub fn is_allowed_payer(&self, account: H160) -> bool {
+            self.allowed_payers.get(account).unwrap_or(false)
+        }
+
+        /// Check if payments in a PSP22 token are accepted
+        #[ink(message)]
+        pub fn is_accepted_token(&self, token: H160) -> bool {
+            self.accepted_tokens.get(token).unwrap_or(false)
+        }
+
+        /// Get every accepted PSP22 token
+        #[ink(message)]
+        pub fn get_accepted_tokens(&self) -> Vec<H160> {
+            self.accepted_token_list.get_or_default()
+        }
+
+        /// Check if the payer whitelist is enforced
+        #[ink(message)]
+        pub fn is_payer_whitelist_enabled(&self) -> bool {
+            self.payer_whitelist_enabled
+        }
+
+        /// Check if payments require full allocation
+        #[ink(message)]
+        pub fn is_strict_allocation(&self) -> bool {
+            self.config.strict_allocation
+        }
+
+        /// Get the campaign's goal, deadline and progress, if one is configured
+        #[ink(message)]
+        pub fn get_campaign_status(&self) -> Option<CampaignStatus> {
+            self.campaign.map(|campaign| CampaignStatus {
+                goal: campaign.goal,
+                deadline: campaign.deadline,
+                raised: self.campaign_raised,
+                finalized: self.campaign_finalized,
+                refunds_open: self.refunds_open,
+            })
+        }
+
+        /// Get the amount a payer has pledged to the campaign
+        #[ink(message)]
+        pub fn get_pledge(&self, payer: H160) -> U256 {
+            self.pledges.get(payer).unwrap_or_default()
+        }
+
+        /// Check if incoming payments are held in escrow
+        #[ink(message)]
+        pub fn is_escrow_enabled(&self) -> bool {
+            self.escrow_enabled
+        }
+
+        /// Get a payment held in escrow by id
+        #[ink(message)]
+        pub fn get_held_payment(&self, id: u64) -> Option<HeldPayment> {
+            self.held_payments.get(id)
+        }
+
+        /// Get the sum of all payments held in escrow
+        #[ink(message)]
+        pub fn get_held_total(&self) -> U256 {
+            self.held_total
+        }
+
+        /// Check if the beneficiary list has been finalized
+        #[ink(message)]
+        pub fn are_shares_locked(&self) -> bool {
+            self.shares_locked
+        }
+
+        /// Get the total paid in by `payer`
+        #[ink(message)]
+        pub fn get_contribution(&self, payer: H160) -> U256 {
+            self.contributions.get(payer).unwrap_or_default()
+        }
+
+        /// Get the number of distinct payers
+        #[ink(message)]
+        pub fn get_contributor_count(&self) -> u32 {
+            self.contributor_count
+        }
+
+        /// Get up to `limit` (capped at `MAX_PAGE_SIZE`) payers starting at `offset`
+        #[ink(message)]
+        pub fn get_contributors(&self, offset: u32, limit: u32) -> Vec<H160> {
+            self.contributors
+                .get_or_default()
+                .iter()
+                .skip(offset as usize)
+                .take(limit.min(MAX_PAGE_SIZE) as usize)
+                .copied()
+                .collect()
+        }
+
+        /// Check whether `pause` leaves withdrawals open
+        #[ink(message)]
+        pub fn get_pause_policy(&self) -> bool {
+            self.withdrawals_allowed_when_paused
+        }
+
+        /// Get the pause state as `(deposits_paused, withdrawals_paused)`
+        #[ink(message)]
+        pub fn get_pause_state(&self) -> (bool, bool) {
+            (self.deposits_paused, self.withdrawals_paused)
+        }
+
+        /// Get contract statistics
+        #[ink(message)]
+        pub fn get_stats(&self) -> (U256, U256, U256) {
+            (
+                self.total_received,
+                self.total_distributed,
+                self.env().balance()
+            )
+        }
+
+        /// Get contract statistics as a named struct
+        #[ink(message)]
+        pub fn get_stats_v2(&self) -> ContractStats {
+            let (total_pending, total_withdrawn) = self.load_beneficiaries()
+                .iter()
+                .fold((U256::zero(), U256::zero()), |(pending, withdrawn), b| {
+                    (
+                        pending.saturating_add(b.pending_balance),
+                        withdrawn.saturating_add(b.total_withdrawn),
+                    )
+                });
+            
+            ContractStats {
+                total_received: self.total_received,
+                total_distributed: self.total_distributed,
+                total_pending,
+                total_withdrawn,
+                contract_balance: self.env().balance(),
+                beneficiary_count: self.beneficiary_count(),
+                payment_count: self.payment_count,
+                last_payment_at: self.last_payment_at,
+                last_distribution_at: self.last_distribution_at,
+                largest_payment: self.largest_payment,
+                paused: self.is_paused(),
+            }
+        }
+
+        /// Recompute the books and check that they balance
+        ///
+        /// Read-only: reports each figure and whether each invariant holds
+        /// rather than failing when one is broken.
+        #[ink(message)]
+        pub fn check_invariants(&self) -> Result<InvariantReport> {
+            let (total_pending, total_withdrawn) = self.load_beneficiaries()
+                .iter()
+                .fold((U256::zero(), U256::zero()), |(pending, withdrawn), b| {
+                    (
+                        pending.saturating_add(b.pending_balance),
+                        withdrawn.saturating_add(b.total_withdrawn),
+                    )
+                });
+            let undistributed = self.undistributed_pool.saturating_add(self.pooled_for_distribution());
+            let contract_balance = self.env().balance();
+            
+            let ledger_balanced = self.ledger_balanced();
+            let owed = total_pending
+                .saturating_add(undistributed)
+                .saturating_add(self.fee_accrued)
+                .saturating_add(self.total_parked)
+                .saturating_add(self.total_claimable)
+                .saturating_add(self.total_failed_payouts)
+                .saturating_add(self.held_total)
+                .saturating_add(self.campaign_raised);
+            
+            Ok(InvariantReport {
+                total_received: self.total_received,
+                total_distributed: self.total_distributed,
+                total_pending,
+                total_withdrawn,
+                undistributed,
+                fee_accrued: self.fee_accrued,
+                total_forfeited: self.total_forfeited,
+                pool_outflows: self.pool_outflows,
+                contract_balance,
+                ledger_balanced,
+                solvent: contract_balance >= owed,
+            })
+        }
+
+        /// Pause the contract if pending balances exceed its balance, returning whether it is solvent
+        ///
+        /// Anyone may call this. Withdrawals run the same check, but their
+        /// `Insolvent` error reverts the pause along with the call, so this is
+        /// how a beneficiary who hit it makes the pause stick.
+        #[ink(message)]
+        pub fn check_solvency(&mut self) -> bool {
+            self.ensure_solvent(U256::zero()).is_ok()
+        }
+
+        /// Check the invariants and pause the contract if any is broken (only owner)
+        ///
+        /// Deposits are always stopped on a violation; withdrawals follow the
+        /// pause policy, as with `pause`. Returns the report either way.
+        #[ink(message)]
+        pub fn assert_invariants(&mut self) -> Result<InvariantReport> {
+            self.ensure_owner()?;
+            
+            let report = self.check_invariants()?;
+            if report.holds() {
+                return Ok(report);
+            }
+            
+            self.deposits_paused = true;
+            if !self.withdrawals_allowed_when_paused {
+                self.withdrawals_paused = true;
+            }
+            
+            self.env().emit_event(InvariantViolated {
+                ledger_balanced: report.ledger_balanced,
+                solvent: report.solvent,
+            });
+            self.env().emit_event(ContractPaused {
+                by: self.env().caller(),
+            });
+            
+            Ok(report)
+        }
+
+        // Private helper functions
+
+        /// Replace the code, keeping storage; deposits must be paused
+        fn upgrade_code(&mut self, code_hash: H256) -> Result<()> {
+            if !self.deposits_paused {
+                return Err(Error::ContractNotPaused);
+            }
+            
+            let old_hash = self.env().own_code_hash().unwrap_or_default();
+            
+            self.env().set_code_hash(&code_hash)
+                .map_err(|_| Error::UpgradeFailed)?;
+            
+            self.env().emit_event(CodeUpgraded {
+                old_hash,
+                new_hash: code_hash,
+            });
+            
+            Ok(())
+        }
+
+        /// Hand ownership to `new_owner`, dropping any pending proposal
+        fn set_owner(&mut self, new_owner: H160) {
+            let previous_owner = self.owner;
+            self.owner = new_owner;
+            self.pending_owner = None;
+            self.log_admin_action(AdminActionKind::TransferOwnership, Some(new_owner));
+            
+            self.env().emit_event(OwnershipTransferred {
+                previous_owner,
+                new_owner,
+            });
+        }
+
+        /// Change an existing beneficiary's share, keeping the total within `MAX_SHARE_BPS`
+        fn set_share(&mut self, account: H160, share_bps: u16) -> Result<()> {
+            if self.share_model != ShareModel::Percentage {
+                return Err(Error::WrongShareModel);
+            }
+            
+            let mut beneficiary = self.beneficiary_data
+                .get(account)
+                .ok_or(Error::BeneficiaryNotFound)?;
+            
+            let old_share_bps = beneficiary.share_bps;
+            let available = MAX_SHARE_BPS
+                .saturating_sub(self.total_shares)
+                .saturating_add(old_share_bps);
+            if share_bps == 0 || share_bps > available || beneficiary.kind != ShareKind::Proportional {
+                return Err(Error::InvalidShare {
+                    requested: share_bps,
+                    available,
+                });
+            }
+            
+            beneficiary.share_bps = share_bps;
+            self.beneficiary_data.insert(account, &beneficiary);
+            self.total_shares = self.total_shares
+                .saturating_sub(old_share_bps)
+                .saturating_add(share_bps);
+            self.record_config();
+            
+            self.env().emit_event(BeneficiaryShareUpdated {
+                beneficiary: account,
+                old_share_bps,
+                new_share_bps: share_bps,
+            });
+            
+            Ok(())
+        }
+
+        /// Carry out an admin action approved by the timelock or the council
+        fn perform_action(&mut self, action: AdminAction) -> Result<()> {
+            match action {
+                AdminAction::RemoveBeneficiary(account) => {
+                    self.ensure_not_paused()?;
+                    self.ensure_shares_unlocked()?;
+                    self.remove_beneficiary_now(account, false)
+                }
+                AdminAction::SetShare(account, share_bps) => {
+                    self.ensure_not_paused()?;
+                    self.ensure_shares_unlocked()?;
+                    self.set_share(account, share_bps)
+                }
+                AdminAction::Upgrade(code_hash) => self.upgrade_code(code_hash),
+                AdminAction::TransferOwnership(new_owner) => {
+                    Self::ensure_not_zero(new_owner)?;
+                    self.set_owner(new_owner);
+                    Ok(())
+                }
+            }
+        }
+
+        /// Validate and store the contract's name and description
+        fn store_metadata(&mut self, name: Vec<u8>, description: Vec<u8>) -> Result<()> {
+            if name.len() > MAX_NAME_LEN || description.len() > MAX_DESCRIPTION_LEN {
+                return Err(Error::MetadataTooLong);
+            }
+            
+            self.name.set(&name);
+            self.description.set(&description);
+            Ok(())
+        }
+
+        /// Check the constraints between settings and store `config`
+        fn apply_config(&mut self, config: Config) -> Result<()> {
+            if config.fee_bps > MAX_FEE_BPS || config.keeper_fee_bps > MAX_KEEPER_FEE_BPS {
+                return Err(Error::FeeTooHigh);
+            }
+            Self::ensure_not_zero(config.fee_recipient)?;
+            if config.max_beneficiaries < self.beneficiary_count() {
+                return Err(Error::TooManyBeneficiaries);
+            }
+            if let Some((amount, window_ms)) = config.withdrawal_limit {
+                if amount.is_zero() || window_ms == 0 {
+                    return Err(Error::ZeroAmount);
+                }
+            }
+            
+            self.config = config;
+            self.log_admin_action(AdminActionKind::UpdateConfig, None);
+            Ok(())
+        }
+
+        /// Append to the admin log, overwriting the oldest entry once it is full
+        fn log_admin_action(&mut self, action: AdminActionKind, target: Option<H160>) {
+            self.admin_log.insert(self.log_head, &AdminLogEntry {
+                actor: self.env().caller(),
+                action,
+                target,
+                timestamp: self.env().block_timestamp(),
+            });
+            self.log_head = (self.log_head + 1) % ADMIN_LOG_CAPACITY;
+            self.log_len = self.log_len.saturating_add(1).min(ADMIN_LOG_CAPACITY);
+        }
+
+        /// Validate and store the council
+        fn configure_council(&mut self, members: Vec<H160>, threshold: u8) -> Result<()> {
+            let distinct = members
+                .iter()
+                .enumerate()
+                .all(|(index, member)| *member != H160::zero() && !members[..index].contains(member));
+            if threshold == 0 || usize::from(threshold) > members.len() || !distinct {
+                return Err(Error::InvalidThreshold);
+            }
+            
+            self.council.set(&members);
+            self.threshold = threshold;
+            
+            self.env().emit_event(CouncilConfigured {
+                members,
+                threshold,
+            });
+            
+            Ok(())
+        }
+
+        /// Drop `id` from the admin action queue
+        fn remove_pending_action(&mut self, id: u64) {
+            self.pending_actions.remove(id);
+            let mut pending_action_ids = self.pending_action_ids.get_or_default();
+            pending_action_ids.retain(|pending| *pending != id);
+            self.pending_action_ids.set(&pending_action_ids);
+        }
+
+        /// Validate and append a new beneficiary
+        fn insert_beneficiary(&mut self, account: H160, share_bps: u16) -> Result<()> {
+            if self.share_model != ShareModel::Percentage {
+                return Err(Error::WrongShareModel);
+            }
+            
+            Self::ensure_not_zero(account)?;
+            self.ensure_not_blacklisted(account)?;
+            
+            let available = MAX_SHARE_BPS.saturating_sub(self.total_shares);
+            if share_bps == 0 || share_bps > available {
+                return Err(Error::InvalidShare {
+                    requested: share_bps,
+                    available,
+                });
+            }
+            
+            // Check if beneficiary already exists
+            if self.beneficiary_data.contains(account) {
+                return Err(Error::BeneficiaryAlreadyExists);
+            }
+            
+            if self.beneficiary_count() >= self.config.max_beneficiaries {
+                return Err(Error::TooManyBeneficiaries);
+            }
+            
+            self.store_new_beneficiary(account, share_bps, 0, ShareKind::Proportional);
+            self.record_config();
+            
+            Ok(())
+        }
+
+        /// Append an already validated beneficiary with default settings
+        fn store_new_beneficiary(&mut self, account: H160, share_bps: u16, weight: u64, kind: ShareKind) {
+            let beneficiary = Beneficiary {
+                account,
+                share_bps,
+                weight,
+                pending_balance: U256::zero(),
+                total_withdrawn: U256::zero(),
+                payout_address: None,
+                frozen: false,
+                last_withdrawal_at: None,
+                withdrawal_count: 0,
+                vesting: None,
+                locked_until: None,
+                priority: 0,
+                cap_per_distribution: None,
+                kind,
+                lifetime_cap: None,
+                total_credited: U256::zero(),
+                total_tips: U256::zero(),
+                notify_on_payout: false,
+                forward_as_payment: false,
+                label: None,
+            };
+            
+            self.beneficiary_data.insert(account, &beneficiary);
+            let mut beneficiary_accounts = self.beneficiary_accounts.get_or_default();
+            beneficiary_accounts.push(account);
+            self.beneficiary_accounts.set(&beneficiary_accounts);
+            self.total_shares = self.total_shares.saturating_add(share_bps);
+            self.total_weight = self.total_weight.saturating_add(weight);
+            self.log_admin_action(AdminActionKind::AddBeneficiary, Some(account));
+            
+            self.env().emit_event(BeneficiaryAdded {
+                beneficiary: account,
+                share_bps,
+                weight,
+                kind,
+                added_by: self.env().caller(),
+            });
+        }
+
+        /// Distribute funds among beneficiaries
+        ///
+        /// Whatever is not credited to a beneficiary (unassigned shares and
+        /// rounding dust) is added to the undistributed pool, so that
+        /// `total_received == total_distributed + undistributed_pool` holds.
+        fn distribute_funds(&mut self, amount: U256, keeper_fee: U256, protocol_fee: U256) -> Result<Vec<(H160, U256)>> {
+            let beneficiaries = self.load_beneficiaries();
+            if beneficiaries.is_empty() {
+                self.undistributed_pool = self.undistributed_pool
+                    .checked_add(amount)
+                    .ok_or(Error::ArithmeticOverflow)?;
+                return Ok(Vec::new());
+            }
+
+            let shares = self.split_amount(&beneficiaries, amount);
+            let mut credited = U256::zero();
+            let mut allocation = Vec::new();
+            let distribution_id = self.distribution_count;
+            self.distribution_count = distribution_id.saturating_add(1);
+
+            for (beneficiary, share_amount) in beneficiaries.iter().zip(shares) {
+                self.credit_share(distribution_id, beneficiary, share_amount)?;
+                credited = credited.checked_add(share_amount).ok_or(Error::ArithmeticOverflow)?;
+                
+                if !share_amount.is_zero() {
+                    allocation.push((beneficiary.account, share_amount));
+                }
+            }
+            
+            self.total_distributed = self.total_distributed
+                .checked_add(credited)
+                .ok_or(Error::ArithmeticOverflow)?;
+            self.last_distribution_at = Some(self.env().block_timestamp());
+            self.update_asset_stats(None, |stats| {
+                stats.total_distributed = stats.total_distributed
+                    .checked_add(credited)
+                    .ok_or(Error::ArithmeticOverflow)?;
+                stats.total_pending = stats.total_pending
+                    .checked_add(credited)
+                    .ok_or(Error::ArithmeticOverflow)?;
+                Ok(())
+            })?;
+            self.undistributed_pool = amount
+                .checked_sub(credited)
+                .and_then(|leftover| self.undistributed_pool.checked_add(leftover))
+                .ok_or(Error::ArithmeticOverflow)?;
+            self.distributions.insert(distribution_id, &DistributionRecord {
+                amount,
+                timestamp: self.env().block_timestamp(),
+                beneficiary_count: beneficiaries.len() as u32,
+                total_shares_at_time: self.total_shares,
+                config_version: self.config_version,
+            });
+            
+            self.env().emit_event(FundsDistributed {
+                distribution_id,
+                config_version: self.config_version,
+                total_amount: credited,
+                beneficiary_count: self.beneficiary_count(),
+                keeper_fee,
+                protocol_fee,
+            });
+            
+            Ok(allocation)
+        }
+
+        /// Add `amount` to a beneficiary's pending balance as part of `distribution_id`
+        ///
+        /// Ledger totals are left to the caller.
+        fn credit_share(&mut self, distribution_id: u64, beneficiary: &Beneficiary, amount: U256) -> Result<()> {
+            let pending = beneficiary.pending_balance
+                .checked_add(amount)
+                .ok_or(Error::ArithmeticOverflow)?;
+            self.balances.insert(beneficiary.account, &(pending, beneficiary.total_withdrawn));
+            
+            if amount.is_zero() {
+                return Ok(());
+            }
+            let lifetime_credited = beneficiary.total_credited
+                .checked_add(amount)
+                .ok_or(Error::ArithmeticOverflow)?;
+            self.credited.insert(beneficiary.account, &lifetime_credited);
+            
+            self.env().emit_event(ShareCredited {
+                distribution_id,
+                beneficiary: beneficiary.account,
+                amount,
+            });
+            
+            if let Some(lifetime_cap) = beneficiary.lifetime_cap {
+                if beneficiary.total_credited < lifetime_cap && lifetime_credited >= lifetime_cap {
+                    self.env().emit_event(CapReached {
+                        beneficiary: beneficiary.account,
+                        lifetime_cap,
+                    });
+                }
+            }
+            
+            Ok(())
+        }
+
+        /// A beneficiary's credit in a chunked distribution, advancing `progress.units_before`
+        ///
+        /// Flooring the running total rather than each share keeps the sum
+        /// equal to the floor of the allocated fraction of the amount.
+        fn chunk_share(&self, progress: &mut DistributionProgress, beneficiary: &Beneficiary) -> U256 {
+            if progress.denominator == 0 {
+                return U256::zero();
+            }
+            
+            let denominator = U256::from(progress.denominator);
+            let units_after = progress.units_before.saturating_add(self.share_units(beneficiary));
+            let (before, _) = Self::mul_div(progress.amount, progress.units_before, denominator);
+            let (after, _) = Self::mul_div(progress.amount, units_after, denominator);
+            progress.units_before = units_after;
+            
+            after.saturating_sub(before)
+        }
+
+        /// Debit the caller's pending balance and send `amount` to `destination`
+        ///
+        /// Without an explicit destination the beneficiary's payout address is used.
+        fn withdraw_own(&mut self, destination: Option<H160>, amount: U256) -> Result<()> {
+            let caller = self.env().caller();
+            
+            if amount.is_zero() {
+                return Err(Error::ZeroAmount);
+            }
+            
+            let beneficiary = self.beneficiary_data
+                .get(caller)
+                .ok_or(Error::Unauthorized)?;
+            if beneficiary.frozen {
+                return Err(Error::BeneficiaryFrozen);
+            }
+            
+            let destination = destination.unwrap_or_else(|| beneficiary.payout_destination());
+            
+            self.release_funds(caller, destination, amount)
+        }
+
+        /// Claim the full unlocked pending balance of `account` to its payout destination
+        fn claim_beneficiary(&mut self, account: H160) -> Result<U256> {
+            let beneficiary = self.beneficiary_data
+                .get(account)
+                .ok_or(Error::BeneficiaryNotFound)?;
+            if beneficiary.frozen {
+                return Err(Error::BeneficiaryFrozen);
+            }
+            
+            let amount = self.withdrawable(account);
+            if amount.is_zero() {
+                return Err(Error::NoFundsAvailable);
+            }
+            
+            self.release_funds(account, beneficiary.payout_destination(), amount)?;
+            self.notify_payout(&beneficiary, amount);
+            
+            Ok(amount)
+        }
+
+        /// Send `amount` to `destination`, through its `receive_payment` when `forward` is set
+        fn send_funds(&self, destination: H160, amount: U256, forward: bool) -> Result<()> {
+            if !forward {
+                return self.env().transfer(destination, amount)
+                    .map_err(|_| Error::TransferFailed);
+            }
+            
+            build_call::<Environment>()
+                .call(destination)
+                .transferred_value(amount)
+                .exec_input(ExecutionInput::new(Selector::new(ink::selector_bytes!("receive_payment"))))
+                .returns::<Result<()>>()
+                .try_invoke()
+                .ok()
+                .and_then(|result| result.ok())
+                .and_then(|result| result.ok())
+                .ok_or(Error::TransferFailed)
+        }
+
+        /// Call `on_split_payout(amount)` on the payout destination if the beneficiary asked for it
+        fn notify_payout(&mut self, beneficiary: &Beneficiary, amount: U256) {
+            let destination = beneficiary.payout_destination();
+            if !beneficiary.notify_on_payout || !self.env().is_contract(&destination) {
+                return;
+            }
+            
+            let result = build_call::<Environment>()
+                .call(destination)
+                .ref_time_limit(NOTIFY_REF_TIME_LIMIT)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(ink::selector_bytes!("on_split_payout")))
+                        .push_arg(amount),
+                )
+                .returns::<()>()
+                .try_invoke();
+            
+            if !matches!(result, Ok(Ok(()))) {
+                self.env().emit_event(CallbackFailed {
+                    beneficiary: beneficiary.account,
+                    destination,
+                    amount,
+                });
+            }
+        }
+
+        /// Send `amount` of `account`'s pending balance to `destination`
+        ///
+        /// The transfer happens before the balance is debited, so a failed
+        /// transfer leaves the balance intact even inside a batch that does not
+        /// revert.
+        fn release_funds(&mut self, account: H160, destination: H160, amount: U256) -> Result<()> {
+            self.release_funds_with_fee(account, destination, amount, destination, U256::zero())
+        }
+
+        /// `release_funds`, paying `fee` of `amount` to `fee_to` instead of `destination`
+        fn release_funds_with_fee(
+            &mut self,
+            account: H160,
+            destination: H160,
+            amount: U256,
+            fee_to: H160,
+            fee: U256,
+        ) -> Result<()> {
+            self.ensure_not_blacklisted(account)?;
+            
+            let available = self.withdrawable(account);
+            if available < amount {
+                return Err(Error::InsufficientBalance {
+                    requested: amount,
+                    available,
+                });
+            }
+            
+            let (pending, withdrawn) = self.balances.get(account).unwrap_or_default();
+            let remaining_pending = pending.checked_sub(amount).ok_or(Error::ArithmeticOverflow)?;
+            let withdrawn = withdrawn.checked_add(amount).ok_or(Error::ArithmeticOverflow)?;
+            let net_amount = amount.checked_sub(fee).ok_or(Error::ArithmeticOverflow)?;
+            
+            self.ensure_release_allowed(account, amount)?;
+            self.ensure_solvent(amount)?;
+            
+            let forward = self.beneficiary_data
+                .get(account)
+                .is_some_and(|b| b.forward_as_payment && b.payout_destination() == destination);
+            self.send_funds(destination, net_amount, forward)?;
+            if !fee.is_zero() {
+                self.send_funds(fee_to, fee, false)?;
+            }
+            
+            self.balances.insert(account, &(remaining_pending, withdrawn));
+            self.record_release(account, amount)?;
+            
+            self.env().emit_event(Withdrawal {
+                beneficiary: account,
+                destination,
+                amount,
+                remaining_pending,
+                triggered_by: self.env().caller(),
+            });
+            
+            Ok(())
+        }
+
+        /// Send `amount` of `account`'s pending PSP22 balance to its payout destination
+        fn release_token(&mut self, token: H160, account: H160, amount: U256) -> Result<()> {
+            self.ensure_not_blacklisted(account)?;
+            
+            let pending = self.token_pending.get((token, account)).unwrap_or_default();
+            if pending < amount {
+                return Err(Error::InsufficientBalance {
+                    requested: amount,
+                    available: pending,
+                });
+            }
+            
+            let destination = match self.beneficiary_data.get(account) {
+                Some(beneficiary) if beneficiary.frozen => return Err(Error::BeneficiaryFrozen),
+                Some(beneficiary) => beneficiary.payout_destination(),
+                None => account,
+            };
+            
+            self.psp22_transfer(token, destination, amount)?;
+            
+            self.token_pending.insert((token, account), &pending.checked_sub(amount).ok_or(Error::ArithmeticOverflow)?);
+            self.update_asset_stats(Some(token), |stats| {
+                stats.total_pending = stats.total_pending.saturating_sub(amount);
+                stats.total_withdrawn = stats.total_withdrawn
+                    .checked_add(amount)
+                    .ok_or(Error::ArithmeticOverflow)?;
+                Ok(())
+            })?;
+            
+            self.env().emit_event(TokenWithdrawal {
+                token,
+                beneficiary: account,
+                destination,
+                amount,
+            });
+            
+            Ok(())
+        }
+
+        /// Call `PSP22::transfer` on `token`, mapping any failure to `TransferFailed`
+        fn psp22_transfer(&self, token: H160, to: H160, value: U256) -> Result<()> {
+            build_call::<Environment>()
+                .call(token)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(ink::selector_bytes!("PSP22::transfer")))
+                        .push_arg(to)
+                        .push_arg(value)
+                        .push_arg(Vec::<u8>::new()),
+                )
+                .returns::<core::result::Result<(), PSP22Error>>()
+                .try_invoke()
+                .ok()
+                .and_then(|result| result.ok())
+                .and_then(|result| result.ok())
+                .ok_or(Error::TransferFailed)
+        }
+
+        /// Call `PSP22::transfer_from` on `token`, mapping any failure to `TransferFailed`
+        fn psp22_transfer_from(&self, token: H160, from: H160, to: H160, value: U256) -> Result<()> {
+            build_call::<Environment>()
+                .call(token)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(ink::selector_bytes!("PSP22::transfer_from")))
+                        .push_arg(from)
+                        .push_arg(to)
+                        .push_arg(value)
+                        .push_arg(Vec::<u8>::new()),
+                )
+                .returns::<core::result::Result<(), PSP22Error>>()
+                .try_invoke()
+                .ok()
+                .and_then(|result| result.ok())
+                .and_then(|result| result.ok())
+                .ok_or(Error::TransferFailed)
+        }
+
+        /// Check the lock, cooldown and withdrawal limit before `amount` leaves `account`
+        fn ensure_release_allowed(&self, account: H160, amount: U256) -> Result<()> {
+            let now = self.env().block_timestamp();
+            
+            let locked_until = self.beneficiary_data.get(account).and_then(|b| b.locked_until);
+            if let Some(until) = locked_until {
+                if now < until {
+                    return Err(Error::FundsLocked(until));
+                }
+            }
+            
+            if let Some(last) = self.last_withdrawal_at.get(account) {
+                let ready_at = last.saturating_add(self.config.withdrawal_cooldown_ms);
+                if now < ready_at {
+                    return Err(Error::CooldownActive {
+                        remaining_ms: ready_at - now,
+                    });
+                }
+            }
+            
+            if let Some((limit, window_ms)) = self.config.withdrawal_limit {
+                let (used, window_start) = self.current_window(account, window_ms);
+                if used.saturating_add(amount) > limit {
+                    return Err(Error::WithdrawalLimitExceeded {
+                        retry_after: window_start.saturating_add(window_ms),
+                    });
+                }
+            }
+            
+            Ok(())
+        }
+
+        /// Record that `amount` left `account`, for the cooldown, withdrawal limit and stats
+        fn record_release(&mut self, account: H160, amount: U256) -> Result<()> {
+            self.last_withdrawal_at.insert(account, &self.env().block_timestamp());
+            let count = self.withdrawal_counts.get(account).unwrap_or_default();
+            self.withdrawal_counts.insert(account, &count.saturating_add(1));
+            self.update_asset_stats(None, |stats| {
+                stats.total_pending = stats.total_pending.saturating_sub(amount);
+                stats.total_withdrawn = stats.total_withdrawn
+                    .checked_add(amount)
+                    .ok_or(Error::ArithmeticOverflow)?;
+                Ok(())
+            })?;
+            
+            if let Some((_, window_ms)) = self.config.withdrawal_limit {
+                let (used, window_start) = self.current_window(account, window_ms);
+                self.withdrawal_windows.insert(account, &(used.saturating_add(amount), window_start));
+            }
+            
+            Ok(())
+        }
+
+        /// `(withdrawn_in_window, window_start)`, starting a fresh window once the old one has ended
+        fn current_window(&self, account: H160, window_ms: u64) -> (U256, u64) {
+            let now = self.env().block_timestamp();
+            
+            match self.withdrawal_windows.get(account) {
+                Some((used, start)) if now < start.saturating_add(window_ms) => (used, start),
+                _ => (U256::zero(), now),
+            }
+        }
+
+        /// Validate the transferred value of a payment and accept it
+        fn process_payment(&mut self, memo: Option<Vec<u8>>) -> Result<DistributionSummary> {
+            self.ensure_not_retired()?;
+            self.ensure_deposits_open()?;
+            self.ensure_accepting()?;
+            
+            let amount = self.env().transferred_value();
+            let caller = self.env().caller();
+            
+            // Erroring reverts the call, bouncing the value back to the payer
+            if self.payer_whitelist_enabled && !self.is_allowed_payer(caller) {
+                return Err(Error::Unauthorized);
+            }
+            self.ensure_not_blacklisted(caller)?;
+            
+            if amount.is_zero() {
+                return Err(Error::ZeroAmount);
+            }
+            
+            if amount < self.config.min_payment {
+                return Err(Error::PaymentTooSmall);
+            }
+            
+            if self.config.strict_allocation && !self.is_fully_allocated() {
+                return Err(Error::SharesNotFullyAllocated);
+            }
+            
+            if let Some(campaign) = self.campaign {
+                if self.refunds_open {
+                    return Err(Error::CampaignEnded);
+                }
+                if !self.campaign_finalized {
+                    if self.env().block_timestamp() >= campaign.deadline {
+                        return Err(Error::CampaignEnded);
+                    }
+                    self.pledge(caller, amount, memo)?;
+                    return Ok(DistributionSummary::held(amount));
+                }
+            }
+            
+            if self.escrow_enabled {
+                self.hold_payment(caller, amount, memo)?;
+                return Ok(DistributionSummary::held(amount));
+            }
+            
+            let summary = self.accept_funds(amount)?;
+            self.record_payment(caller, amount, memo.clone());
+            self.record_contribution(caller, amount)?;
+            
+            self.env().emit_event(FundsReceived {
+                from: caller,
+                amount,
+                fee: summary.fee,
+                net_amount: amount.saturating_sub(summary.fee),
+                memo,
+            });
+            
+            Ok(summary)
+        }
+
+        /// Record a campaign pledge; it is only accepted once the goal is met
+        fn pledge(&mut self, payer: H160, amount: U256, memo: Option<Vec<u8>>) -> Result<()> {
+            let pledged = self.pledges.get(payer).unwrap_or_default();
+            self.pledges.insert(payer, &pledged.checked_add(amount).ok_or(Error::ArithmeticOverflow)?);
+            self.campaign_raised = self.campaign_raised
+                .checked_add(amount)
+                .ok_or(Error::ArithmeticOverflow)?;
+            
+            self.record_payment(payer, amount, memo);
+            self.record_contribution(payer, amount)?;
+            
+            self.env().emit_event(Pledged {
+                payer,
+                amount,
+            });
+            
+            Ok(())
+        }
+
+        /// Validate and store both acceptance window bounds
+        fn set_acceptance_window(&mut self, accepting_from: Option<u64>, accepting_until: Option<u64>) -> Result<()> {
+            if let (Some(from), Some(until)) = (accepting_from, accepting_until) {
+                if until < from {
+                    return Err(Error::InvalidAcceptanceWindow);
+                }
+            }
+            
+            self.accepting_from = accepting_from;
+            self.accepting_until = accepting_until;
+            
+            self.env().emit_event(AcceptanceWindowChanged {
+                accepting_from,
+                accepting_until,
+            });
+            
+            Ok(())
+        }
+
+        /// Store a payment in escrow instead of accepting it
+        fn hold_payment(&mut self, payer: H160, amount: U256, memo: Option<Vec<u8>>) -> Result<()> {
+            let id = self.held_count;
+            
+            self.held_total = self.held_total
+                .checked_add(amount)
+                .ok_or(Error::ArithmeticOverflow)?;
+            self.held_payments.insert(id, &HeldPayment {
+                id,
+                payer,
+                amount,
+            });
+            self.held_count = id.saturating_add(1);
+            
+            self.env().emit_event(PaymentHeld {
+                id,
+                payer,
+                amount,
+                memo,
+            });
+            
+            Ok(())
+        }
+
+        /// Add `amount` to the payer's running contribution total
+        fn record_contribution(&mut self, payer: H160, amount: U256) -> Result<()> {
+            match self.contributions.get(payer) {
+                Some(total) => {
+                    self.contributions.insert(payer, &total.checked_add(amount).ok_or(Error::ArithmeticOverflow)?);
+                }
+                None => {
+                    self.contributions.insert(payer, &amount);
+                    let mut contributors = self.contributors.get_or_default();
+                    contributors.push(payer);
+                    self.contributors.set(&contributors);
+                    self.contributor_count = self.contributor_count.saturating_add(1);
+                }
+            }
+            
+            Ok(())
+        }
+
+        /// Append a payment to the on-chain history, dropping the oldest
+        /// record once `max_history` is exceeded
+        fn record_payment(&mut self, payer: H160, amount: U256, memo: Option<Vec<u8>>) {
+            let id = self.payment_count;
+            let timestamp = self.env().block_timestamp();
+            
+            self.payments.insert(id, &PaymentRecord {
+                payer,
+                amount,
+                timestamp,
+                block: self.env().block_number(),
+                memo,
+            });
+            self.payment_count = id.saturating_add(1);
+            self.last_payment_at = Some(timestamp);
+            self.largest_payment = self.largest_payment.max(amount);
+            
+            if self.config.max_history > 0 {
+                let keep_from = self.payment_count.saturating_sub(self.config.max_history);
+                self.prune_payment_records(keep_from, MAX_PRUNE_PER_PAYMENT);
+            }
+        }
+
+        /// Delete up to `limit` of the oldest payment records with an id below `end`
+        fn prune_payment_records(&mut self, end: u64, limit: u64) {
+            let end = end.min(self.payment_history_start.saturating_add(limit));
+            while self.payment_history_start < end {
+                self.payments.remove(self.payment_history_start);
+                self.payment_history_start += 1;
+            }
+        }
+
+        /// Record incoming funds, take the protocol fee and split or pool the
+        /// rest per the distribution mode, returning how `amount` was allocated
+        fn accept_funds(&mut self, amount: U256) -> Result<DistributionSummary> {
+            self.total_received = self.total_received
+                .checked_add(amount)
+                .ok_or(Error::ArithmeticOverflow)?;
+            self.update_asset_stats(None, |stats| {
+                stats.total_received = stats.total_received
+                    .checked_add(amount)
+                    .ok_or(Error::ArithmeticOverflow)?;
+                Ok(())
+            })?;
+            
+            let (fee, _) = Self::mul_div(amount, u64::from(self.config.fee_bps), U256::from(MAX_SHARE_BPS));
+            let net_amount = amount.saturating_sub(fee);
+            self.fee_accrued = self.fee_accrued
+                .checked_add(fee)
+                .ok_or(Error::ArithmeticOverflow)?;
+            
+            let threshold = self.config.distribution_threshold;
+            let credited = match self.config.distribution_mode {
+                // Distribute the received funds immediately
+                DistributionMode::Immediate if threshold.is_zero() => {
+                    self.distribute_funds(net_amount, U256::zero(), fee)?
+                }
+                // Pool them and split the whole pool once it reaches the threshold
+                DistributionMode::Immediate => {
+                    self.distribution_pool = self.distribution_pool
+                        .checked_add(net_amount)
+                        .ok_or(Error::ArithmeticOverflow)?;
+                    if self.distribution_pool >= threshold {
+                        let pooled = core::mem::take(&mut self.distribution_pool);
+                        self.distribute_funds(pooled, U256::zero(), U256::zero())?
+                    } else {
+                        Vec::new()
+                    }
+                }
+                // Hold them until someone calls `distribute`
+                DistributionMode::Manual => {
+                    self.distribution_pool = self.distribution_pool
+                        .checked_add(net_amount)
+                        .ok_or(Error::ArithmeticOverflow)?;
+                    Vec::new()
+                }
+            };
+            let total_credited = credited
+                .iter()
+                .fold(U256::zero(), |acc, (_, share)| acc.saturating_add(*share));
+            
+            Ok(DistributionSummary {
+                gross: amount,
+                fee,
+                credited,
+                undistributed: net_amount.saturating_sub(total_credited),
+            })
+        }
+
+        /// Apply `update` to an asset's running totals, listing the asset on first use
+        fn update_asset_stats(
+            &mut self,
+            asset: Option<H160>,
+            update: impl FnOnce(&mut AssetStats) -> Result<()>,
+        ) -> Result<()> {
+            let mut stats = match self.asset_stats.get(asset) {
+                Some(stats) => stats,
+                None => {
+                    let mut assets = self.assets.get_or_default();
+                    assets.push(asset);
+                    self.assets.set(&assets);
+                    AssetStats::default()
+                }
+            };
+            
+            update(&mut stats)?;
+            self.asset_stats.insert(asset, &stats);
+            Ok(())
+        }
+
+        /// Distribution pool plus what a chunked distribution has yet to credit
+        fn pooled_for_distribution(&self) -> U256 {
+            let in_flight = self.distribution_progress
+                .as_ref()
+                .map(|progress| progress.amount.saturating_sub(progress.credited))
+                .unwrap_or_default();
+            
+            self.distribution_pool.saturating_add(in_flight)
+        }
+
+        /// Whether every unit received is accounted for as distributed, pooled or paid out
+        fn ledger_balanced(&self) -> bool {
+            self.total_received.saturating_add(self.total_forfeited)
+                == self.total_distributed
+                    .saturating_add(self.undistributed_pool)
+                    .saturating_add(self.pooled_for_distribution())
+                    .saturating_add(self.fee_accrued)
+                    .saturating_add(self.pool_outflows)
+        }
+
+        /// Circuit breaker run before native funds leave a beneficiary's balance
+        ///
+        /// Fails with `Insolvent` when the contract cannot cover `amount` or
+        /// the running native `total_pending` plus parked, escrowed and failed
+        /// payouts exceeds its balance, after
+        /// pausing deposits and withdrawals so later callers do not race for
+        /// what is left. The owner can `unpause` once the cause is fixed.
+        fn ensure_solvent(&mut self, amount: U256) -> Result<()> {
+            let balance = self.env().balance();
+            let owed = self.asset_stats
+                .get(None)
+                .unwrap_or_default()
+                .total_pending
+                .saturating_add(self.total_parked)
+                .saturating_add(self.total_claimable)
+                .saturating_add(self.total_failed_payouts);
+            if amount <= balance && owed <= balance {
+                return Ok(());
+            }
+            
+            self.deposits_paused = true;
+            self.withdrawals_paused = true;
+            
+            self.env().emit_event(InvariantViolated {
+                ledger_balanced: self.ledger_balanced(),
+                solvent: false,
+            });
+            self.env().emit_event(ContractPaused {
+                by: self.env().caller(),
+            });
+            
+            Err(Error::Insolvent)
+        }
+
+        /// Sum of all beneficiaries' pending balances
+        fn total_pending(&self) -> U256 {
+            self.load_beneficiaries()
+                .iter()
+                .fold(U256::zero(), |acc, b| acc.saturating_add(b.pending_balance))
+        }
+
+        /// Number of beneficiaries currently in the list
+        fn beneficiary_count(&self) -> u32 {
+            self.beneficiary_accounts.get_or_default().len() as u32
+        }
+
+        /// Load every beneficiary record with its balances, in insertion order
+        fn load_beneficiaries(&self) -> Vec<Beneficiary> {
+            self.beneficiary_accounts
+                .get_or_default()
+                .iter()
+                .filter_map(|account| self.load_beneficiary(*account))
+                .collect()
+        }
+
+        /// Ensure a label is UTF-8 and at most `MAX_LABEL_LEN` bytes
+        fn ensure_valid_label(label: &[u8]) -> Result<()> {
+            if label.len() > MAX_LABEL_LEN {
+                return Err(Error::LabelTooLong);
+            }
+            core::str::from_utf8(label)
+                .map(|_| ())
+                .map_err(|_| Error::InvalidLabel)
+        }
+
+        /// Validate and store `account`'s label, clearing it for `None`
+        fn store_label(&mut self, account: H160, label: Option<Vec<u8>>) -> Result<()> {
+            match &label {
+                Some(bytes) => {
+                    Self::ensure_valid_label(bytes)?;
+                    self.labels.insert(account, bytes);
+                }
+                None => self.labels.remove(account),
+            }
+            
+            self.env().emit_event(BeneficiaryLabelChanged {
+                beneficiary: account,
+                label,
+            });
+            
+            Ok(())
+        }
+
+        /// Load a beneficiary record with its balances filled in from `balances`
+        fn load_beneficiary(&self, account: H160) -> Option<Beneficiary> {
+            let mut beneficiary = self.beneficiary_data.get(account)?;
+            let (pending, withdrawn) = self.balances.get(account).unwrap_or_default();
+            
+            beneficiary.pending_balance = pending;
+            beneficiary.total_withdrawn = withdrawn;
+            beneficiary.last_withdrawal_at = self.last_withdrawal_at.get(account);
+            beneficiary.withdrawal_count = self.withdrawal_counts.get(account).unwrap_or_default();
+            beneficiary.total_credited = self.credited.get(account).unwrap_or_default();
+            beneficiary.total_tips = self.tips.get(account).unwrap_or_default();
+            beneficiary.label = self.labels.get(account);
+            
+            Some(beneficiary)
+        }
+
+        /// Remove a beneficiary immediately, paying, parking or forfeiting their balance
+        fn remove_beneficiary_now(&mut self, account: H160, forfeit_unvested: bool) -> Result<()> {
+            let withdrawable = self.withdrawable(account);
+            let beneficiary = self.beneficiary_data
+                .take(account)
+                .ok_or(Error::BeneficiaryNotFound)?;
+            
+            // Keep insertion order, which rounding tie-breaks depend on
+            let mut beneficiary_accounts = self.beneficiary_accounts.get_or_default();
+            if let Some(position) = beneficiary_accounts.iter().position(|a| *a == account) {
+                beneficiary_accounts.remove(position);
+                self.beneficiary_accounts.set(&beneficiary_accounts);
+            }
+            self.total_shares = self.total_shares.saturating_sub(beneficiary.share_bps);
+            self.total_weight = self.total_weight.saturating_sub(beneficiary.weight);
+            let (pending, _) = self.balances.take(account).unwrap_or_default();
+            self.credited.remove(account);
+            self.tips.remove(account);
+            self.labels.remove(account);
+            
+            let paid = if forfeit_unvested { withdrawable } else { pending };
+            let forfeited = pending.saturating_sub(paid);
+            // Parked funds no longer belong to a beneficiary, so count them as withdrawn
+            self.update_asset_stats(None, |stats| {
+                stats.total_pending = stats.total_pending.saturating_sub(pending);
+                stats.total_withdrawn = stats.total_withdrawn
+                    .checked_add(paid)
+                    .ok_or(Error::ArithmeticOverflow)?;
+                Ok(())
+            })?;
+            self.undistributed_pool = self.undistributed_pool
+                .checked_add(forfeited)
+                .ok_or(Error::ArithmeticOverflow)?;
+            self.total_forfeited = self.total_forfeited
+                .checked_add(forfeited)
+                .ok_or(Error::ArithmeticOverflow)?;
+            
+            self.clear_pending_removal(account);
+            self.record_config();
+            self.log_admin_action(AdminActionKind::RemoveBeneficiary, Some(account));
+            
+            let now = self.env().block_timestamp();
+            match beneficiary.locked_until {
+                Some(until) if now < until && !paid.is_zero() => {
+                    let (parked, unlocks_at) = self.parked.get(account).unwrap_or_default();
+                    let parked = parked.checked_add(paid).ok_or(Error::ArithmeticOverflow)?;
+                    let unlocks_at = unlocks_at.max(until);
+                    self.parked.insert(account, &(parked, unlocks_at));
+                    self.total_parked = self.total_parked
+                        .checked_add(paid)
+                        .ok_or(Error::ArithmeticOverflow)?;
+                    
+                    self.env().emit_event(FundsParked {
+                        account,
+                        amount: paid,
+                        unlocks_at,
+                    });
+                }
+                _ => {
+                    // If beneficiary has a pending balance, transfer it to their
+                    // payout destination. A failed transfer (e.g. below the
+                    // existential deposit) must not block the removal, so the
+                    // funds wait in `claimable` under the beneficiary account.
+                    if !paid.is_zero()
+                        && self.send_funds(beneficiary.payout_destination(), paid, beneficiary.forward_as_payment).is_err()
+                    {
+                        let claimable = self.claimable.get(account).unwrap_or_default();
+                        self.claimable.insert(account, &claimable.checked_add(paid).ok_or(Error::ArithmeticOverflow)?);
+                        self.total_claimable = self.total_claimable
+                            .checked_add(paid)
+                            .ok_or(Error::ArithmeticOverflow)?;
+                        
+                        self.env().emit_event(PayoutEscrowed {
+                            account,
+                            amount: paid,
+                        });
+                    }
+                }
+            }
+            
+            self.env().emit_event(BeneficiaryRemoved {
+                beneficiary: account,
+                removed_by: self.env().caller(),
+                forfeited,
+                freed_share_bps: beneficiary.share_bps,
+            });
+            
+            Ok(())
+        }
+
+        /// Scale every share proportionally so they sum to `target_total`
+        fn rescale_shares(&mut self, target_total: u16) {
+            let current_total = u32::from(self.total_shares);
+            if current_total == 0 {
+                return;
+            }
+            
+            let mut beneficiaries = self.beneficiary_accounts
+                .get_or_default()
+                .iter()
+                .filter_map(|account| self.beneficiary_data.get(account))
+                .collect::<Vec<_>>();
+            let mut new_shares = Vec::with_capacity(beneficiaries.len());
+            let mut remainders = Vec::with_capacity(beneficiaries.len());
+            let mut floor_total = 0u32;
+            
+            for (index, beneficiary) in beneficiaries.iter().enumerate() {
+                let scaled = u32::from(beneficiary.share_bps) * u32::from(target_total);
+                let share = scaled / current_total;
+                
+                new_shares.push(share);
+                remainders.push((index, scaled % current_total));
+                floor_total += share;
+            }
+            
+            // Stable sort keeps list order among equal remainders
+            let mut leftover = u32::from(target_total).saturating_sub(floor_total);
+            remainders.sort_by(|a, b| b.1.cmp(&a.1));
+            for (index, _) in remainders {
+                if leftover == 0 {
+                    break;
+                }
+                new_shares[index] += 1;
+                leftover -= 1;
+            }
+            
+            for (beneficiary, new_share) in beneficiaries.iter_mut().zip(new_shares) {
+                let old_share_bps = beneficiary.share_bps;
+                let new_share_bps = new_share as u16;
+                if old_share_bps == new_share_bps {
+                    continue;
+                }
+                
+                beneficiary.share_bps = new_share_bps;
+                self.beneficiary_data.insert(beneficiary.account, beneficiary);
+                
+                self.env().emit_event(BeneficiaryShareUpdated {
+                    beneficiary: beneficiary.account,
+                    old_share_bps,
+                    new_share_bps,
+                });
+            }
+            
+            self.total_shares = target_total;
+            self.record_config();
+        }
+
+        /// Remove and return the share offered to `account`
+        fn take_invitation(&mut self, account: H160) -> Result<u16> {
+            let share_bps = self.invitations
+                .take(account)
+                .ok_or(Error::InvitationNotFound)?;
+            let mut invitation_accounts = self.invitation_accounts.get_or_default();
+            invitation_accounts.retain(|a| *a != account);
+            self.invitation_accounts.set(&invitation_accounts);
+            
+            Ok(share_bps)
+        }
+
+        /// Start a new config version holding the current share table
+        fn record_config(&mut self) {
+            let snapshot = self.beneficiary_accounts
+                .get_or_default()
+                .iter()
+                .filter_map(|account| self.beneficiary_data.get(account))
+                .map(|b| (b.account, b.share_bps))
+                .collect::<Vec<_>>();
+            
+            self.config_version = self.config_version.saturating_add(1);
+            self.config_history.insert(self.config_version, &snapshot);
+        }
+
+        /// Drop `account` from the pending removals, returning whether it was there
+        fn clear_pending_removal(&mut self, account: H160) -> bool {
+            if self.pending_removals.take(account).is_none() {
+                return false;
+            }
+            
+            let mut pending_removal_accounts = self.pending_removal_accounts.get_or_default();
+            pending_removal_accounts.retain(|a| *a != account);
+            self.pending_removal_accounts.set(&pending_removal_accounts);
+            true
+        }
+
+        /// Pending balance of `account` that its vesting schedule has unlocked
+        fn withdrawable(&self, account: H160) -> U256 {
+            let Some(beneficiary) = self.beneficiary_data.get(account) else {
+                return U256::zero();
+            };
+            let (pending, withdrawn) = self.balances.get(account).unwrap_or_default();
+            
+            match beneficiary.vesting {
+                Some(schedule) => {
+                    let credited = pending.saturating_add(withdrawn);
+                    schedule.vested_amount(credited, self.env().block_timestamp())
+                        .saturating_sub(withdrawn)
+                        .min(pending)
+                }
+                None => pending,
+            }
+        }
+
+        /// Contract balance not accounted for by pending balances or pools
+        fn untracked_balance(&self) -> U256 {
+            let tracked = self.total_pending()
+                .saturating_add(self.undistributed_pool)
+                .saturating_add(self.pooled_for_distribution())
+                .saturating_add(self.total_parked)
+                .saturating_add(self.total_claimable)
+                .saturating_add(self.total_failed_payouts)
+                .saturating_add(self.fee_accrued)
+                .saturating_add(self.held_total)
+                .saturating_add(self.campaign_raised);
+            
+            self.env().balance().saturating_sub(tracked)
+        }
+
+        /// Transfer the whole pending balance of a beneficiary
+        ///
+        /// On a failed transfer the amount moves to the failed payout queue and
+        /// `PayoutFailed` is emitted.
+        fn payout_beneficiary(&mut self, account: H160) -> Result<U256> {
+            self.ensure_not_blacklisted(account)?;
+            
+            let beneficiary = self.load_beneficiary(account)
+                .ok_or(Error::BeneficiaryNotFound)?;
+            let destination = beneficiary.payout_destination();
+            let amount = self.withdrawable(account);
+            
+            if beneficiary.frozen {
+                return Err(Error::BeneficiaryFrozen);
+            }
+            
+            if amount.is_zero() {
+                return Err(Error::NoFundsAvailable);
+            }
+            
+            self.ensure_release_allowed(account, amount)?;
+            self.ensure_solvent(amount)?;
+            
+            // Either way the amount leaves the pending balance
+            let pending = beneficiary.pending_balance
+                .checked_sub(amount)
+                .ok_or(Error::ArithmeticOverflow)?;
+            let withdrawn = beneficiary.total_withdrawn
+                .checked_add(amount)
+                .ok_or(Error::ArithmeticOverflow)?;
+            self.balances.insert(account, &(pending, withdrawn));
+            
+            if let Err(error) = self.send_funds(destination, amount, beneficiary.forward_as_payment) {
+                // Queued funds count as withdrawn, like escrowed removal payouts
+                self.update_asset_stats(None, |stats| {
+                    stats.total_pending = stats.total_pending.saturating_sub(amount);
+                    stats.total_withdrawn = stats.total_withdrawn
+                        .checked_add(amount)
+                        .ok_or(Error::ArithmeticOverflow)?;
+                    Ok(())
+                })?;
+                self.queue_failed_payout(account, amount)?;
+                
+                self.env().emit_event(PayoutFailed {
+                    beneficiary: account,
+                    amount,
+                    reason_code: error.error_code(),
+                });
+                
+                return Err(Error::TransferFailed);
+            }
+            
+            self.record_release(account, amount)?;
+            
+            self.env().emit_event(Payout {
+                beneficiary: account,
+                amount,
+            });
+            self.notify_payout(&beneficiary, amount);
+            
+            Ok(amount)
+        }
+
+        /// Add `amount` to an account's failed payout
+        fn queue_failed_payout(&mut self, account: H160, amount: U256) -> Result<()> {
+            let failed = self.failed_payouts.get(account).unwrap_or_default();
+            if failed.is_zero() {
+                let mut failed_payout_accounts = self.failed_payout_accounts.get_or_default();
+                failed_payout_accounts.push(account);
+                self.failed_payout_accounts.set(&failed_payout_accounts);
+            }
+            
+            self.failed_payouts.insert(account, &failed.checked_add(amount).ok_or(Error::ArithmeticOverflow)?);
+            self.total_failed_payouts = self.total_failed_payouts
+                .checked_add(amount)
+                .ok_or(Error::ArithmeticOverflow)?;
+            Ok(())
+        }
+
+        /// Send an account's failed payout again, clearing it on success
+        fn retry_payout(&mut self, account: H160) -> Result<U256> {
+            let amount = self.failed_payouts.get(account).unwrap_or_default();
+            if amount.is_zero() {
+                return Err(Error::NoFundsAvailable);
+            }
+            
+            self.ensure_not_blacklisted(account)?;
+            
+            let (destination, forward) = match self.beneficiary_data.get(account) {
+                Some(beneficiary) if beneficiary.frozen => return Err(Error::BeneficiaryFrozen),
+                Some(beneficiary) => (beneficiary.payout_destination(), beneficiary.forward_as_payment),
+                None => (account, false),
+            };
+            self.ensure_solvent(amount)?;
+            self.send_funds(destination, amount, forward)?;
+            
+            self.failed_payouts.remove(account);
+            let mut failed_payout_accounts = self.failed_payout_accounts.get_or_default();
+            failed_payout_accounts.retain(|failed| *failed != account);
+            self.failed_payout_accounts.set(&failed_payout_accounts);
+            self.total_failed_payouts = self.total_failed_payouts.saturating_sub(amount);
+            
+            self.env().emit_event(Payout {
+                beneficiary: account,
+                amount,
+            });
+            
+            Ok(amount)
+        }
+
+        /// Work out what each of `beneficiaries` is credited out of `amount`, in list order
+        ///
+        /// Flat amounts are credited first, then capped tiers are filled (see
+        /// `set_tier`), then the rest is split by share with `split_by_units`.
+        /// Finally lifetime caps are applied (see `set_lifetime_cap`).
+        fn split_amount(&self, beneficiaries: &[Beneficiary], amount: U256) -> Vec<U256> {
+            let mut credits = beneficiaries.iter().map(|_| U256::zero()).collect::<Vec<_>>();
+            let mut remaining = amount;
+            
+            for (credit, beneficiary) in credits.iter_mut().zip(beneficiaries) {
+                if let ShareKind::Fixed(fixed) = beneficiary.kind {
+                    *credit = fixed.min(remaining);
+                    remaining = remaining.saturating_sub(*credit);
+                }
+            }
+            
+            // Stable sort keeps list order within a priority
+            let mut capped = (0..beneficiaries.len())
+                .filter(|index| beneficiaries[*index].kind == ShareKind::Proportional)
+                .filter(|index| beneficiaries[*index].cap_per_distribution.is_some())
+                .collect::<Vec<_>>();
+            capped.sort_by_key(|index| beneficiaries[*index].priority);
+            
+            let mut capped_units = 0u64;
+            for index in capped {
+                if let Some(cap) = beneficiaries[index].cap_per_distribution {
+                    let credit = cap.min(remaining);
+                    credits[index] = credit;
+                    remaining = remaining.saturating_sub(credit);
+                    capped_units = capped_units.saturating_add(self.share_units(&beneficiaries[index]));
+                }
+            }
+            
+            let units = beneficiaries
+                .iter()
+                .map(|b| if b.cap_per_distribution.is_some() { 0 } else { self.share_units(b) })
+                .collect::<Vec<_>>();
+            let denominator = self.share_denominator().saturating_sub(capped_units);
+            
+            for (credit, share) in credits.iter_mut().zip(Self::split_by_units(&units, denominator, remaining)) {
+                *credit = credit.saturating_add(share);
+            }
+            
+            self.apply_lifetime_caps(beneficiaries, &mut credits);
+            
+            credits
+        }
+
+        /// Clamp `credits` at each beneficiary's lifetime cap
+        ///
+        /// The excess is split by share among uncapped-tier proportional
+        /// beneficiaries still below their cap, repeating until none is left
+        /// or nobody can take it; whatever remains is left uncredited.
+        fn apply_lifetime_caps(&self, beneficiaries: &[Beneficiary], credits: &mut [U256]) {
+            let headroom = beneficiaries
+                .iter()
+                .map(|b| b.lifetime_cap.map(|cap| cap.saturating_sub(b.total_credited)))
+                .collect::<Vec<_>>();
+            
+            // Every pass with an excess saturates at least one more beneficiary
+            loop {
+                let mut excess = U256::zero();
+                for (credit, room) in credits.iter_mut().zip(&headroom) {
+                    if let Some(room) = room {
+                        if *credit > *room {
+                            excess = excess.saturating_add(credit.saturating_sub(*room));
+                            *credit = *room;
+                        }
+                    }
+                }
+                if excess.is_zero() {
+                    return;
+                }
+                
+                let units = beneficiaries
+                    .iter()
+                    .zip(credits.iter())
+                    .zip(&headroom)
+                    .map(|((b, credit), room)| match room {
+                        Some(room) if credit >= room => 0,
+                        _ if b.cap_per_distribution.is_some() => 0,
+                        _ => self.share_units(b),
+                    })
+                    .collect::<Vec<_>>();
+                let unit_total = units.iter().fold(0u64, |acc, unit| acc.saturating_add(*unit));
+                if unit_total == 0 {
+                    return;
+                }
+                
+                for (credit, extra) in credits.iter_mut().zip(Self::split_by_units(&units, unit_total, excess)) {
+                    *credit = credit.saturating_add(extra);
+                }
+            }
+        }
+
+        /// Split `amount` in proportion to `units / denominator`, in list order
+        ///
+        /// Uses largest-remainder rounding: every entry gets the floor of its
+        /// exact share, then the units lost to flooring are handed out one at a
+        /// time to the largest fractional remainders (ties go to whoever comes
+        /// first in the list). When `units` sum to `denominator` the result
+        /// sums exactly to `amount`.
+        fn split_by_units(units: &[u64], denominator: u64, amount: U256) -> Vec<U256> {
+            if denominator == 0 {
+                return units.iter().map(|_| U256::zero()).collect();
+            }
+            let denominator = U256::from(denominator);
+            let mut shares = Vec::with_capacity(units.len());
+            let mut remainders = Vec::with_capacity(units.len());
+            let mut floor_total = U256::zero();
+            let mut unit_total = 0u64;
+
+            for (index, unit) in units.iter().enumerate() {
+                let (share, remainder) = Self::mul_div(amount, *unit, denominator);
+                
+                shares.push(share);
+                remainders.push((index, remainder));
+                floor_total = floor_total.saturating_add(share);
+                unit_total = unit_total.saturating_add(*unit);
+            }
+
+            // Amount owed to the allocated shares as a whole
+            let (allocatable, _) = Self::mul_div(amount, unit_total, denominator);
+            let mut leftover = allocatable.saturating_sub(floor_total);
+
+            // Stable sort keeps list order among equal remainders
+            remainders.sort_by(|a, b| b.1.cmp(&a.1));
+
+            for (index, _) in remainders {
+                if leftover.is_zero() {
+                    break;
+                }
+                shares[index] = shares[index].saturating_add(U256::one());
+                leftover = leftover.saturating_sub(U256::one());
+            }
+
+            shares
+        }
+
+        /// `(amount * units / denominator, amount * units % denominator)`
+        ///
+        /// Splits `amount` around `denominator` instead of forming the full
+        /// product, so the result is exact and, with `units <= denominator` as
+        /// every share split guarantees, cannot overflow even near `U256::MAX`.
+        fn mul_div(amount: U256, units: u64, denominator: U256) -> (U256, U256) {
+            let units = U256::from(units);
+            let high = (amount / denominator).saturating_mul(units);
+            let low = (amount % denominator) * units;
+            (high.saturating_add(low / denominator), low % denominator)
+        }
+
+        /// A beneficiary's share in the units of the contract's share model
+        fn share_units(&self, beneficiary: &Beneficiary) -> u64 {
+            match self.share_model {
+                ShareModel::Percentage => u64::from(beneficiary.share_bps),
+                ShareModel::Weighted => beneficiary.weight,
+            }
+        }
+
+        /// Sum of all beneficiaries' `share_units`
+        fn allocated_units(&self) -> u64 {
+            match self.share_model {
+                ShareModel::Percentage => u64::from(self.total_shares),
+                ShareModel::Weighted => self.total_weight,
+            }
+        }
+
+        /// What `share_units` are a fraction of
+        fn share_denominator(&self) -> u64 {
+            match self.share_model {
+                ShareModel::Percentage => u64::from(MAX_SHARE_BPS),
+                ShareModel::Weighted => self.total_weight,
+            }
+        }
+
+        /// Whether every payment is fully credited to beneficiaries
+        fn is_fully_allocated(&self) -> bool {
+            match self.share_model {
+                ShareModel::Percentage => self.total_shares == MAX_SHARE_BPS,
+                ShareModel::Weighted => self.total_weight > 0,
+            }
+        }
+
+        /// Whether ownership has been renounced
+        fn is_renounced(&self) -> bool {
+            self.owner == H160::zero()
+        }
+
+        /// Ensure the caller may manage approvals for `spender`, returning the caller
+        fn ensure_can_approve(&self, spender: H160) -> Result<H160> {
+            let caller = self.env().caller();
+            self.ensure_approver(caller, spender)?;
+            
+            Ok(caller)
+        }
+
+        /// Ensure `owner` is an unfrozen beneficiary able to approve `spender`
+        fn ensure_approver(&self, owner: H160, spender: H160) -> Result<()> {
+            self.ensure_not_paused()?;
+            Self::ensure_not_zero(spender)?;
+            self.ensure_not_blacklisted(spender)?;
+            
+            let beneficiary = self.beneficiary_data
+                .get(owner)
+                .ok_or(Error::Unauthorized)?;
+            if beneficiary.frozen {
+                return Err(Error::BeneficiaryFrozen);
+            }
+            
+            Ok(())
+        }
+
+        /// Ensure `account` is not blacklisted
+        fn ensure_not_blacklisted(&self, account: H160) -> Result<()> {
+            if self.is_blacklisted(account) {
+                Err(Error::Blacklisted)
+            } else {
+                Ok(())
+            }
+        }
+
+        /// Ethereum-style address whose key produced `signature` over `digest`
+        fn recover_signer(&self, digest: &[u8; 32], signature: &[u8; 65]) -> Result<H160> {
+            let mut public_key = [0u8; 33];
+            self.env().ecdsa_recover(signature, digest, &mut public_key)
+                .map_err(|_| Error::InvalidSignature)?;
+            
+            let mut signer = [0u8; 20];
+            self.env().ecdsa_to_eth_address(&public_key, &mut signer)
+                .map_err(|_| Error::InvalidSignature)?;
+            
+            Ok(H160::from(signer))
+        }
+
+        /// Write an approval (removing it when `amount` is zero) and emit `ApprovalGranted`
+        fn store_approval(&mut self, owner: H160, spender: H160, amount: U256, expires_at: Option<u64>) {
+            if amount.is_zero() {
+                self.remove_approval(owner, spender);
+            } else {
+                if !self.approvals.contains((owner, spender)) {
+                    let mut spenders = self.spenders_of.get(owner).unwrap_or_default();
+                    spenders.push(spender);
+                    self.spenders_of.insert(owner, &spenders);
+                }
+                self.approvals.insert((owner, spender), &Approval {
+                    spender,
+                    amount,
+                    expires_at,
+                });
+            }
+            
+            self.env().emit_event(ApprovalGranted {
+                owner,
+                spender,
+                amount,
+                expires_at,
+            });
+        }
+
+        /// Whether an approval is past its `expires_at`
+        fn is_expired(&self, approval: &Approval) -> bool {
+            approval.expires_at
+                .is_some_and(|expires_at| self.env().block_timestamp() > expires_at)
+        }
+
+        /// Load an approval, treating it as absent once past `expires_at`
+        fn live_approval(&self, owner: H160, spender: H160) -> Option<Approval> {
+            self.approvals.get((owner, spender))
+                .filter(|a| !self.is_expired(a))
+        }
+
+        /// Delete every approval granted by `owner`, returning how many were removed
+        fn revoke_approvals_of(&mut self, owner: H160) -> u32 {
+            let spenders = self.spenders_of.take(owner).unwrap_or_default();
+            for spender in spenders.iter() {
+                self.approvals.remove((owner, *spender));
+                
+                self.env().emit_event(ApprovalRevoked {
+                    owner,
+                    spender: *spender,
+                });
+            }
+            
+            spenders.len() as u32
+        }
+
+        /// Delete an approval and drop the spender from the owner's index
+        fn remove_approval(&mut self, owner: H160, spender: H160) {
+            if self.approvals.take((owner, spender)).is_none() {
+                return;
+            }
+            
+            let mut spenders = self.spenders_of.get(owner).unwrap_or_default();
+            spenders.retain(|s| *s != spender);
+            if spenders.is_empty() {
+                self.spenders_of.remove(owner);
+            } else {
+                self.spenders_of.insert(owner, &spenders);
+            }
+        }
+
+        /// Reject the zero address where a real account is required
+        fn ensure_not_zero(account: H160) -> Result<()> {
+            if account == H160::zero() {
+                Err(Error::ZeroAddress)
+            } else {
+                Ok(())
+            }
+        }
+
+        /// Ensure the beneficiary list has not been finalized and is not being distributed to
+        fn ensure_shares_unlocked(&self) -> Result<()> {
+            if self.shares_locked {
+                return Err(Error::SharesLocked);
+            }
+            
+            self.ensure_not_distributing()
+        }
+
+        /// Ensure no chunked distribution is under way
+        fn ensure_not_distributing(&self) -> Result<()> {
+            if self.distribution_progress.is_some() {
+                Err(Error::DistributionInProgress)
+            } else {
+                Ok(())
+            }
+        }
+
+        /// Ensure caller is the owner
+        fn ensure_owner(&self) -> Result<()> {
+            if !self.is_renounced() && self.env().caller() == self.owner {
+                Ok(())
+            } else {
+                Err(Error::Unauthorized)
+            }
+        }
+
+        /// Ensure caller is owner or manager
+        fn ensure_manager_or_owner(&self) -> Result<()> {
+            if self.is_renounced() {
+                return Err(Error::Unauthorized);
+            }
+            
+            let caller = self.env().caller();
+            if caller == self.owner || self.is_manager(caller) {
+                Ok(())
+            } else {
+                Err(Error::Unauthorized)
+            }
+        }
+
+        /// Ensure caller may push payouts: the owner, an operator or a distribution manager
+        fn ensure_can_trigger(&self) -> Result<()> {
+            if !self.is_renounced() && self.is_operator(self.env().caller()) {
+                return Ok(());
+            }
+            self.ensure_permission(Permission::TriggerDistribution)
+        }
+
+        /// Permissions of `account` if it holds an unexpired manager appointment
+        fn active_manager(&self, account: H160) -> Option<ManagerPermissions> {
+            self.managers
+                .get(account)
+                .filter(|appointment| appointment.is_active(self.env().block_timestamp()))
+                .map(|appointment| appointment.permissions)
+        }
+
+        /// Ensure caller is the owner or a manager holding `permission`
+        fn ensure_permission(&self, permission: Permission) -> Result<()> {
+            if self.is_renounced() {
+                return Err(Error::Unauthorized);
+            }
+            
+            let caller = self.env().caller();
+            if caller == self.owner {
+                return Ok(());
+            }
+            
+            match self.active_manager(caller) {
+                Some(permissions) if permissions.allows(permission) => Ok(()),
+                _ => Err(Error::Unauthorized),
+            }
+        }
+
+        /// Ensure neither deposits nor withdrawals are paused (admin changes)
+        fn ensure_not_paused(&self) -> Result<()> {
+            if self.is_paused() {
+                Err(Error::ContractPaused)
+            } else {
+                Ok(())
+            }
+        }
+
+        /// Ensure deposits are not paused
+        fn ensure_deposits_open(&self) -> Result<()> {
+            if self.deposits_paused {
+                Err(Error::ContractPaused)
+            } else {
+                Ok(())
+            }
+        }
+
+        /// Ensure the current time is inside the acceptance window
+        fn ensure_accepting(&self) -> Result<()> {
+            let now = self.env().block_timestamp();
+            let too_early = self.accepting_from.is_some_and(|from| now < from);
+            let too_late = self.accepting_until.is_some_and(|until| now > until);
+            
+            if too_early || too_late {
+                Err(Error::OutsideAcceptanceWindow)
+            } else {
+                Ok(())
+            }
+        }
+
+        /// Ensure destructive admin actions may be called directly (no council or admin delay)
+        fn ensure_direct_admin_allowed(&self) -> Result<()> {
+            self.ensure_no_council()?;
+            
+            if self.admin_delay_ms > 0 {
+                Err(Error::ActionTimelocked)
+            } else {
+                Ok(())
+            }
+        }
+
+        /// Ensure no council is configured
+        fn ensure_no_council(&self) -> Result<()> {
+            if self.council.get_or_default().is_empty() {
+                Ok(())
+            } else {
+                Err(Error::CouncilApprovalRequired)
+            }
+        }
+
+        /// Ensure the caller is a council member, returning the caller
+        fn ensure_council_member(&self) -> Result<H160> {
+            let caller = self.env().caller();
+            if self.council.get_or_default().contains(&caller) {
+                Ok(caller)
+            } else {
+                Err(Error::NotCouncilMember)
+            }
+        }
+
+        /// Run `f` with the reentrancy lock held, failing with `ReentrantCall` if it already is
+        fn non_reentrant<T>(&mut self, f: impl FnOnce(&mut Self) -> Result<T>) -> Result<T> {
+            if self.reentrancy_lock.get().unwrap_or(false) {
+                return Err(Error::ReentrantCall);
+            }
+            
+            self.reentrancy_lock.set(&true);
+            let result = f(self);
+            self.reentrancy_lock.set(&false);
+            
+            result
+        }
+
+        /// Ensure beneficiaries may be added without an accepted invitation
+        fn ensure_direct_add_allowed(&self) -> Result<()> {
+            if self.require_acceptance {
+                Err(Error::AcceptanceRequired)
+            } else {
+                Ok(())
+            }
+        }
+
+        /// Ensure the contract has not been retired
+        fn ensure_not_retired(&self) -> Result<()> {
+            if self.retired {
+                Err(Error::ContractRetired)
+            } else {
+                Ok(())
+            }
+        }
+
+        /// Ensure withdrawals are not paused; a retired contract never blocks them
+        fn ensure_withdrawals_open(&self) -> Result<()> {
+            if self.withdrawals_paused && !self.retired {
+                Err(Error::ContractPaused)
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    impl crate::SplitPaymentInterface for SplitPayment {
+        #[ink(message, payable)]
+        fn receive_payment(&mut self) -> Result<()> {
+            self.process_payment(None).map(|_| ())
+        }
+
+        #[ink(message)]
+        fn get_beneficiary(&self, account: H160) -> Option<Beneficiary> {
+            self.load_beneficiary(account)
+        }
+
+        #[ink(message)]
+        fn get_total_shares(&self) -> u16 {
+            self.total_shares
+        }
+
+        #[ink(message)]
+        fn get_pending_balance(&self, account: H160) -> U256 {
+            SplitPayment::get_pending_balance(self, account)
+        }
+
+        #[ink(message)]
+        fn is_paused(&self) -> bool {
+            SplitPayment::is_paused(self)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use secp256k1::{Message, PublicKey, Secp256k1, SecretKey};
+
+        #[ink::test]
+        fn constructor_works() {
+            let contract = SplitPayment::new();
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            assert_eq!(contract.is_paused(), false);
+            assert_eq!(contract.get_total_shares(), 0);
+            assert_eq!(contract.get_owner(), accounts.alice);
+        }
+
+        #[ink::test]
+        fn constructor_with_owner_does_not_empower_instantiator() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            
+            // Alice instantiates on Bob's behalf
+            let mut contract = SplitPayment::new_with_owner(accounts.bob).unwrap();
+            assert_eq!(contract.get_owner(), accounts.bob);
+            assert_eq!(contract.add_beneficiary(accounts.charlie, 5_000), Err(Error::Unauthorized));
+            assert_eq!(contract.pause(), Err(Error::Unauthorized));
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert!(contract.add_beneficiary(accounts.charlie, 5_000).is_ok());
+            
+            // Naming the instantiator is the same as `new`
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(SplitPayment::new_with_owner(accounts.alice).unwrap().get_owner(), accounts.alice);
+        }
+
+        #[ink::test]
+        fn add_beneficiary_works() {
+            let mut contract = SplitPayment::new();
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            
+            assert!(contract.add_beneficiary(accounts.alice, 5000).is_ok());
+            assert_eq!(contract.get_total_shares(), 5000);
+            
+            let beneficiary = contract.get_beneficiary(accounts.alice).unwrap();
+            assert_eq!(beneficiary.share_bps, 5000);
+        }
+
+        #[ink::test]
+        fn approval_system_works() {
+            let mut contract = SplitPayment::new();
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            
+            // Add beneficiary first
+            contract.add_beneficiary(accounts.alice, 5000).unwrap();
+            
+            // Set caller to alice for approval
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            
+            // Grant approval
+            assert!(contract.approve(accounts.bob, 1000, None).is_ok());
+            assert_eq!(contract.get_approval(accounts.alice, accounts.bob), 1000);
+            
+            // Revoke approval
+            assert!(contract.revoke_approval(accounts.bob).is_ok());
+            assert_eq!(contract.get_approval(accounts.alice, accounts.bob), 0);
+        }
+
+        #[ink::test]
+        fn access_control_works() {
+            let mut contract = SplitPayment::new();
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            
+            // Non-owner cannot add manager
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.eve);
+            assert_eq!(contract.add_manager(accounts.bob, ManagerPermissions::all(), None), Err(Error::Unauthorized));
+            
+            // Owner (the instantiator) can add manager
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert!(contract.add_manager(accounts.bob, ManagerPermissions::all(), None).is_ok());
+            assert!(contract.is_manager(accounts.bob));
+        }
+
+        #[ink::test]
+        fn unallocated_shares_go_to_undistributed_pool() {
+            let mut contract = SplitPayment::new();
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            
+            contract.add_beneficiary(accounts.alice, 3300).unwrap();
+            contract.add_beneficiary(accounts.bob, 3700).unwrap();
+            
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(1001));
+            contract.receive_payment().unwrap();
+            
+            // 33% of 1001 = 330, 37% of 1001 = 370, remaining 301 is unallocated
+            assert_eq!(contract.get_beneficiary(accounts.alice).unwrap().pending_balance, U256::from(330));
+            assert_eq!(contract.get_beneficiary(accounts.bob).unwrap().pending_balance, U256::from(370));
+            assert_eq!(contract.get_undistributed(), U256::from(301));
+            
+            let (received, distributed, _) = contract.get_stats();
+            assert_eq!(received, distributed + contract.get_undistributed());
+        }
+
+        #[ink::test]
+        fn sweep_undistributed_is_owner_only() {
+            let mut contract = SplitPayment::new();
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            
+            assert_eq!(contract.sweep_undistributed(accounts.bob), Err(Error::NoFundsAvailable));
+            
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(100));
+            contract.receive_payment().unwrap();
+            assert_eq!(contract.get_undistributed(), U256::from(100));
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.sweep_undistributed(accounts.bob), Err(Error::Unauthorized));
+        }
+
+        #[ink::test]
+        fn fractional_basis_point_split_works() {
+            let mut contract = SplitPayment::new();
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            
+            contract.add_beneficiary(accounts.alice, 3333).unwrap();
+            contract.add_beneficiary(accounts.bob, 3333).unwrap();
+            contract.add_beneficiary(accounts.charlie, 3334).unwrap();
+            assert_eq!(contract.get_total_shares(), MAX_SHARE_BPS);
+            
+            // Fully allocated, so nothing more can be added
+            assert_eq!(
+                contract.add_beneficiary(accounts.django, 1),
+                Err(Error::InvalidShare { requested: 1, available: 0 })
+            );
+            
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(1_000_000));
+            contract.receive_payment().unwrap();
+            
+            assert_eq!(contract.get_beneficiary(accounts.alice).unwrap().pending_balance, U256::from(333_300));
+            assert_eq!(contract.get_beneficiary(accounts.bob).unwrap().pending_balance, U256::from(333_300));
+            assert_eq!(contract.get_beneficiary(accounts.charlie).unwrap().pending_balance, U256::from(333_400));
+            assert_eq!(contract.get_undistributed(), U256::zero());
+            
+            // Awkward amount: rounding never leaks into the pool when fully allocated
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(10));
+            contract.receive_payment().unwrap();
+            assert_eq!(contract.get_undistributed(), U256::zero());
+        }
+
+        #[ink::test]
+        fn manual_distribution_mode_works() {
+            let (mut contract, accounts) = contract_with_shares(&[5000, 5000]);
+            
+            assert!(contract.set_distribution_mode(DistributionMode::Manual).is_ok());
+            assert_eq!(contract.get_distribution_mode(), DistributionMode::Manual);
+            
+            // Nothing to distribute yet
+            assert_eq!(contract.distribute(), Err(Error::NoFundsAvailable));
+            
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(300));
+            contract.receive_payment().unwrap();
+            contract.receive_payment().unwrap();
+            
+            // Payments are held, not credited
+            assert_eq!(pending_of(&contract, &accounts), vec![U256::zero(), U256::zero()]);
+            
+            // Anyone can trigger the distribution
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(H160::from([0x42; 20]));
+            assert_eq!(contract.distribute(), Ok(U256::from(600)));
+            assert_eq!(pending_of(&contract, &accounts), vec![U256::from(300), U256::from(300)]);
+            assert_eq!(contract.distribute(), Err(Error::NoFundsAvailable));
+        }
+
+        #[ink::test]
+        fn set_distribution_mode_is_owner_only() {
+            let mut contract = SplitPayment::new();
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(
+                contract.set_distribution_mode(DistributionMode::Manual),
+                Err(Error::Unauthorized)
+            );
+            assert_eq!(contract.get_distribution_mode(), DistributionMode::Immediate);
+        }
+
+        #[ink::test]
+        fn payout_all_skips_failed_transfers() {
+            let (mut contract, accounts) = contract_with_shares(&[5000, 5000]);
+            
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(1000));
+            contract.receive_payment().unwrap();
+            
+            // The second beneficiary's payout fails, the first still goes through
+            set_contract_balance(U256::from(1000));
+            contract.freeze_beneficiary(accounts[1], true).unwrap();
+            
+            assert_eq!(contract.payout_all(), Ok(1));
+            
+            let first = contract.get_beneficiary(accounts[0]).unwrap();
+            assert_eq!(first.pending_balance, U256::zero());
+            assert_eq!(first.total_withdrawn, U256::from(500));
+            
+            let second = contract.get_beneficiary(accounts[1]).unwrap();
+            assert_eq!(second.pending_balance, U256::from(500));
+            assert_eq!(second.total_withdrawn, U256::zero());
+        }
+
+        #[ink::test]
+        fn payout_single_beneficiary_works() {
+            let (mut contract, accounts) = contract_with_shares(&[5000, 5000]);
+            
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(1000));
+            contract.receive_payment().unwrap();
+            set_contract_balance(U256::from(1000));
+            
+            assert!(contract.payout(accounts[1]).is_ok());
+            assert_eq!(contract.get_beneficiary(accounts[1]).unwrap().total_withdrawn, U256::from(500));
+            assert_eq!(contract.payout(accounts[1]), Err(Error::NoFundsAvailable));
+            assert_eq!(contract.payout(H160::from([0x42; 20])), Err(Error::BeneficiaryNotFound));
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts[0]);
+            assert_eq!(contract.payout_all(), Err(Error::Unauthorized));
+        }
+
+        #[ink::test]
+        fn withdraw_emits_event() {
+            let (mut contract, accounts) = contract_with_shares(&[10000]);
+            
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(1000));
+            contract.receive_payment().unwrap();
+            set_contract_balance(U256::from(1000));
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts[0]);
+            contract.withdraw(U256::from(400)).unwrap();
+            
+            let events = ink::env::test::recorded_events().collect::<Vec<_>>();
+            let event = <Withdrawal as ink::scale::Decode>::decode(
+                &mut &events.last().unwrap().data[..]
+            ).unwrap();
+            
+            assert_eq!(event.beneficiary, accounts[0]);
+            assert_eq!(event.destination, accounts[0]);
+            assert_eq!(event.amount, U256::from(400));
+            assert_eq!(event.remaining_pending, U256::from(600));
+        }
+
+        #[ink::test]
+        fn withdraw_to_sends_to_destination() {
+            let (mut contract, accounts) = contract_with_shares(&[10000]);
+            let destination = H160::from([0x42; 20]);
+            
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(1000));
+            contract.receive_payment().unwrap();
+            set_contract_balance(U256::from(1000));
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts[0]);
+            assert_eq!(contract.withdraw_to(H160::zero(), U256::from(100)), Err(Error::ZeroAddress));
+            assert!(contract.withdraw_to(destination, U256::from(250)).is_ok());
+            
+            let beneficiary = contract.get_beneficiary(accounts[0]).unwrap();
+            assert_eq!(beneficiary.pending_balance, U256::from(750));
+            assert_eq!(beneficiary.total_withdrawn, U256::from(250));
+            
+            let events = ink::env::test::recorded_events().collect::<Vec<_>>();
+            let event = <Withdrawal as ink::scale::Decode>::decode(
+                &mut &events.last().unwrap().data[..]
+            ).unwrap();
+            assert_eq!(event.beneficiary, accounts[0]);
+            assert_eq!(event.destination, destination);
+        }
+
+        #[ink::test]
+        fn payout_address_is_used_for_withdrawals() {
+            let (mut contract, accounts) = contract_with_shares(&[10000]);
+            let hot_wallet = H160::from([0x42; 20]);
+            
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(1000));
+            contract.receive_payment().unwrap();
+            set_contract_balance(U256::from(1000));
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts[0]);
+            assert_eq!(contract.set_payout_address(Some(H160::zero())), Err(Error::ZeroAddress));
+            assert!(contract.set_payout_address(Some(hot_wallet)).is_ok());
+            assert_eq!(contract.get_beneficiary(accounts[0]).unwrap().payout_address, Some(hot_wallet));
+            
+            assert_eq!(contract.withdraw_all(), Ok(U256::from(1000)));
+            
+            let events = ink::env::test::recorded_events().collect::<Vec<_>>();
+            let event = <Withdrawal as ink::scale::Decode>::decode(
+                &mut &events.last().unwrap().data[..]
+            ).unwrap();
+            assert_eq!(event.destination, hot_wallet);
+            assert_eq!(contract.withdraw_all(), Err(Error::NoFundsAvailable));
+        }
+
+        #[ink::test]
+        fn only_beneficiary_sets_payout_address() {
+            let (mut contract, _) = contract_with_shares(&[10000]);
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.set_payout_address(Some(accounts.bob)), Err(Error::Unauthorized));
+        }
+
+        #[ink::test]
+        fn two_step_ownership_transfer_works() {
+            let mut contract = SplitPayment::new();
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let owner = contract.get_owner();
+            let new_owner = H160::from([0x42; 20]);
+            
+            assert_eq!(contract.propose_owner(H160::zero()), Err(Error::ZeroAddress));
+            assert!(contract.propose_owner(new_owner).is_ok());
+            assert_eq!(contract.get_pending_owner(), Some(new_owner));
+            
+            // Ownership does not move until accepted, and only by the proposed owner
+            assert_eq!(contract.get_owner(), owner);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.accept_ownership(), Err(Error::Unauthorized));
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(new_owner);
+            assert!(contract.accept_ownership().is_ok());
+            assert_eq!(contract.get_owner(), new_owner);
+            assert_eq!(contract.get_pending_owner(), None);
+        }
+
+        #[ink::test]
+        fn ownership_proposal_can_be_cancelled() {
+            let mut contract = SplitPayment::new();
+            let new_owner = H160::from([0x42; 20]);
+            
+            assert_eq!(contract.cancel_ownership_proposal(), Err(Error::NoPendingOwner));
+            
+            contract.propose_owner(new_owner).unwrap();
+            assert!(contract.cancel_ownership_proposal().is_ok());
+            assert_eq!(contract.get_pending_owner(), None);
+            
+            let events = ink::env::test::recorded_events().collect::<Vec<_>>();
+            let event = <OwnershipProposalCancelled as ink::scale::Decode>::decode(
+                &mut &events.last().unwrap().data[..]
+            ).unwrap();
+            assert_eq!(event.cancelled, new_owner);
+            
+            assert_eq!(contract.cancel_ownership_proposal(), Err(Error::NoPendingOwner));
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(new_owner);
+            assert_eq!(contract.accept_ownership(), Err(Error::Unauthorized));
+        }
+
+        #[ink::test]
+        fn transfer_ownership_rejects_zero_address() {
+            let mut contract = SplitPayment::new();
+            let owner = contract.get_owner();
+            
+            assert_eq!(contract.transfer_ownership(H160::zero()), Err(Error::ZeroAddress));
+            assert_eq!(contract.get_owner(), owner);
+        }
+
+        #[ink::test]
+        fn transfer_ownership_emits_event() {
+            let mut contract = SplitPayment::new();
+            let owner = contract.get_owner();
+            let new_owner = H160::from([0x42; 20]);
+            
+            assert!(contract.transfer_ownership(new_owner).is_ok());
+            assert_eq!(contract.get_owner(), new_owner);
+            
+            let events = ink::env::test::recorded_events().collect::<Vec<_>>();
+            let event = <OwnershipTransferred as ink::scale::Decode>::decode(
+                &mut &events.last().unwrap().data[..]
+            ).unwrap();
+            assert_eq!(event.previous_owner, owner);
+            assert_eq!(event.new_owner, new_owner);
+        }
+
+        #[ink::test]
+        fn renounce_ownership_requires_full_allocation() {
+            let (mut contract, _) = contract_with_shares(&[6000]);
+            
+            assert_eq!(contract.renounce_ownership(), Err(Error::SharesNotFullyAllocated));
+            
+            contract.add_beneficiary(H160::from([0x42; 20]), 4000).unwrap();
+            contract.pause().unwrap();
+            assert_eq!(contract.renounce_ownership(), Err(Error::ContractPaused));
+            
+            contract.unpause().unwrap();
+            assert!(contract.renounce_ownership().is_ok());
+            assert_eq!(contract.get_owner(), H160::zero());
+        }
+
+        #[ink::test]
+        fn can_still_withdraw_after_renounce() {
+            let (mut contract, accounts) = contract_with_shares(&[10000]);
+            let manager = H160::from([0x42; 20]);
+            contract.add_manager(manager, ManagerPermissions::all(), None).unwrap();
+            
+            assert!(contract.renounce_ownership().is_ok());
+            
+            // Admin paths are closed for the former owner and managers alike
+            assert_eq!(contract.pause(), Err(Error::Unauthorized));
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(manager);
+            assert_eq!(contract.remove_beneficiary(accounts[0], false), Err(Error::Unauthorized));
+            
+            // Payments and withdrawals keep working
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(500));
+            assert!(contract.receive_payment().is_ok());
+            set_contract_balance(U256::from(500));
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts[0]);
+            assert!(contract.withdraw(U256::from(500)).is_ok());
+        }
+
+        #[ink::test]
+        fn manager_list_is_maintained() {
+            let mut contract = SplitPayment::new();
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            
+            contract.add_manager(accounts.bob, ManagerPermissions::all(), None).unwrap();
+            contract.add_manager(accounts.charlie, ManagerPermissions::all(), None).unwrap();
+            contract.add_manager(accounts.bob, ManagerPermissions::all(), None).unwrap();
+            assert_eq!(contract.get_managers(), vec![accounts.bob, accounts.charlie]);
+            assert_eq!(contract.get_manager_count(), 2);
+            
+            contract.remove_manager(accounts.bob).unwrap();
+            assert_eq!(contract.get_managers(), vec![accounts.charlie]);
+            assert!(!contract.is_manager(accounts.bob));
+            
+            assert_eq!(contract.remove_manager(accounts.bob), Err(Error::ManagerNotFound));
+        }
+
+        #[ink::test]
+        fn manager_permissions_are_enforced() {
+            let (mut contract, accounts) = contract_with_shares(&[5000]);
+            let manager = H160::from([0x42; 20]);
+            let add_only = ManagerPermissions {
+                can_add_beneficiary: true,
+                ..Default::default()
+            };
+            
+            contract.add_manager(manager, add_only, None).unwrap();
+            assert!(contract.is_manager(manager));
+            assert_eq!(contract.get_manager_permissions(manager), Some(add_only));
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(manager);
+            assert!(contract.add_beneficiary(H160::from([0x43; 20]), 1000).is_ok());
+            assert_eq!(contract.remove_beneficiary(accounts[0], false), Err(Error::Unauthorized));
+            assert_eq!(contract.pause(), Err(Error::Unauthorized));
+            assert_eq!(contract.payout_all(), Err(Error::Unauthorized));
+        }
+
+        #[ink::test]
+        fn set_manager_permissions_works() {
+            let (mut contract, accounts) = contract_with_shares(&[5000]);
+            let manager = H160::from([0x42; 20]);
+            let owner = contract.get_owner();
+            
+            assert_eq!(
+                contract.set_manager_permissions(manager, ManagerPermissions::all()),
+                Err(Error::ManagerNotFound)
+            );
+            
+            contract.add_manager(manager, ManagerPermissions::default(), None).unwrap();
+            contract.set_manager_permissions(manager, ManagerPermissions {
+                can_remove_beneficiary: true,
+                can_pause: true,
+                ..Default::default()
+            }).unwrap();
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(manager);
+            assert!(contract.pause().is_ok());
+            // Unpausing stays with the owner
+            assert_eq!(contract.unpause(), Err(Error::Unauthorized));
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(owner);
+            contract.unpause().unwrap();
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(manager);
+            assert!(contract.remove_beneficiary(accounts[0], false).is_ok());
+        }
+
+        #[ink::test]
+        fn deposits_and_withdrawals_pause_independently() {
+            let (mut contract, accounts) = contract_with_shares(&[10000]);
+            let owner = contract.get_owner();
+            
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(1000));
+            contract.receive_payment().unwrap();
+            set_contract_balance(U256::from(1000));
+            
+            // Deposits paused: beneficiaries can still withdraw
+            contract.pause_deposits().unwrap();
+            assert_eq!(contract.get_pause_state(), (true, false));
+            assert!(contract.is_paused());
+            assert_eq!(contract.receive_payment(), Err(Error::ContractPaused));
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts[0]);
+            assert!(contract.withdraw(U256::from(100)).is_ok());
+            
+            // Withdrawals paused: payments still come in
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(owner);
+            contract.unpause_deposits().unwrap();
+            contract.pause_withdrawals().unwrap();
+            assert_eq!(contract.get_pause_state(), (false, true));
+            assert!(contract.receive_payment().is_ok());
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts[0]);
+            assert_eq!(contract.withdraw(U256::from(100)), Err(Error::ContractPaused));
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(owner);
+            contract.unpause_withdrawals().unwrap();
+            assert!(!contract.is_paused());
+        }
+
+        #[ink::test]
+        fn withdrawals_allowed_when_paused_by_default() {
+            let (mut contract, accounts) = contract_with_shares(&[10000]);
+            assert!(contract.get_pause_policy());
+            
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(1000));
+            contract.receive_payment().unwrap();
+            set_contract_balance(U256::from(1000));
+            
+            contract.pause().unwrap();
+            assert_eq!(contract.get_pause_state(), (true, false));
+            assert_eq!(contract.receive_payment(), Err(Error::ContractPaused));
+            assert_eq!(contract.add_beneficiary(accounts[0], 1), Err(Error::ContractPaused));
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts[0]);
+            assert!(contract.withdraw(U256::from(100)).is_ok());
+            assert!(contract.withdraw_all().is_ok());
+        }
+
+        #[ink::test]
+        fn pause_policy_can_block_withdrawals() {
+            let (mut contract, accounts) = contract_with_shares(&[10000]);
+            
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(1000));
+            contract.receive_payment().unwrap();
+            set_contract_balance(U256::from(1000));
+            
+            contract.set_pause_policy(false).unwrap();
+            contract.pause().unwrap();
+            assert_eq!(contract.get_pause_state(), (true, true));
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts[0]);
+            assert_eq!(contract.withdraw(U256::from(100)), Err(Error::ContractPaused));
+            assert_eq!(contract.set_pause_policy(true), Err(Error::Unauthorized));
+        }
+
+        #[ink::test]
+        fn rescue_funds_excludes_pending_balances() {
+            let (mut contract, _) = contract_with_shares(&[6000]);
+            let rescuer = H160::from([0x42; 20]);
+            
+            // 600 pending for the beneficiary, 400 in the undistributed pool
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(1000));
+            contract.receive_payment().unwrap();
+            
+            set_contract_balance(U256::from(1000));
+            assert_eq!(contract.rescue_funds(rescuer), Err(Error::NoFundsAvailable));
+            
+            // 250 arrives via a bare transfer
+            set_contract_balance(U256::from(1250));
+            assert_eq!(contract.rescue_funds(rescuer), Ok(U256::from(250)));
+            assert_eq!(contract.get_undistributed(), U256::from(400));
+        }
+
+        #[ink::test]
+        fn rescue_funds_is_owner_only() {
+            let mut contract = SplitPayment::new();
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            set_contract_balance(U256::from(100));
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.rescue_funds(accounts.bob), Err(Error::Unauthorized));
+        }
+
+        #[ink::test]
+        fn sync_balance_distributes_untracked_funds() {
+            let (mut contract, accounts) = contract_with_shares(&[5000, 5000]);
+            
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(1000));
+            contract.receive_payment().unwrap();
+            set_contract_balance(U256::from(1000));
+            assert_eq!(contract.sync_balance(), Err(Error::NoFundsAvailable));
+            
+            // Out-of-band transfer of 600
+            set_contract_balance(U256::from(1600));
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(H160::from([0x42; 20]));
+            assert_eq!(contract.sync_balance(), Ok(U256::from(600)));
+            
+            assert_eq!(pending_of(&contract, &accounts), vec![U256::from(800), U256::from(800)]);
+            let (received, _, _) = contract.get_stats();
+            assert_eq!(received, U256::from(1600));
+        }
+
+        #[ink::test]
+        fn zero_value_payment_is_rejected_silently() {
+            let (mut contract, accounts) = contract_with_shares(&[10000]);
+            let events_before = ink::env::test::recorded_events().count();
+            
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::zero());
+            assert_eq!(contract.receive_payment(), Err(Error::ZeroAmount));
+            
+            assert_eq!(ink::env::test::recorded_events().count(), events_before);
+            let (received, distributed, _) = contract.get_stats();
+            assert_eq!(received, U256::zero());
+            assert_eq!(distributed, U256::zero());
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts[0]);
+            assert_eq!(contract.withdraw(U256::zero()), Err(Error::ZeroAmount));
+            assert_eq!(contract.approve(H160::from([0x42; 20]), U256::zero(), None), Err(Error::ZeroAmount));
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(H160::from([0x42; 20]));
+            assert_eq!(contract.withdraw_from(accounts[0], U256::zero(), false), Err(Error::ZeroAmount));
+        }
+
+        #[ink::test]
+        fn payment_below_minimum_reverts() {
+            let (mut contract, accounts) = contract_with_shares(&[10000]);
+            
+            assert_eq!(contract.get_min_payment(), U256::zero());
+            contract.set_min_payment(U256::from(100)).unwrap();
+            assert_eq!(contract.get_min_payment(), U256::from(100));
+            
+            // The error reverts the call, so the value is never kept or credited
+            let events_before = ink::env::test::recorded_events().count();
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(99));
+            assert_eq!(contract.receive_payment(), Err(Error::PaymentTooSmall));
+            assert_eq!(ink::env::test::recorded_events().count(), events_before);
+            assert_eq!(pending_of(&contract, &accounts), vec![U256::zero()]);
+            
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(100));
+            assert!(contract.receive_payment().is_ok());
+            assert_eq!(pending_of(&contract, &accounts), vec![U256::from(100)]);
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts[0]);
+            assert_eq!(contract.set_min_payment(U256::zero()), Err(Error::Unauthorized));
+        }
+
+        #[ink::test]
+        fn receive_payment_with_memo_works() {
+            let (mut contract, accounts) = contract_with_shares(&[10000]);
+            let memo = b"INV-2024-0042".to_vec();
+            
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(500));
+            assert!(contract.receive_payment_with_memo(memo.clone()).is_ok());
+            assert_eq!(pending_of(&contract, &accounts), vec![U256::from(500)]);
+            
+            let events = ink::env::test::recorded_events().collect::<Vec<_>>();
+            let event = <FundsReceived as ink::scale::Decode>::decode(
+                &mut &events.last().unwrap().data[..]
+            ).unwrap();
+            assert_eq!(event.amount, U256::from(500));
+            assert_eq!(event.memo, Some(memo));
+        }
+
+        #[ink::test]
+        fn overlong_memo_is_rejected() {
+            let mut contract = SplitPayment::new();
+            
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(500));
+            assert!(contract.receive_payment_with_memo(vec![0u8; MAX_MEMO_LEN]).is_ok());
+            assert_eq!(
+                contract.receive_payment_with_memo(vec![0u8; MAX_MEMO_LEN + 1]),
+                Err(Error::MemoTooLong)
+            );
+        }
+
+        #[ink::test]
+        fn payment_history_is_recorded() {
+            let mut contract = SplitPayment::new();
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            for amount in 1..=3u64 {
+                ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(amount * 100));
+                contract.receive_payment().unwrap();
+            }
+            
+            assert_eq!(contract.get_payment_count(), 3);
+            let record = contract.get_payment(1).unwrap();
+            assert_eq!(record.payer, accounts.bob);
+            assert_eq!(record.amount, U256::from(200));
+            
+            let page = contract.get_payments(1, 10);
+            assert_eq!(page.len(), 2);
+            assert_eq!(page[1].amount, U256::from(300));
+            assert!(contract.get_payments(3, 10).is_empty());
+        }
+
+        #[ink::test]
+        fn payment_history_retention_works() {
+            let mut contract = SplitPayment::new();
+            contract.set_max_history(2).unwrap();
+            
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(100));
+            for _ in 0..4 {
+                contract.receive_payment().unwrap();
+            }
+            
+            assert_eq!(contract.get_payment_count(), 4);
+            assert!(contract.get_payment(1).is_none());
+            assert!(contract.get_payment(2).is_some());
+            assert_eq!(contract.get_payments(0, 10).len(), 2);
+            
+            contract.prune_payments(3).unwrap();
+            assert!(contract.get_payment(2).is_none());
+            assert!(contract.get_payment(3).is_some());
+        }
+
+        #[ink::test]
+        fn lowering_max_history_prunes_in_small_steps() {
+            let mut contract = SplitPayment::new();
+            
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(100));
+            for _ in 0..6 {
+                contract.receive_payment().unwrap();
+            }
+            
+            // Each payment deletes at most `MAX_PRUNE_PER_PAYMENT` excess records
+            contract.set_max_history(1).unwrap();
+            contract.receive_payment().unwrap();
+            assert!(contract.get_payment(1).is_none());
+            assert!(contract.get_payment(2).is_some());
+            contract.receive_payment().unwrap();
+            assert!(contract.get_payment(3).is_none());
+            assert!(contract.get_payment(4).is_some());
+            
+            contract.prune_payments(7).unwrap();
+            assert!(contract.get_payment(6).is_none());
+            assert!(contract.get_payment(7).is_some());
+        }
+
+        #[ink::test]
+        fn contributions_are_tracked_per_payer() {
+            let mut contract = SplitPayment::new();
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(100));
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            contract.receive_payment().unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            contract.receive_payment().unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            contract.receive_payment().unwrap();
+            
+            assert_eq!(contract.get_contribution(accounts.bob), U256::from(200));
+            assert_eq!(contract.get_contribution(accounts.charlie), U256::from(100));
+            assert_eq!(contract.get_contribution(accounts.django), U256::zero());
+            
+            assert_eq!(contract.get_contributor_count(), 2);
+            assert_eq!(contract.get_contributors(0, 10), vec![accounts.bob, accounts.charlie]);
+            assert_eq!(contract.get_contributors(1, 10), vec![accounts.charlie]);
+            assert!(contract.get_contributors(2, 10).is_empty());
+        }
+
+        #[ink::test]
+        fn payer_whitelist_is_enforced() {
+            let mut contract = SplitPayment::new();
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let owner = contract.get_owner();
+            
+            contract.set_payer_whitelist_enabled(true).unwrap();
+            contract.add_allowed_payer(accounts.bob).unwrap();
+            assert!(contract.is_allowed_payer(accounts.bob));
+            
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(100));
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            assert_eq!(contract.receive_payment(), Err(Error::Unauthorized));
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert!(contract.receive_payment().is_ok());
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(owner);
+            contract.remove_allowed_payer(accounts.bob).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.receive_payment(), Err(Error::Unauthorized));
+        }
+
+        #[ink::test]
+        fn payer_whitelist_disabled_allows_anyone() {
+            let mut contract = SplitPayment::new();
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            
+            assert!(!contract.is_payer_whitelist_enabled());
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(100));
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            assert!(contract.receive_payment().is_ok());
+            assert_eq!(contract.add_allowed_payer(accounts.charlie), Err(Error::Unauthorized));
+        }
+
+        #[ink::test]
+        fn preview_matches_actual_distribution() {
+            let (mut contract, accounts) = contract_with_shares(&[3333, 3333, 2500]);
+            let amount = U256::from(1_001);
+            
+            let (allocation, leftover) = contract.preview_distribution(amount);
+            
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(amount);
+            contract.receive_payment().unwrap();
+            
+            let expected: Vec<(H160, U256)> = accounts
+                .iter()
+                .copied()
+                .zip(pending_of(&contract, &accounts))
+                .collect();
+            assert_eq!(allocation, expected);
+            assert_eq!(leftover, contract.get_undistributed());
+        }
+
+        #[ink::test]
+        fn mapping_layout_add_remove_withdraw() {
+            let (mut contract, accounts) = contract_with_shares(&[2000, 3000, 5000]);
+            
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(1000));
+            contract.receive_payment().unwrap();
+            set_contract_balance(U256::from(1000));
+            
+            // Removing from the middle keeps the remaining order intact
+            contract.remove_beneficiary(accounts[1], false).unwrap();
+            assert!(contract.get_beneficiary(accounts[1]).is_none());
+            let listed: Vec<H160> = contract.get_beneficiaries().iter().map(|b| b.account).collect();
+            assert_eq!(listed, vec![accounts[0], accounts[2]]);
+            assert_eq!(contract.get_total_shares(), 7000);
+            
+            // Re-adding goes to the end of the list
+            contract.add_beneficiary(accounts[1], 3000).unwrap();
+            let listed: Vec<H160> = contract.get_beneficiaries().iter().map(|b| b.account).collect();
+            assert_eq!(listed, vec![accounts[0], accounts[2], accounts[1]]);
+            assert_eq!(contract.get_beneficiary(accounts[1]).unwrap().pending_balance, U256::zero());
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts[2]);
+            contract.withdraw(U256::from(200)).unwrap();
+            let beneficiary = contract.get_beneficiary(accounts[2]).unwrap();
+            assert_eq!(beneficiary.pending_balance, U256::from(300));
+            assert_eq!(beneficiary.total_withdrawn, U256::from(200));
+            
+            // Other records are untouched
+            assert_eq!(contract.get_beneficiary(accounts[0]).unwrap().pending_balance, U256::from(200));
+        }
+
+        #[ink::test]
+        fn balances_are_stitched_into_beneficiary() {
+            let (mut contract, accounts) = contract_with_shares(&[10000]);
+            
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(1000));
+            contract.receive_payment().unwrap();
+            set_contract_balance(U256::from(1000));
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts[0]);
+            contract.withdraw(U256::from(300)).unwrap();
+            
+            // The stored share record is never rewritten by a withdrawal
+            let stored = contract.beneficiary_data.get(accounts[0]).unwrap();
+            assert_eq!(stored.pending_balance, U256::zero());
+            assert_eq!(contract.balances.get(accounts[0]), Some((U256::from(700), U256::from(300))));
+            
+            let beneficiary = contract.get_beneficiary(accounts[0]).unwrap();
+            assert_eq!(beneficiary.share_bps, 10000);
+            assert_eq!(beneficiary.pending_balance, U256::from(700));
+            assert_eq!(beneficiary.total_withdrawn, U256::from(300));
+            assert_eq!(contract.get_beneficiaries(), vec![beneficiary]);
+        }
+
+        #[ink::test]
+        fn beneficiary_limit_is_enforced() {
+            let mut contract = SplitPayment::new_with_max_beneficiaries(2);
+            assert_eq!(contract.get_max_beneficiaries(), 2);
+            
+            contract.add_beneficiary(H160::from([0x10; 20]), 1000).unwrap();
+            contract.add_beneficiary(H160::from([0x11; 20]), 1000).unwrap();
+            assert_eq!(
+                contract.add_beneficiary(H160::from([0x12; 20]), 1000),
+                Err(Error::TooManyBeneficiaries)
+            );
+            
+            // Cannot lower the limit below the current count
+            assert_eq!(contract.set_max_beneficiaries(1), Err(Error::TooManyBeneficiaries));
+            
+            contract.set_max_beneficiaries(3).unwrap();
+            assert!(contract.add_beneficiary(H160::from([0x12; 20]), 1000).is_ok());
+        }
+
+        #[ink::test]
+        fn default_beneficiary_limit() {
+            let contract = SplitPayment::new();
+            assert_eq!(contract.get_max_beneficiaries(), DEFAULT_MAX_BENEFICIARIES);
+        }
+
+        #[ink::test]
+        fn paginated_beneficiary_listing() {
+            let shares = [100u16; 60];
+            let (contract, accounts) = contract_with_shares(&shares);
+            
+            assert_eq!(contract.get_beneficiary_count(), 60);
+            
+            // Limit is capped
+            let page = contract.get_beneficiary_accounts_paged(0, 1000);
+            assert_eq!(page.len(), MAX_PAGE_SIZE as usize);
+            assert_eq!(page[..], accounts[..MAX_PAGE_SIZE as usize]);
+            
+            let tail = contract.get_beneficiaries_paged(55, 10);
+            assert_eq!(tail.len(), 5);
+            assert_eq!(tail[0].account, accounts[55]);
+            
+            assert!(contract.get_beneficiaries_paged(60, 10).is_empty());
+            assert!(contract.get_beneficiary_accounts_paged(u32::MAX, 10).is_empty());
+        }
+
+        #[ink::test]
+        fn pending_balance_queries_work() {
+            let (mut contract, accounts) = contract_with_shares(&[2500, 7500]);
+            let outsider = H160::from([0x42; 20]);
+            
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(1000));
+            contract.receive_payment().unwrap();
+            
+            assert!(contract.is_beneficiary(accounts[0]));
+            assert!(!contract.is_beneficiary(outsider));
+            assert_eq!(contract.get_pending_balance(accounts[1]), U256::from(750));
+            assert_eq!(contract.get_pending_balance(outsider), U256::zero());
+            assert_eq!(contract.get_total_pending(), U256::from(1000));
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts[0]);
+            assert_eq!(contract.get_my_pending_balance(), U256::from(250));
+        }
+
+        #[ink::test]
+        fn stats_v2_aggregates_balances() {
+            let (mut contract, accounts) = contract_with_shares(&[5000, 5000]);
+            
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(1000));
+            contract.receive_payment().unwrap();
+            set_contract_balance(U256::from(1000));
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts[0]);
+            contract.withdraw(U256::from(200)).unwrap();
+            
+            let stats = contract.get_stats_v2();
+            assert_eq!(stats.total_received, U256::from(1000));
+            assert_eq!(stats.total_distributed, U256::from(1000));
+            assert_eq!(stats.total_pending, U256::from(800));
+            assert_eq!(stats.total_withdrawn, U256::from(200));
+            assert_eq!(stats.contract_balance, U256::from(800));
+            assert_eq!(stats.beneficiary_count, 2);
+            assert_eq!(stats.payment_count, 1);
+            assert!(!stats.paused);
+            
+            // The tuple form is still available
+            assert_eq!(contract.get_stats(), (U256::from(1000), U256::from(1000), U256::from(800)));
+        }
+
+        #[ink::test]
+        fn insufficient_errors_carry_amounts() {
+            let (mut contract, accounts) = contract_with_shares(&[5000, 5000]);
+            let spender = H160::from([0x42; 20]);
+            
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(1000));
+            contract.receive_payment().unwrap();
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts[0]);
+            let err = contract.withdraw(U256::from(501)).unwrap_err();
+            assert_eq!(
+                err,
+                Error::InsufficientBalance { requested: U256::from(501), available: U256::from(500) }
+            );
+            assert_eq!(err.error_code(), 1);
+            
+            contract.approve(spender, U256::from(100), None).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(spender);
+            assert_eq!(
+                contract.withdraw_from(accounts[0], U256::from(150), false),
+                Err(Error::InsufficientAllowance { requested: U256::from(150), available: U256::from(100) })
+            );
+            assert_eq!(
+                contract.withdraw_from(accounts[1], U256::from(150), false),
+                Err(Error::InsufficientAllowance { requested: U256::from(150), available: U256::zero() })
+            );
+        }
+
+        #[ink::test]
+        fn zero_address_and_duplicate_are_distinct_errors() {
+            let (mut contract, accounts) = contract_with_shares(&[5000]);
+            
+            assert_eq!(contract.add_beneficiary(H160::zero(), 1000), Err(Error::ZeroAddress));
+            assert_eq!(contract.add_beneficiary(accounts[0], 1000), Err(Error::BeneficiaryAlreadyExists));
+            assert_eq!(
+                contract.add_manager(H160::zero(), ManagerPermissions::all(), None),
+                Err(Error::ZeroAddress)
+            );
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts[0]);
+            assert_eq!(contract.approve(H160::zero(), U256::from(10), None), Err(Error::ZeroAddress));
+        }
+
+        #[ink::test]
+        fn increase_and_decrease_allowance_work() {
+            let (mut contract, accounts) = contract_with_shares(&[5000]);
+            let spender = H160::from([0x42; 20]);
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts[0]);
+            contract.approve(spender, U256::from(100), Some(1_000)).unwrap();
+            
+            contract.increase_allowance(spender, U256::from(50)).unwrap();
+            assert_eq!(contract.get_approval(accounts[0], spender), U256::from(150));
+            
+            let events = ink::env::test::recorded_events();
+            let event = <ApprovalGranted as ink::scale::Decode>::decode(&mut &events.last().unwrap().data[..]).unwrap();
+            assert_eq!(event.amount, U256::from(150));
+            assert_eq!(event.expires_at, Some(1_000));
+            
+            assert_eq!(
+                contract.decrease_allowance(spender, U256::from(200)),
+                Err(Error::InsufficientAllowance { requested: U256::from(200), available: U256::from(150) })
+            );
+            contract.decrease_allowance(spender, U256::from(150)).unwrap();
+            assert_eq!(contract.get_approval(accounts[0], spender), U256::zero());
+            
+            // Non-beneficiaries cannot grant allowances
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(spender);
+            assert_eq!(contract.increase_allowance(accounts[0], U256::from(1)), Err(Error::Unauthorized));
+        }
+
+        #[ink::test]
+        fn approve_with_expected_is_compare_and_set() {
+            let (mut contract, accounts) = contract_with_shares(&[5000]);
+            let spender = H160::from([0x42; 20]);
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts[0]);
+            contract.approve(spender, U256::from(100), None).unwrap();
+            
+            assert_eq!(
+                contract.approve_with_expected(spender, U256::from(90), U256::from(50), None),
+                Err(Error::AllowanceMismatch { expected: U256::from(90), current: U256::from(100) })
+            );
+            assert!(contract.approve_with_expected(spender, U256::from(100), U256::from(50), None).is_ok());
+            assert_eq!(contract.get_approval(accounts[0], spender), U256::from(50));
+        }
+
+        #[ink::test]
+        fn approvals_can_be_listed_and_revoked_together() {
+            let (mut contract, accounts) = contract_with_shares(&[5000, 5000]);
+            let first = H160::from([0x42; 20]);
+            let second = H160::from([0x43; 20]);
+            
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(1000));
+            contract.receive_payment().unwrap();
+            set_contract_balance(U256::from(1000));
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts[0]);
+            contract.approve(first, U256::from(100), None).unwrap();
+            contract.approve(second, U256::from(50), None).unwrap();
+            
+            // Overwriting does not duplicate the index entry
+            contract.approve(first, U256::from(80), None).unwrap();
+            let approvals = contract.get_approvals_of(accounts[0]);
+            assert_eq!(approvals.len(), 2);
+            assert_eq!(approvals[0].spender, first);
+            assert_eq!(approvals[0].amount, U256::from(80));
+            
+            // Spending an approval down to zero drops it from the index
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(second);
+            contract.withdraw_from(accounts[0], U256::from(50), false).unwrap();
+            assert_eq!(contract.get_approvals_of(accounts[0]).len(), 1);
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts[0]);
+            contract.approve(second, U256::from(10), None).unwrap();
+            assert_eq!(contract.revoke_all_approvals(), Ok(2));
+            assert!(contract.get_approvals_of(accounts[0]).is_empty());
+            assert_eq!(contract.get_approval(accounts[0], first), U256::zero());
+        }
+
+        #[ink::test]
+        fn expired_approval_is_reported_consistently() {
+            let (mut contract, accounts) = contract_with_shares(&[5000]);
+            let spender = H160::from([0x42; 20]);
+            
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(1000));
+            contract.receive_payment().unwrap();
+            
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(100);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts[0]);
+            contract.approve(spender, U256::from(100), Some(200)).unwrap();
+            
+            assert!(contract.is_approval_valid(accounts[0], spender));
+            assert_eq!(contract.get_approval(accounts[0], spender), U256::from(100));
+            
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(201);
+            assert!(!contract.is_approval_valid(accounts[0], spender));
+            assert_eq!(contract.get_approval(accounts[0], spender), U256::zero());
+            
+            // Details still show what was granted and when it lapsed
+            let details = contract.get_approval_details(accounts[0], spender).unwrap();
+            assert_eq!(details.amount, U256::from(100));
+            assert_eq!(details.expires_at, Some(200));
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(spender);
+            assert_eq!(contract.withdraw_from(accounts[0], U256::from(10), false), Err(Error::ApprovalExpired));
+        }
+
+        #[ink::test]
+        fn cleanup_expired_approvals_removes_only_expired() {
+            let (mut contract, accounts) = contract_with_shares(&[5000]);
+            let expiring = H160::from([0x42; 20]);
+            let lasting = H160::from([0x43; 20]);
+            
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(100);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts[0]);
+            contract.approve(expiring, U256::from(100), Some(200)).unwrap();
+            contract.approve(lasting, U256::from(100), None).unwrap();
+            
+            // Nothing has expired yet
+            assert_eq!(contract.cleanup_expired_approvals(accounts[0]), Ok(0));
+            
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(201);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(H160::from([0x44; 20]));
+            assert_eq!(contract.cleanup_expired_approvals(accounts[0]), Ok(1));
+            
+            assert_eq!(contract.get_approval_details(accounts[0], expiring), None);
+            assert_eq!(contract.get_approvals_of(accounts[0]).len(), 1);
+            assert!(contract.is_approval_valid(accounts[0], lasting));
+        }
+
+        #[ink::test]
+        fn force_revoke_approvals_is_owner_only() {
+            let (mut contract, accounts) = contract_with_shares(&[5000]);
+            let spender = H160::from([0x42; 20]);
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts[0]);
+            contract.approve(spender, U256::from(100), None).unwrap();
+            assert_eq!(contract.force_revoke_approvals(accounts[0]), Err(Error::Unauthorized));
+            
+            let owner = contract.get_owner();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(owner);
+            assert_eq!(contract.force_revoke_approvals(accounts[0]), Ok(1));
+            assert!(contract.get_approvals_of(accounts[0]).is_empty());
+        }
+
+        #[ink::test]
+        fn frozen_beneficiary_accrues_but_cannot_move_funds() {
+            let (mut contract, accounts) = contract_with_shares(&[5000, 5000]);
+            let spender = H160::from([0x42; 20]);
+            let owner = contract.get_owner();
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts[0]);
+            contract.approve(spender, U256::from(100), None).unwrap();
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(owner);
+            assert!(contract.freeze_beneficiary(accounts[0], true).is_ok());
+            assert!(contract.get_beneficiary(accounts[0]).unwrap().frozen);
+            
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(1000));
+            contract.receive_payment().unwrap();
+            set_contract_balance(U256::from(1000));
+            assert_eq!(pending_of(&contract, &accounts), vec![U256::from(500), U256::from(500)]);
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts[0]);
+            assert_eq!(contract.withdraw(U256::from(100)), Err(Error::BeneficiaryFrozen));
+            assert_eq!(contract.approve(spender, U256::from(50), None), Err(Error::BeneficiaryFrozen));
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(spender);
+            assert_eq!(contract.withdraw_from(accounts[0], U256::from(50), false), Err(Error::BeneficiaryFrozen));
+            
+            // Push payouts skip the frozen account
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(owner);
+            assert_eq!(contract.payout_all(), Ok(1));
+            assert_eq!(pending_of(&contract, &accounts), vec![U256::from(500), U256::zero()]);
+            
+            assert!(contract.freeze_beneficiary(accounts[0], false).is_ok());
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts[0]);
+            assert!(contract.withdraw(U256::from(100)).is_ok());
+        }
+
+        #[ink::test]
+        fn withdraw_from_can_deliver_to_either_party() {
+            let (mut contract, accounts) = contract_with_shares(&[5000]);
+            let spender = H160::from([0x42; 20]);
+            
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(1000));
+            contract.receive_payment().unwrap();
+            set_contract_balance(U256::from(1000));
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts[0]);
+            contract.approve(spender, U256::from(300), None).unwrap();
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(spender);
+            assert!(contract.withdraw_from(accounts[0], U256::from(100), false).is_ok());
+            let events = ink::env::test::recorded_events();
+            let event = <WithdrawalByApproval as ink::scale::Decode>::decode(&mut &events.last().unwrap().data[..]).unwrap();
+            assert_eq!(event.recipient, spender);
+            
+            assert!(contract.withdraw_from(accounts[0], U256::from(100), true).is_ok());
+            let events = ink::env::test::recorded_events();
+            let event = <WithdrawalByApproval as ink::scale::Decode>::decode(&mut &events.last().unwrap().data[..]).unwrap();
+            assert_eq!(event.recipient, accounts[0]);
+            assert_eq!(event.spender, spender);
+            
+            // Both paths burn the approval and debit the beneficiary
+            assert_eq!(contract.get_approval(accounts[0], spender), U256::from(100));
+            assert_eq!(pending_of(&contract, &accounts), vec![U256::from(800)]);
+        }
+
+        #[ink::test]
+        fn claim_for_pays_the_beneficiary_not_the_caller() {
+            let (mut contract, accounts) = contract_with_shares(&[5000, 5000]);
+            let keeper = H160::from([0x42; 20]);
+            
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(1000));
+            contract.receive_payment().unwrap();
+            set_contract_balance(U256::from(1000));
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(keeper);
+            assert_eq!(contract.claim_for(accounts[0]), Ok(U256::from(500)));
+            
+            let events = ink::env::test::recorded_events();
+            let event = <Withdrawal as ink::scale::Decode>::decode(&mut &events.last().unwrap().data[..]).unwrap();
+            assert_eq!(event.beneficiary, accounts[0]);
+            assert_eq!(event.destination, accounts[0]);
+            assert_eq!(event.triggered_by, keeper);
+            
+            assert_eq!(contract.get_beneficiary(accounts[0]).unwrap().total_withdrawn, U256::from(500));
+            assert_eq!(contract.claim_for(accounts[0]), Err(Error::NoFundsAvailable));
+            assert_eq!(contract.claim_for(keeper), Err(Error::BeneficiaryNotFound));
+        }
+
+        #[ink::test]
+        fn claim_for_many_skips_failures() {
+            let (mut contract, accounts) = contract_with_shares(&[5000, 5000]);
+            
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(1000));
+            contract.receive_payment().unwrap();
+            set_contract_balance(U256::from(1000));
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(H160::from([0x42; 20]));
+            contract.claim_for(accounts[1]).unwrap();
+            
+            // Unknown account and an already-claimed beneficiary are skipped
+            let batch = vec![accounts[0], H160::from([0x43; 20]), accounts[1]];
+            assert_eq!(contract.claim_for_many(batch), Ok(1));
+            assert_eq!(pending_of(&contract, &accounts), vec![U256::zero(), U256::zero()]);
+        }
+
+        #[ink::test]
+        fn keeper_fee_is_paid_to_third_parties_only() {
+            let (mut contract, accounts) = contract_with_shares(&[5000, 5000]);
+            let keeper = H160::from([0x42; 20]);
+            
+            assert_eq!(contract.set_keeper_fee_bps(MAX_KEEPER_FEE_BPS + 1), Err(Error::FeeTooHigh));
+            assert!(contract.set_keeper_fee_bps(MAX_KEEPER_FEE_BPS).is_ok());
+            contract.set_distribution_mode(DistributionMode::Manual).unwrap();
+            
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(10_000));
+            contract.receive_payment().unwrap();
+            set_contract_balance(U256::from(10_000));
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(keeper);
+            assert_eq!(contract.distribute(), Ok(U256::from(10_000)));
+            assert_eq!(pending_of(&contract, &accounts), vec![U256::from(4950), U256::from(4950)]);
+            
+            let events = ink::env::test::recorded_events();
+            let event = <FundsDistributed as ink::scale::Decode>::decode(&mut &events.last().unwrap().data[..]).unwrap();
+            assert_eq!(event.keeper_fee, U256::from(100));
+            
+            // The owner triggering it pays no fee
+            let owner = contract.get_owner();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(owner);
+            contract.receive_payment().unwrap();
+            assert_eq!(contract.distribute(), Ok(U256::from(10_000)));
+            assert_eq!(pending_of(&contract, &accounts), vec![U256::from(9950), U256::from(9950)]);
+        }
+
+        #[ink::test]
+        fn zero_keeper_fee_distributes_everything() {
+            let (mut contract, accounts) = contract_with_shares(&[5000, 5000]);
+            contract.set_distribution_mode(DistributionMode::Manual).unwrap();
+            
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(1000));
+            contract.receive_payment().unwrap();
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(H160::from([0x42; 20]));
+            assert_eq!(contract.distribute(), Ok(U256::from(1000)));
+            assert_eq!(pending_of(&contract, &accounts), vec![U256::from(500), U256::from(500)]);
+        }
+
+        #[ink::test]
+        fn withdrawal_limit_resets_with_the_window() {
+            let (mut contract, accounts) = contract_with_shares(&[5000, 5000]);
+            let spender = H160::from([0x42; 20]);
+            
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(1000));
+            contract.receive_payment().unwrap();
+            set_contract_balance(U256::from(1000));
+            
+            assert!(contract.set_withdrawal_limit(Some((U256::from(100), 1_000))).is_ok());
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(5_000);
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts[0]);
+            assert!(contract.withdraw(U256::from(60)).is_ok());
+            assert_eq!(
+                contract.withdraw(U256::from(50)),
+                Err(Error::WithdrawalLimitExceeded { retry_after: 6_000 })
+            );
+            
+            // Approved spenders draw from the same window
+            contract.approve(spender, U256::from(100), None).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(spender);
+            assert!(contract.withdraw_from(accounts[0], U256::from(40), false).is_ok());
+            assert_eq!(
+                contract.withdraw_from(accounts[0], U256::from(1), false),
+                Err(Error::WithdrawalLimitExceeded { retry_after: 6_000 })
+            );
+            
+            // Windows are tracked per beneficiary
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts[1]);
+            assert!(contract.withdraw(U256::from(100)).is_ok());
+            
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(6_000);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts[0]);
+            assert!(contract.withdraw(U256::from(100)).is_ok());
+            assert_eq!(contract.get_withdrawal_window(accounts[0]), (U256::from(100), 6_000));
+        }
+
+        #[ink::test]
+        fn withdrawal_cooldown_is_enforced() {
+            let (mut contract, accounts) = contract_with_shares(&[5000]);
+            
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(1000));
+            contract.receive_payment().unwrap();
+            set_contract_balance(U256::from(1000));
+            
+            assert!(contract.set_withdrawal_cooldown(1_000).is_ok());
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(10_000);
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts[0]);
+            assert!(contract.withdraw(U256::from(100)).is_ok());
+            assert_eq!(contract.get_beneficiary(accounts[0]).unwrap().last_withdrawal_at, Some(10_000));
+            
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(10_400);
+            assert_eq!(contract.withdraw(U256::from(100)), Err(Error::CooldownActive { remaining_ms: 600 }));
+            assert_eq!(contract.withdraw_all(), Err(Error::CooldownActive { remaining_ms: 600 }));
+            
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(11_000);
+            assert!(contract.withdraw(U256::from(100)).is_ok());
+        }
+
+        #[ink::test]
+        fn vesting_releases_credited_funds_linearly() {
+            let (mut contract, accounts) = contract_with_shares(&[5000]);
+            let vested = H160::from([0x42; 20]);
+            let schedule = VestingSchedule { start: 1_000, cliff_ms: 1_000, duration_ms: 4_000 };
+            
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(1_000);
+            assert!(contract.add_beneficiary_with_vesting(vested, 5000, schedule).is_ok());
+            assert_eq!(contract.get_beneficiary(vested).unwrap().vesting, Some(schedule));
+            
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(2000));
+            contract.receive_payment().unwrap();
+            set_contract_balance(U256::from(2000));
+            
+            // Before the cliff nothing is withdrawable, though the balance is credited
+            assert_eq!(contract.get_pending_balance(vested), U256::from(1000));
+            assert_eq!(contract.get_withdrawable(vested), U256::zero());
+            assert_eq!(contract.get_withdrawable(accounts[0]), U256::from(1000));
+            
+            // Halfway through the schedule
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(3_000);
+            assert_eq!(contract.get_withdrawable(vested), U256::from(500));
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(vested);
+            assert_eq!(
+                contract.withdraw(U256::from(600)),
+                Err(Error::InsufficientBalance { requested: U256::from(600), available: U256::from(500) })
+            );
+            assert_eq!(contract.withdraw_all(), Ok(U256::from(500)));
+            assert_eq!(contract.get_withdrawable(vested), U256::zero());
+            
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(5_000);
+            assert_eq!(contract.get_withdrawable(vested), U256::from(500));
+        }
+
+        #[ink::test]
+        fn removing_vested_beneficiary_can_forfeit_locked_funds() {
+            let (mut contract, _) = contract_with_shares(&[5000]);
+            let vested = H160::from([0x42; 20]);
+            let schedule = VestingSchedule { start: 0, cliff_ms: 0, duration_ms: 4_000 };
+            
+            contract.add_beneficiary_with_vesting(vested, 5000, schedule).unwrap();
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(2000));
+            contract.receive_payment().unwrap();
+            set_contract_balance(U256::from(2000));
+            
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(1_000);
+            assert!(contract.remove_beneficiary(vested, true).is_ok());
+            
+            let events = ink::env::test::recorded_events();
+            let event = <BeneficiaryRemoved as ink::scale::Decode>::decode(&mut &events.last().unwrap().data[..]).unwrap();
+            assert_eq!(event.forfeited, U256::from(750));
+            assert_eq!(contract.get_undistributed(), U256::from(750));
+        }
+
+        #[ink::test]
+        fn locked_funds_cannot_be_withdrawn() {
+            let (mut contract, accounts) = contract_with_shares(&[5000, 5000]);
+            
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(1000));
+            contract.receive_payment().unwrap();
+            set_contract_balance(U256::from(1000));
+            
+            assert!(contract.set_lock(accounts[0], Some(5_000)).is_ok());
+            assert_eq!(contract.get_beneficiary(accounts[0]).unwrap().locked_until, Some(5_000));
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts[0]);
+            assert_eq!(contract.set_lock(accounts[0], None), Err(Error::Unauthorized));
+            assert_eq!(contract.withdraw(U256::from(100)), Err(Error::FundsLocked(5_000)));
+            assert_eq!(contract.claim_for(accounts[0]), Err(Error::FundsLocked(5_000)));
+            
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(5_000);
+            assert!(contract.withdraw(U256::from(100)).is_ok());
+        }
+
+        #[ink::test]
+        fn removing_locked_beneficiary_parks_funds() {
+            let (mut contract, accounts) = contract_with_shares(&[5000, 5000]);
+            
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(1000));
+            contract.receive_payment().unwrap();
+            set_contract_balance(U256::from(1000));
+            
+            contract.set_lock(accounts[0], Some(5_000)).unwrap();
+            assert!(contract.remove_beneficiary(accounts[0], false).is_ok());
+            assert_eq!(contract.get_parked(accounts[0]), (U256::from(500), 5_000));
+            
+            // Parked funds are not rescuable
+            assert_eq!(contract.rescue_funds(H160::from([0x42; 20])), Err(Error::NoFundsAvailable));
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts[0]);
+            assert_eq!(contract.claim_parked(), Err(Error::FundsLocked(5_000)));
+            
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(5_000);
+            assert_eq!(contract.claim_parked(), Ok(U256::from(500)));
+            assert_eq!(contract.get_parked(accounts[0]), (U256::zero(), 0));
+        }
+
+        #[ink::test]
+        fn parked_funds_count_toward_solvency() {
+            let (mut contract, accounts) = contract_with_shares(&[5000, 5000]);
+            
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(1000));
+            contract.receive_payment().unwrap();
+            set_contract_balance(U256::from(1000));
+            contract.set_lock(accounts[0], Some(5_000)).unwrap();
+            contract.remove_beneficiary(accounts[0], false).unwrap();
+            
+            // Enough for the remaining pending balance, not for the parked funds too
+            set_contract_balance(U256::from(600));
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts[1]);
+            assert_eq!(contract.withdraw(U256::from(500)), Err(Error::Insolvent));
+            
+            assert!(contract.is_paused());
+            
+            // Claims are checked the same way
+            let owner = contract.get_owner();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(owner);
+            contract.unpause().unwrap();
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(5_000);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts[0]);
+            assert_eq!(contract.claim_parked(), Err(Error::Insolvent));
+        }
+
+        #[ink::test]
+        fn delayed_removal_lifecycle() {
+            let (mut contract, accounts) = contract_with_shares(&[5000, 5000]);
+            
+            assert!(contract.set_removal_delay(1_000).is_ok());
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(10_000);
+            
+            assert!(contract.request_remove_beneficiary(accounts[0], false).is_ok());
+            assert_eq!(
+                contract.request_remove_beneficiary(accounts[0], false),
+                Err(Error::RemovalAlreadyRequested)
+            );
+            assert_eq!(contract.get_pending_removals(), vec![(accounts[0], 11_000)]);
+            
+            // Still accruing while the removal is pending
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(1000));
+            contract.receive_payment().unwrap();
+            set_contract_balance(U256::from(1000));
+            assert_eq!(contract.get_pending_balance(accounts[0]), U256::from(500));
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(H160::from([0x42; 20]));
+            assert_eq!(
+                contract.finalize_removal(accounts[0]),
+                Err(Error::RemovalNotDue { effective_at: 11_000 })
+            );
+            
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(11_000);
+            assert!(contract.finalize_removal(accounts[0]).is_ok());
+            assert!(!contract.is_beneficiary(accounts[0]));
+            assert!(contract.get_pending_removals().is_empty());
+            assert_eq!(contract.finalize_removal(accounts[0]), Err(Error::RemovalNotRequested));
+        }
+
+        #[ink::test]
+        fn pending_removal_can_be_cancelled_by_owner() {
+            let (mut contract, accounts) = contract_with_shares(&[5000]);
+            
+            contract.request_remove_beneficiary(accounts[0], false).unwrap();
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts[0]);
+            assert_eq!(contract.cancel_removal(accounts[0]), Err(Error::Unauthorized));
+            
+            let owner = contract.get_owner();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(owner);
+            assert!(contract.cancel_removal(accounts[0]).is_ok());
+            assert_eq!(contract.cancel_removal(accounts[0]), Err(Error::RemovalNotRequested));
+            assert!(contract.is_beneficiary(accounts[0]));
+        }
+
+        #[ink::test]
+        fn beneficiary_can_exit() {
+            let (mut contract, accounts) = contract_with_shares(&[6000, 4000]);
+            
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(1000));
+            contract.receive_payment().unwrap();
+            set_contract_balance(U256::from(1000));
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts[1]);
+            assert!(contract.exit().is_ok());
+            
+            let events = ink::env::test::recorded_events();
+            let event = <BeneficiaryRemoved as ink::scale::Decode>::decode(&mut &events.last().unwrap().data[..]).unwrap();
+            assert_eq!(event.removed_by, accounts[1]);
+            assert_eq!(event.freed_share_bps, 4000);
+            assert_eq!(event.forfeited, U256::zero());
+            
+            assert!(!contract.is_beneficiary(accounts[1]));
+            assert_eq!(contract.get_total_shares(), 6000);
+            assert_eq!(contract.exit(), Err(Error::Unauthorized));
+        }
+
+        #[ink::test]
+        fn removed_share_is_redistributed_proportionally() {
+            let (mut contract, accounts) = contract_with_shares(&[4000, 3000, 2000, 1000]);
+            
+            assert!(contract.remove_beneficiary_and_redistribute(accounts[0]).is_ok());
+            
+            // 3000 : 2000 : 1000 scaled to 10_000 is 5000 : 3333.33 : 1666.67
+            assert_eq!(contract.get_beneficiary(accounts[1]).unwrap().share_bps, 5000);
+            assert_eq!(contract.get_beneficiary(accounts[2]).unwrap().share_bps, 3333);
+            assert_eq!(contract.get_beneficiary(accounts[3]).unwrap().share_bps, 1667);
+            assert_eq!(contract.get_total_shares(), MAX_SHARE_BPS);
+            
+            // The last beneficiary simply takes the whole freed share
+            assert!(contract.remove_beneficiary_and_redistribute(accounts[1]).is_ok());
+            assert!(contract.remove_beneficiary_and_redistribute(accounts[2]).is_ok());
+            assert_eq!(contract.get_beneficiary(accounts[3]).unwrap().share_bps, MAX_SHARE_BPS);
+        }
+
+        #[ink::test]
+        fn set_beneficiaries_swaps_configuration_atomically() {
+            let (mut contract, accounts) = contract_with_shares(&[5000, 5000]);
+            let newcomer = H160::from([0x42; 20]);
+            
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(1000));
+            contract.receive_payment().unwrap();
+            set_contract_balance(U256::from(1000));
+            
+            assert!(contract.set_beneficiaries(vec![(newcomer, 3000), (accounts[1], 7000)]).is_ok());
+            
+            let events = ink::env::test::recorded_events();
+            let event = <BeneficiariesReset as ink::scale::Decode>::decode(&mut &events.last().unwrap().data[..]).unwrap();
+            assert_eq!(event.beneficiary_count, 2);
+            assert_eq!(event.total_shares, MAX_SHARE_BPS);
+            
+            assert!(!contract.is_beneficiary(accounts[0]));
+            assert_eq!(contract.get_beneficiary(accounts[1]).unwrap().share_bps, 7000);
+            assert_eq!(contract.get_pending_balance(accounts[1]), U256::from(500));
+            
+            let listed: Vec<H160> = contract.get_beneficiaries().iter().map(|b| b.account).collect();
+            assert_eq!(listed, vec![newcomer, accounts[1]]);
+        }
+
+        #[ink::test]
+        fn set_beneficiaries_rejects_invalid_configurations() {
+            let (mut contract, accounts) = contract_with_shares(&[5000, 5000]);
+            
+            assert_eq!(
+                contract.set_beneficiaries(vec![(accounts[0], 6000), (accounts[1], 4001)]),
+                Err(Error::InvalidShare { requested: 4001, available: 4000 })
+            );
+            assert_eq!(
+                contract.set_beneficiaries(vec![(accounts[0], 1000), (accounts[0], 1000)]),
+                Err(Error::BeneficiaryAlreadyExists)
+            );
+            assert_eq!(
+                contract.set_beneficiaries(vec![(H160::zero(), 1000)]),
+                Err(Error::ZeroAddress)
+            );
+            
+            // Nothing changed
+            assert_eq!(contract.get_total_shares(), MAX_SHARE_BPS);
+            assert_eq!(contract.get_beneficiary_count(), 2);
+        }
+
+        #[ink::test]
+        fn strict_allocation_refuses_partial_splits() {
+            let mut contract = SplitPayment::new_with_strict_allocation(true);
+            assert!(contract.is_strict_allocation());
+            
+            contract.add_beneficiary(H160::from([0x10; 20]), 7000).unwrap();
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(1000));
+            assert_eq!(contract.receive_payment(), Err(Error::SharesNotFullyAllocated));
+            
+            contract.add_beneficiary(H160::from([0x11; 20]), 3000).unwrap();
+            assert!(contract.receive_payment().is_ok());
+            
+            assert!(contract.set_strict_allocation(false).is_ok());
+            assert!(!contract.is_strict_allocation());
+        }
+
+        #[ink::test]
+        fn finalized_shares_cannot_change() {
+            let (mut contract, accounts) = contract_with_shares(&[7000]);
+            
+            assert_eq!(contract.finalize_shares(), Err(Error::SharesNotFullyAllocated));
+            contract.add_beneficiary(H160::from([0x42; 20]), 3000).unwrap();
+            assert!(contract.finalize_shares().is_ok());
+            assert!(contract.are_shares_locked());
+            
+            assert_eq!(contract.add_beneficiary(H160::from([0x43; 20]), 1), Err(Error::SharesLocked));
+            assert_eq!(contract.remove_beneficiary(accounts[0], false), Err(Error::SharesLocked));
+            assert_eq!(contract.set_beneficiaries(vec![(accounts[0], 10_000)]), Err(Error::SharesLocked));
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts[0]);
+            assert_eq!(contract.exit(), Err(Error::SharesLocked));
+        }
+
+        #[ink::test]
+        fn weighted_model_splits_by_weight() {
+            let mut contract = SplitPayment::new_with_share_model(ShareModel::Weighted);
+            let accounts = [H160::from([0x10; 20]), H160::from([0x11; 20]), H160::from([0x12; 20])];
+            
+            assert_eq!(contract.add_beneficiary(accounts[0], 5000), Err(Error::WrongShareModel));
+            assert_eq!(contract.add_weighted_beneficiary(accounts[0], 0), Err(Error::InvalidWeight));
+            
+            contract.add_weighted_beneficiary(accounts[0], 3).unwrap();
+            contract.add_weighted_beneficiary(accounts[1], 2).unwrap();
+            contract.add_weighted_beneficiary(accounts[2], 1).unwrap();
+            assert_eq!(contract.get_total_weight(), 6);
+            
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(601));
+            contract.receive_payment().unwrap();
+            set_contract_balance(U256::from(601));
+            
+            // 300.5 : 200.33 : 100.17, with the spare unit going to the largest remainder
+            assert_eq!(pending_of(&contract, &accounts), vec![U256::from(301), U256::from(200), U256::from(100)]);
+            assert_eq!(contract.get_undistributed(), U256::zero());
+            
+            // Removing a member implicitly redistributes their weight
+            contract.remove_beneficiary(accounts[0], false).unwrap();
+            assert_eq!(contract.get_total_weight(), 3);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(300));
+            contract.receive_payment().unwrap();
+            assert_eq!(contract.get_pending_balance(accounts[1]), U256::from(400));
+            assert_eq!(contract.get_pending_balance(accounts[2]), U256::from(200));
+        }
+
+        #[ink::test]
+        fn percentage_model_is_the_default() {
+            let mut contract = SplitPayment::new();
+            
+            assert_eq!(contract.get_share_model(), ShareModel::Percentage);
+            assert_eq!(
+                contract.add_weighted_beneficiary(H160::from([0x10; 20]), 1),
+                Err(Error::WrongShareModel)
+            );
+            contract.add_beneficiary(H160::from([0x10; 20]), 2500).unwrap();
+            assert_eq!(contract.get_total_weight(), 2500);
+        }
+
+        #[ink::test]
+        fn capped_tier_is_paid_first() {
+            let (mut contract, accounts) = contract_with_shares(&[2000, 4000, 4000]);
+            
+            assert!(contract.set_tier(accounts[0], 0, Some(U256::from(1000))).is_ok());
+            
+            // Overflow: the lead takes their cap and the rest is split 50/50
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(3000));
+            contract.receive_payment().unwrap();
+            assert_eq!(
+                pending_of(&contract, &accounts),
+                vec![U256::from(1000), U256::from(1000), U256::from(1000)]
+            );
+            
+            // Exactly the cap: nothing is left for the uncapped tier
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(1000));
+            contract.receive_payment().unwrap();
+            assert_eq!(
+                pending_of(&contract, &accounts),
+                vec![U256::from(2000), U256::from(1000), U256::from(1000)]
+            );
+            
+            // Under the cap: the lead takes everything
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(400));
+            contract.receive_payment().unwrap();
+            assert_eq!(
+                pending_of(&contract, &accounts),
+                vec![U256::from(2400), U256::from(1000), U256::from(1000)]
+            );
+        }
+
+        #[ink::test]
+        fn capped_tiers_fill_in_priority_order() {
+            let (mut contract, accounts) = contract_with_shares(&[2000, 2000, 6000]);
+            
+            // Listed first but filled second
+            contract.set_tier(accounts[0], 1, Some(U256::from(200))).unwrap();
+            contract.set_tier(accounts[1], 0, Some(U256::from(100))).unwrap();
+            
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(250));
+            contract.receive_payment().unwrap();
+            assert_eq!(
+                pending_of(&contract, &accounts),
+                vec![U256::from(150), U256::from(100), U256::zero()]
+            );
+            
+            // The last uncapped beneficiary cannot be capped
+            assert_eq!(
+                contract.set_tier(accounts[2], 0, Some(U256::from(1))),
+                Err(Error::NoUncappedBeneficiary)
+            );
+        }
+
+        #[ink::test]
+        fn fixed_amount_is_credited_before_the_split() {
+            let (mut contract, accounts) = contract_with_shares(&[5000, 5000]);
+            let platform = H160::from([0x42; 20]);
+            
+            contract.add_fixed_beneficiary(platform, U256::from(5)).unwrap();
+            assert_eq!(contract.get_total_shares(), 10_000);
+            assert_eq!(contract.get_beneficiary(platform).unwrap().kind, ShareKind::Fixed(U256::from(5)));
+            
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(105));
+            contract.receive_payment().unwrap();
+            
+            assert_eq!(contract.get_pending_balance(platform), U256::from(5));
+            assert_eq!(pending_of(&contract, &accounts), vec![U256::from(50), U256::from(50)]);
+        }
+
+        #[ink::test]
+        fn payment_below_fixed_total_is_clamped_in_list_order() {
+            let (mut contract, accounts) = contract_with_shares(&[10_000]);
+            let first = H160::from([0x42; 20]);
+            let second = H160::from([0x43; 20]);
+            
+            contract.add_fixed_beneficiary(first, U256::from(5)).unwrap();
+            contract.add_fixed_beneficiary(second, U256::from(5)).unwrap();
+            
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(7));
+            contract.receive_payment().unwrap();
+            
+            assert_eq!(contract.get_pending_balance(first), U256::from(5));
+            assert_eq!(contract.get_pending_balance(second), U256::from(2));
+            assert_eq!(pending_of(&contract, &accounts), vec![U256::zero()]);
+            assert_eq!(contract.get_undistributed(), U256::zero());
+        }
+
+        #[ink::test]
+        fn lifetime_cap_straddling_payment_overflows_to_others() {
+            let (mut contract, accounts) = contract_with_shares(&[5000, 5000]);
+            contract.set_lifetime_cap(accounts[0], Some(U256::from(100))).unwrap();
+            
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(150));
+            contract.receive_payment().unwrap();
+            assert_eq!(pending_of(&contract, &accounts), vec![U256::from(75), U256::from(75)]);
+            
+            // Only 25 of the next 50 fit under the cap; the rest goes to the other beneficiary
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(100));
+            contract.receive_payment().unwrap();
+            assert_eq!(pending_of(&contract, &accounts), vec![U256::from(100), U256::from(150)]);
+            
+            let events = ink::env::test::recorded_events().collect::<Vec<_>>();
+            assert!(events.iter().any(|event| {
+                <CapReached as ink::scale::Decode>::decode(&mut &event.data[..])
+                    .is_ok_and(|e| e.beneficiary == accounts[0] && e.lifetime_cap == U256::from(100))
+            }));
+            
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(100));
+            contract.receive_payment().unwrap();
+            assert_eq!(pending_of(&contract, &accounts), vec![U256::from(100), U256::from(250)]);
+            assert_eq!(contract.get_beneficiary(accounts[0]).unwrap().total_credited, U256::from(100));
+        }
+
+        #[ink::test]
+        fn lifetime_cap_excess_goes_to_pool_without_takers() {
+            let (mut contract, accounts) = contract_with_shares(&[10_000]);
+            contract.set_lifetime_cap(accounts[0], Some(U256::from(10))).unwrap();
+            
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(30));
+            contract.receive_payment().unwrap();
+            
+            assert_eq!(pending_of(&contract, &accounts), vec![U256::from(10)]);
+            assert_eq!(contract.get_undistributed(), U256::from(20));
+        }
+
+        #[ink::test]
+        fn protocol_fee_is_carved_out_before_the_split() {
+            let (mut contract, accounts) = contract_with_shares(&[5000, 5000]);
+            let owner = contract.get_owner();
+            
+            assert_eq!(contract.set_fee_bps(MAX_FEE_BPS + 1), Err(Error::FeeTooHigh));
+            contract.set_fee_bps(500).unwrap();
+            
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(1000));
+            contract.receive_payment().unwrap();
+            
+            let events = ink::env::test::recorded_events().collect::<Vec<_>>();
+            let event = <FundsReceived as ink::scale::Decode>::decode(&mut &events.last().unwrap().data[..]).unwrap();
+            assert_eq!(event.amount, U256::from(1000));
+            assert_eq!(event.fee, U256::from(50));
+            assert_eq!(event.net_amount, U256::from(950));
+            assert_eq!(contract.get_fee_accrued(), U256::from(50));
+            assert_eq!(pending_of(&contract, &accounts), vec![U256::from(475), U256::from(475)]);
+            
+            // Accrued fees are unaffected by a later rate change
+            contract.set_fee_bps(0).unwrap();
+            assert_eq!(contract.get_fee_accrued(), U256::from(50));
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts[0]);
+            assert_eq!(contract.withdraw_fees(), Err(Error::Unauthorized));
+            
+            set_contract_balance(U256::from(1000));
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(owner);
+            assert_eq!(contract.withdraw_fees(), Ok(U256::from(50)));
+            assert_eq!(contract.get_fee_accrued(), U256::zero());
+        }
+
+        #[ink::test]
+        fn tip_credits_one_beneficiary() {
+            let (mut contract, accounts) = contract_with_shares(&[5000, 5000]);
+            let patron = H160::from([0x42; 20]);
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(patron);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(300));
+            assert!(contract.tip(accounts[1]).is_ok());
+            
+            assert_eq!(pending_of(&contract, &accounts), vec![U256::zero(), U256::from(300)]);
+            assert_eq!(contract.get_beneficiary(accounts[1]).unwrap().total_tips, U256::from(300));
+            assert_eq!(contract.get_stats().0, U256::from(300));
+            
+            let events = ink::env::test::recorded_events().collect::<Vec<_>>();
+            let event = <TipReceived as ink::scale::Decode>::decode(&mut &events.last().unwrap().data[..]).unwrap();
+            assert_eq!(event.from, patron);
+            assert_eq!(event.beneficiary, accounts[1]);
+            assert_eq!(event.amount, U256::from(300));
+            
+            assert_eq!(contract.tip(H160::from([0x43; 20])), Err(Error::BeneficiaryNotFound));
+        }
+
+        #[ink::test]
+        fn tip_is_refused_while_deposits_are_paused() {
+            let (mut contract, accounts) = contract_with_shares(&[10_000]);
+            contract.pause_deposits().unwrap();
+            
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(300));
+            assert_eq!(contract.tip(accounts[0]), Err(Error::ContractPaused));
+        }
+
+        #[ink::test]
+        fn escrowed_payment_is_split_on_release() {
+            let (mut contract, accounts) = contract_with_shares(&[5000, 5000]);
+            let payer = H160::from([0x42; 20]);
+            let owner = contract.get_owner();
+            contract.set_escrow_enabled(true).unwrap();
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(payer);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(400));
+            contract.receive_payment().unwrap();
+            
+            assert_eq!(
+                contract.get_held_payment(0),
+                Some(HeldPayment { id: 0, payer, amount: U256::from(400) })
+            );
+            assert_eq!(contract.get_held_total(), U256::from(400));
+            assert_eq!(pending_of(&contract, &accounts), vec![U256::zero(), U256::zero()]);
+            assert_eq!(contract.release_payment(0), Err(Error::Unauthorized));
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(owner);
+            contract.release_payment(0).unwrap();
+            
+            assert_eq!(pending_of(&contract, &accounts), vec![U256::from(200), U256::from(200)]);
+            assert_eq!(contract.get_held_total(), U256::zero());
+            assert_eq!(contract.get_stats().0, U256::from(400));
+            assert_eq!(contract.release_payment(0), Err(Error::HeldPaymentNotFound));
+        }
+
+        #[ink::test]
+        fn escrowed_payment_can_be_refunded() {
+            let (mut contract, accounts) = contract_with_shares(&[10_000]);
+            let payer = H160::from([0x42; 20]);
+            let owner = contract.get_owner();
+            contract.set_escrow_enabled(true).unwrap();
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(payer);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(400));
+            contract.receive_payment().unwrap();
+            
+            set_contract_balance(U256::from(400));
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(owner);
+            contract.refund_payment(0).unwrap();
+            
+            assert_eq!(contract.get_held_payment(0), None);
+            assert_eq!(contract.get_held_total(), U256::zero());
+            assert_eq!(pending_of(&contract, &accounts), vec![U256::zero()]);
+            assert_eq!(contract.get_stats().0, U256::zero());
+            
+            let events = ink::env::test::recorded_events().collect::<Vec<_>>();
+            let event = <PaymentRefunded as ink::scale::Decode>::decode(&mut &events.last().unwrap().data[..]).unwrap();
+            assert_eq!(event.payer, payer);
+            assert_eq!(event.amount, U256::from(400));
+        }
+
+        #[ink::test]
+        fn campaign_meeting_its_goal_is_split() {
+            let mut contract = SplitPayment::new_with_campaign(U256::from(1000), 5_000);
+            let beneficiary = H160::from([0x10; 20]);
+            contract.add_beneficiary(beneficiary, 10_000).unwrap();
+            
+            for payer in [H160::from([0x42; 20]), H160::from([0x43; 20])] {
+                ink::env::test::set_caller::<ink::env::DefaultEnvironment>(payer);
+                ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(600));
+                contract.receive_payment().unwrap();
+            }
+            assert_eq!(contract.get_pending_balance(beneficiary), U256::zero());
+            assert_eq!(contract.finalize_campaign(), Err(Error::CampaignNotEnded));
+            
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(5_000);
+            assert_eq!(contract.receive_payment(), Err(Error::CampaignEnded));
+            
+            contract.finalize_campaign().unwrap();
+            assert_eq!(contract.get_pending_balance(beneficiary), U256::from(1200));
+            assert_eq!(contract.refund(), Err(Error::RefundsNotOpen));
+            assert_eq!(contract.finalize_campaign(), Err(Error::CampaignNotActive));
+            
+            let status = contract.get_campaign_status().unwrap();
+            assert!(status.finalized);
+            assert!(!status.refunds_open);
+            assert_eq!(status.raised, U256::zero());
+        }
+
+        #[ink::test]
+        fn campaign_missing_its_goal_refunds_once() {
+            let mut contract = SplitPayment::new_with_campaign(U256::from(1000), 5_000);
+            let beneficiary = H160::from([0x10; 20]);
+            let payer = H160::from([0x42; 20]);
+            contract.add_beneficiary(beneficiary, 10_000).unwrap();
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(payer);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(600));
+            contract.receive_payment().unwrap();
+            assert_eq!(contract.get_pledge(payer), U256::from(600));
+            
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(5_000);
+            contract.finalize_campaign().unwrap();
+            assert!(contract.get_campaign_status().unwrap().refunds_open);
+            assert_eq!(contract.get_pending_balance(beneficiary), U256::zero());
+            
+            set_contract_balance(U256::from(600));
+            assert_eq!(contract.refund(), Ok(U256::from(600)));
+            assert_eq!(contract.refund(), Err(Error::NoFundsAvailable));
+            assert_eq!(contract.get_campaign_status().unwrap().raised, U256::zero());
+        }
+
+        #[ink::test]
+        fn payments_outside_acceptance_window_bounce() {
+            let (mut contract, accounts) = contract_with_shares(&[10_000]);
+            
+            contract.set_accepting_from(Some(1_000)).unwrap();
+            contract.set_accepting_until(Some(2_000)).unwrap();
+            assert_eq!(contract.get_acceptance_window(), (Some(1_000), Some(2_000)));
+            assert_eq!(contract.set_accepting_until(Some(999)), Err(Error::InvalidAcceptanceWindow));
+            
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(100));
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(999);
+            assert_eq!(contract.receive_payment(), Err(Error::OutsideAcceptanceWindow));
+            
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(1_000);
+            assert!(contract.receive_payment().is_ok());
+            
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(2_001);
+            assert_eq!(contract.receive_payment(), Err(Error::OutsideAcceptanceWindow));
+            assert_eq!(contract.tip(accounts[0]), Err(Error::OutsideAcceptanceWindow));
+            
+            // Clearing a bound reopens that side only
+            contract.set_accepting_until(None).unwrap();
+            assert!(contract.receive_payment().is_ok());
+            assert_eq!(pending_of(&contract, &accounts), vec![U256::from(200)]);
+        }
+
+        #[ink::test]
+        fn token_withdrawals_check_the_token_balance() {
+            let (mut contract, accounts) = contract_with_shares(&[10_000]);
+            let token = H160::from([0x77; 20]);
+            contract.add_accepted_token(token).unwrap();
+            
+            assert_eq!(contract.receive_token_payment(token, U256::zero()), Err(Error::ZeroAmount));
+            assert_eq!(contract.receive_token_payment(H160::zero(), U256::one()), Err(Error::ZeroAddress));
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts[0]);
+            assert_eq!(contract.get_token_pending(token, accounts[0]), U256::zero());
+            assert_eq!(
+                contract.withdraw_token(token, U256::from(10)),
+                Err(Error::InsufficientBalance { requested: U256::from(10), available: U256::zero() })
+            );
+            assert_eq!(contract.withdraw_all_token(token), Err(Error::NoFundsAvailable));
+        }
+
+        #[ink::test]
+        fn only_accepted_tokens_can_be_paid_in() {
+            let (mut contract, accounts) = contract_with_shares(&[10_000]);
+            let token = H160::from([0x77; 20]);
+            
+            assert_eq!(contract.receive_token_payment(token, U256::from(10)), Err(Error::TokenNotAccepted));
+            
+            contract.add_accepted_token(token).unwrap();
+            contract.add_accepted_token(token).unwrap();
+            assert!(contract.is_accepted_token(token));
+            assert_eq!(contract.get_accepted_tokens(), vec![token]);
+            
+            contract.remove_accepted_token(token).unwrap();
+            assert!(!contract.is_accepted_token(token));
+            assert!(contract.get_accepted_tokens().is_empty());
+            assert_eq!(contract.receive_token_payment(token, U256::from(10)), Err(Error::TokenNotAccepted));
+            
+            // Delisting doesn't block withdrawals of what was already credited
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts[0]);
+            assert_eq!(contract.withdraw_all_token(token), Err(Error::NoFundsAvailable));
+            assert_eq!(contract.add_accepted_token(token), Err(Error::Unauthorized));
+        }
+
+        #[ink::test]
+        fn asset_stats_track_native_flows() {
+            let (mut contract, accounts) = contract_with_shares(&[5000, 4000]);
+            assert!(contract.get_assets().is_empty());
+            
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(1000));
+            contract.receive_payment().unwrap();
+            
+            set_contract_balance(U256::from(1000));
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts[0]);
+            contract.withdraw(U256::from(200)).unwrap();
+            
+            assert_eq!(contract.get_assets(), vec![None]);
+            assert_eq!(
+                contract.get_asset_stats(None),
+                AssetStats {
+                    total_received: U256::from(1000),
+                    total_distributed: U256::from(900),
+                    total_pending: U256::from(700),
+                    total_withdrawn: U256::from(200),
+                }
+            );
+            assert_eq!(contract.get_asset_stats(None).total_pending, contract.get_total_pending());
+            assert_eq!(contract.get_asset_stats(Some(H160::from([0x77; 20]))), AssetStats::default());
+        }
+
+        #[ink::test]
+        fn notify_on_payout_is_set_by_the_beneficiary() {
+            let (mut contract, accounts) = contract_with_shares(&[10_000]);
+            
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(500));
+            contract.receive_payment().unwrap();
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(H160::from([0x42; 20]));
+            assert_eq!(contract.set_notify_on_payout(true), Err(Error::Unauthorized));
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts[0]);
+            contract.set_notify_on_payout(true).unwrap();
+            assert!(contract.get_beneficiary(accounts[0]).unwrap().notify_on_payout);
+            
+            // A plain account destination is paid without a callback
+            set_contract_balance(U256::from(500));
+            assert_eq!(contract.claim_for(accounts[0]), Ok(U256::from(500)));
+            
+            let events = ink::env::test::recorded_events().collect::<Vec<_>>();
+            let event = <Withdrawal as ink::scale::Decode>::decode(&mut &events.last().unwrap().data[..]).unwrap();
+            assert_eq!(event.amount, U256::from(500));
+        }
+
+        #[ink::test]
+        fn forward_as_payment_is_owner_or_manager_only() {
+            let (mut contract, accounts) = contract_with_shares(&[10_000]);
+            
+            assert_eq!(contract.set_forward_as_payment(H160::from([0x42; 20]), true), Err(Error::BeneficiaryNotFound));
+            contract.set_forward_as_payment(accounts[0], true).unwrap();
+            assert!(contract.get_beneficiary(accounts[0]).unwrap().forward_as_payment);
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts[0]);
+            assert_eq!(contract.set_forward_as_payment(accounts[0], false), Err(Error::Unauthorized));
+        }
+
+        #[ink::test]
+        fn upgrade_is_owner_only_and_requires_pause() {
+            let mut contract = SplitPayment::new();
+            let code_hash = H256::from([0x11; 32]);
+            
+            assert_eq!(contract.get_code_version(), CODE_VERSION);
+            assert_eq!(contract.upgrade(code_hash), Err(Error::ContractNotPaused));
+            
+            contract.pause().unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(H160::from([0x42; 20]));
+            assert_eq!(contract.upgrade(code_hash), Err(Error::Unauthorized));
+        }
+
+        #[ink::test]
+        fn timelocked_action_waits_for_the_delay() {
+            let mut contract = SplitPayment::new_with_admin_delay(1_000);
+            let first = H160::from([0x10; 20]);
+            let second = H160::from([0x11; 20]);
+            contract.add_beneficiary(first, 4000).unwrap();
+            contract.add_beneficiary(second, 4000).unwrap();
+            
+            assert_eq!(contract.remove_beneficiary(first, false), Err(Error::ActionTimelocked));
+            
+            let id = contract.propose_action(AdminAction::SetShare(first, 6000)).unwrap();
+            assert_eq!(
+                contract.get_pending_actions(),
+                vec![PendingAction { id, action: AdminAction::SetShare(first, 6000), execute_after: 1_000 }]
+            );
+            assert_eq!(contract.execute_action(id), Err(Error::ActionNotReady { execute_after: 1_000 }));
+            
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(1_000);
+            contract.execute_action(id).unwrap();
+            assert_eq!(contract.get_beneficiary(first).unwrap().share_bps, 6000);
+            assert_eq!(contract.get_total_shares(), 10_000);
+            assert!(contract.get_pending_actions().is_empty());
+            assert_eq!(contract.execute_action(id), Err(Error::ActionNotFound));
+        }
+
+        #[ink::test]
+        fn admin_delay_only_increases_and_actions_can_be_cancelled() {
+            let mut contract = SplitPayment::new_with_admin_delay(1_000);
+            
+            assert_eq!(contract.increase_admin_delay(999), Err(Error::AdminDelayTooShort));
+            contract.increase_admin_delay(2_000).unwrap();
+            assert_eq!(contract.get_admin_delay(), 2_000);
+            
+            let id = contract.propose_action(AdminAction::TransferOwnership(H160::from([0x42; 20]))).unwrap();
+            contract.cancel_action(id).unwrap();
+            assert!(contract.get_pending_actions().is_empty());
+            assert_eq!(contract.cancel_action(id), Err(Error::ActionNotFound));
+        }
+
+        #[ink::test]
+        fn council_executes_once_threshold_is_met() {
+            let members = vec![H160::from([0x31; 20]), H160::from([0x32; 20]), H160::from([0x33; 20])];
+            let mut contract = SplitPayment::new_with_council(members.clone(), 2).unwrap();
+            let beneficiary = H160::from([0x10; 20]);
+            contract.add_beneficiary(beneficiary, 5000).unwrap();
+            
+            assert_eq!(contract.remove_beneficiary(beneficiary, false), Err(Error::CouncilApprovalRequired));
+            assert_eq!(contract.submit_admin_tx(AdminAction::SetShare(beneficiary, 7000)), Err(Error::NotCouncilMember));
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(members[0]);
+            let id = contract.submit_admin_tx(AdminAction::SetShare(beneficiary, 7000)).unwrap();
+            assert_eq!(contract.confirm_admin_tx(id), Err(Error::AlreadyConfirmed));
+            
+            // Revoking drops the count back to zero
+            contract.revoke_confirmation(id).unwrap();
+            assert_eq!(contract.get_admin_tx(id), Some((AdminAction::SetShare(beneficiary, 7000), 0)));
+            assert_eq!(contract.revoke_confirmation(id), Err(Error::NotConfirmed));
+            
+            contract.confirm_admin_tx(id).unwrap();
+            assert_eq!(contract.get_beneficiary(beneficiary).unwrap().share_bps, 5000);
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(members[2]);
+            contract.confirm_admin_tx(id).unwrap();
+            assert_eq!(contract.get_beneficiary(beneficiary).unwrap().share_bps, 7000);
+            assert_eq!(contract.get_admin_tx(id), None);
+            assert!(!contract.is_confirmed(id, members[0]));
+        }
+
+        #[ink::test]
+        fn council_threshold_is_validated() {
+            let member = H160::from([0x31; 20]);
+            
+            assert_eq!(SplitPayment::new_with_council(vec![member], 2).err(), Some(Error::InvalidThreshold));
+            assert_eq!(SplitPayment::new_with_council(vec![member, member], 1).err(), Some(Error::InvalidThreshold));
+            
+            let mut contract = SplitPayment::new();
+            assert_eq!(contract.get_council(), (Vec::new(), 0));
+            contract.set_council(vec![member], 1).unwrap();
+            assert_eq!(contract.set_council(vec![member], 1), Err(Error::CouncilApprovalRequired));
+        }
+
+        #[ink::test]
+        fn terminate_requires_pause_and_full_settlement() {
+            let (mut contract, accounts) = contract_with_shares(&[10_000]);
+            
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(500));
+            contract.receive_payment().unwrap();
+            
+            assert_eq!(contract.terminate(), Err(Error::ContractNotPaused));
+            
+            contract.pause().unwrap();
+            contract.freeze_beneficiary(accounts[0], true).unwrap();
+            set_contract_balance(U256::from(500));
+            assert_eq!(
+                contract.terminate(),
+                Err(Error::SettlementFailed { failed: vec![accounts[0]] })
+            );
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts[0]);
+            assert_eq!(contract.terminate(), Err(Error::Unauthorized));
+        }
+
+        #[ink::test]
+        fn terminate_refuses_undistributed_pool() {
+            let (mut contract, _) = contract_with_shares(&[10_000]);
+            contract.set_distribution_mode(DistributionMode::Manual).unwrap();
+            
+            set_contract_balance(U256::from(500));
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(500));
+            contract.receive_payment().unwrap();
+            
+            contract.pause().unwrap();
+            assert_eq!(contract.terminate(), Err(Error::FundsOutstanding));
+        }
+
+        #[ink::test]
+        fn terminate_refuses_during_chunked_distribution() {
+            let (mut contract, _) = contract_with_shares(&[5_000, 5_000]);
+            contract.set_distribution_mode(DistributionMode::Manual).unwrap();
+            
+            set_contract_balance(U256::from(500));
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(500));
+            contract.receive_payment().unwrap();
+            contract.start_distribution().unwrap();
+            contract.continue_distribution(1).unwrap();
+            
+            contract.pause().unwrap();
+            assert_eq!(contract.terminate(), Err(Error::DistributionInProgress));
+        }
+
+        #[ink::test]
+        fn retired_contract_refuses_deposits_for_good() {
+            let (mut contract, accounts) = contract_with_shares(&[10_000]);
+            
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(500));
+            contract.receive_payment().unwrap();
+            
+            contract.retire().unwrap();
+            assert!(contract.is_retired());
+            assert_eq!(contract.retire(), Err(Error::ContractRetired));
+            
+            let events = ink::env::test::recorded_events().collect::<Vec<_>>();
+            let event = <ContractRetired as ink::scale::Decode>::decode(&mut &events.last().unwrap().data[..]).unwrap();
+            assert_eq!(event.total_received, U256::from(500));
+            
+            // Nothing brings deposits back
+            contract.pause().unwrap();
+            contract.unpause().unwrap();
+            contract.set_min_payment(U256::zero()).unwrap();
+            contract.set_accepting_from(None).unwrap();
+            assert_eq!(contract.receive_payment(), Err(Error::ContractRetired));
+            assert_eq!(contract.tip(accounts[0]), Err(Error::ContractRetired));
+            
+            // Withdrawals ignore the pause flags
+            contract.pause_withdrawals().unwrap();
+            set_contract_balance(U256::from(500));
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts[0]);
+            assert!(contract.withdraw(U256::from(500)).is_ok());
+        }
+
+        #[ink::test]
+        fn invariants_hold_through_fees_keeper_fees_and_sweeps() {
+            let (mut contract, accounts) = contract_with_shares(&[6_000]);
+            let owner = contract.get_owner();
+            
+            contract.set_fee_bps(500).unwrap();
+            contract.set_keeper_fee_bps(MAX_KEEPER_FEE_BPS).unwrap();
+            contract.set_distribution_mode(DistributionMode::Manual).unwrap();
+            
+            set_contract_balance(U256::from(1_000));
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(1_000));
+            contract.receive_payment().unwrap();
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(H160::from([0x42; 20]));
+            contract.distribute().unwrap();
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(owner);
+            contract.withdraw_fees().unwrap();
+            contract.sweep_undistributed(owner).unwrap();
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts[0]);
+            contract.withdraw(U256::from(100)).unwrap();
+            
+            let report = contract.check_invariants().unwrap();
+            assert!(report.ledger_balanced);
+            assert_eq!(report.total_received, U256::from(1_000));
+            assert_eq!(report.total_withdrawn, U256::from(100));
+            assert_eq!(report.fee_accrued, U256::zero());
+            assert!(report.undistributed.is_zero());
+        }
+
+        #[ink::test]
+        fn assert_invariants_pauses_when_insolvent() {
+            let (mut contract, _) = contract_with_shares(&[10_000]);
+            
+            set_contract_balance(U256::from(1_000));
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(1_000));
+            contract.receive_payment().unwrap();
+            
+            let report = contract.assert_invariants().unwrap();
+            assert!(report.holds());
+            assert!(!contract.is_paused());
+            
+            // Funds vanish from under the books
+            set_contract_balance(U256::from(400));
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(H160::from([0x42; 20]));
+            assert_eq!(contract.assert_invariants(), Err(Error::Unauthorized));
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(contract.get_owner());
+            
+            let report = contract.assert_invariants().unwrap();
+            assert!(report.ledger_balanced);
+            assert!(!report.solvent);
+            assert_eq!(report.total_pending, U256::from(1_000));
+            assert!(contract.is_paused());
+            
+            let events = ink::env::test::recorded_events().collect::<Vec<_>>();
+            let event = <InvariantViolated as ink::scale::Decode>::decode(&mut &events[events.len() - 2].data[..]).unwrap();
+            assert!(event.ledger_balanced);
+            assert!(!event.solvent);
+        }
+
+        #[ink::test]
+        fn transfer_messages_refuse_reentry() {
+            let (mut contract, accounts) = contract_with_shares(&[10_000]);
+            
+            set_contract_balance(U256::from(1_000));
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(1_000));
+            contract.receive_payment().unwrap();
+            
+            // As seen from a callee re-entering during an outgoing transfer
+            contract.reentrancy_lock.set(&true);
+            assert_eq!(contract.payout_all(), Err(Error::ReentrantCall));
+            assert_eq!(contract.remove_beneficiary(accounts[0], false), Err(Error::ReentrantCall));
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts[0]);
+            assert_eq!(contract.withdraw(U256::from(100)), Err(Error::ReentrantCall));
+            assert_eq!(contract.get_pending_balance(accounts[0]), U256::from(1_000));
+            
+            // Released after both failed and successful calls
+            contract.reentrancy_lock.set(&false);
+            assert!(contract.withdraw(U256::from(5_000)).is_err());
+            assert_eq!(contract.reentrancy_lock.get(), Some(false));
+            contract.withdraw(U256::from(100)).unwrap();
+            assert_eq!(contract.reentrancy_lock.get(), Some(false));
+            assert_eq!(contract.get_pending_balance(accounts[0]), U256::from(900));
+        }
+
+        #[ink::test]
+        fn failed_removal_payout_is_escrowed_for_claim() {
+            let (mut contract, accounts) = contract_with_shares(&[5_000, 5_000]);
+            
+            set_contract_balance(U256::from(1_000));
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(1_000));
+            contract.receive_payment().unwrap();
+            
+            // Not enough balance for the transfer to go through
+            set_contract_balance(U256::from(100));
+            contract.remove_beneficiary(accounts[0], false).unwrap();
+            assert!(contract.get_beneficiary(accounts[0]).is_none());
+            assert_eq!(contract.get_escrowed(accounts[0]), U256::from(500));
+            
+            let events = ink::env::test::recorded_events().collect::<Vec<_>>();
+            let event = <PayoutEscrowed as ink::scale::Decode>::decode(&mut &events[events.len() - 2].data[..]).unwrap();
+            assert_eq!(event.account, accounts[0]);
+            assert_eq!(event.amount, U256::from(500));
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts[1]);
+            assert_eq!(contract.claim_escrow(), Err(Error::NoFundsAvailable));
+            
+            set_contract_balance(U256::from(1_000));
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts[0]);
+            assert_eq!(contract.claim_escrow(), Ok(U256::from(500)));
+            assert!(contract.get_escrowed(accounts[0]).is_zero());
+        }
+
+        #[ink::test]
+        fn withdrawal_stats_count_every_path() {
+            let (mut contract, accounts) = contract_with_shares(&[10_000]);
+            let spender = H160::from([0x42; 20]);
+            
+            set_contract_balance(U256::from(1_000));
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(1_000));
+            contract.receive_payment().unwrap();
+            assert_eq!(contract.get_withdrawal_stats(accounts[0]), (0, None));
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts[0]);
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(1_000);
+            contract.withdraw(U256::from(100)).unwrap();
+            contract.approve(spender, U256::from(100), None).unwrap();
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(spender);
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(2_000);
+            contract.withdraw_from(accounts[0], U256::from(100), true).unwrap();
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(contract.get_owner());
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(3_000);
+            contract.payout(accounts[0]).unwrap();
+            
+            // Failed attempts are not counted
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts[0]);
+            assert!(contract.withdraw_all().is_err());
+            
+            assert_eq!(contract.get_withdrawal_stats(accounts[0]), (3, Some(3_000)));
+            let beneficiary = contract.get_beneficiary(accounts[0]).unwrap();
+            assert_eq!(beneficiary.withdrawal_count, 3);
+            assert_eq!(beneficiary.last_withdrawal_at, Some(3_000));
+        }
+
+        #[ink::test]
+        fn stats_track_recent_activity() {
+            let (mut contract, _) = contract_with_shares(&[10_000]);
+            contract.set_distribution_mode(DistributionMode::Manual).unwrap();
+            
+            let stats = contract.get_stats_v2();
+            assert_eq!(stats.last_payment_at, None);
+            assert_eq!(stats.last_distribution_at, None);
+            assert!(stats.largest_payment.is_zero());
+            
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(1_000);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(700));
+            contract.receive_payment().unwrap();
+            
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(2_000);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(300));
+            contract.receive_payment().unwrap();
+            
+            let stats = contract.get_stats_v2();
+            assert_eq!(stats.payment_count, 2);
+            assert_eq!(stats.last_payment_at, Some(2_000));
+            assert_eq!(stats.last_distribution_at, None);
+            assert_eq!(stats.largest_payment, U256::from(700));
+            
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(3_000);
+            contract.distribute().unwrap();
+            assert_eq!(contract.get_stats_v2().last_distribution_at, Some(3_000));
+        }
+
+        #[ink::test]
+        fn distribution_emits_credit_per_beneficiary() {
+            let (mut contract, accounts) = contract_with_shares(&[7_000, 3_000]);
+            
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(1_000));
+            contract.receive_payment().unwrap();
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(100));
+            contract.receive_payment().unwrap();
+            assert_eq!(contract.get_distribution_count(), 2);
+            
+            // ShareCredited x2, FundsDistributed, FundsReceived for the second payment
+            let events = ink::env::test::recorded_events().collect::<Vec<_>>();
+            let tail = &events[events.len() - 4..];
+            let credits = tail[..2]
+                .iter()
+                .map(|event| <ShareCredited as ink::scale::Decode>::decode(&mut &event.data[..]).unwrap())
+                .collect::<Vec<_>>();
+            assert_eq!(credits[0].distribution_id, 1);
+            assert_eq!(credits[0].beneficiary, accounts[0]);
+            assert_eq!(credits[0].amount, U256::from(70));
+            assert_eq!(credits[1].beneficiary, accounts[1]);
+            assert_eq!(credits[1].amount, U256::from(30));
+            
+            let distributed = <FundsDistributed as ink::scale::Decode>::decode(&mut &tail[2].data[..]).unwrap();
+            assert_eq!(distributed.distribution_id, 1);
+            assert_eq!(distributed.total_amount, U256::from(100));
+        }
+
+        #[ink::test]
+        fn distribution_records_keep_the_share_table_size() {
+            let (mut contract, accounts) = contract_with_shares(&[6_000]);
+            
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(1_000);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(1_000));
+            contract.receive_payment().unwrap();
+            
+            contract.add_beneficiary(H160::from([0x42; 20]), 4_000).unwrap();
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(2_000);
+            contract.receive_payment().unwrap();
+            
+            assert_eq!(contract.get_distribution_count(), 2);
+            assert_eq!(contract.get_distribution(0), Some(DistributionRecord {
+                amount: U256::from(1_000),
+                timestamp: 1_000,
+                beneficiary_count: 1,
+                total_shares_at_time: 6_000,
+                config_version: 1,
+            }));
+            let second = contract.get_distribution(1).unwrap();
+            assert_eq!(second.beneficiary_count, 2);
+            assert_eq!(second.total_shares_at_time, 10_000);
+            assert_eq!(contract.get_distribution(2), None);
+            assert_eq!(pending_of(&contract, &accounts), vec![U256::from(1_200)]);
+        }
+
+        #[ink::test]
+        fn config_history_snapshots_every_share_change() {
+            let (mut contract, accounts) = contract_with_shares(&[6_000, 4_000]);
+            assert_eq!(contract.get_config_version(), 2);
+            assert_eq!(contract.get_config_at(1), Some(vec![(accounts[0], 6_000)]));
+            
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(100));
+            contract.receive_payment().unwrap();
+            
+            set_contract_balance(U256::from(100));
+            contract.remove_beneficiary_and_redistribute(accounts[1]).unwrap();
+            
+            // Removal, then the rescale to the old total
+            assert_eq!(contract.get_config_version(), 4);
+            assert_eq!(contract.get_config_at(3), Some(vec![(accounts[0], 6_000)]));
+            assert_eq!(contract.get_config_at(4), Some(vec![(accounts[0], 10_000)]));
+            
+            contract.receive_payment().unwrap();
+            assert_eq!(contract.get_distribution(0).unwrap().config_version, 2);
+            assert_eq!(contract.get_distribution(1).unwrap().config_version, 4);
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(H160::from([0x42; 20]));
+            assert_eq!(contract.prune_config_history(4), Err(Error::Unauthorized));
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(contract.get_owner());
+            contract.prune_config_history(u64::MAX).unwrap();
+            assert_eq!(contract.get_config_at(3), None);
+            assert_eq!(contract.get_config_at(4), Some(vec![(accounts[0], 10_000)]));
+        }
+
+        #[ink::test]
+        fn invited_beneficiary_counts_only_after_accepting() {
+            let mut contract = SplitPayment::new_with_acceptance(true);
+            let invitee = H160::from([0x10; 20]);
+            let other = H160::from([0x11; 20]);
+            
+            assert_eq!(contract.add_beneficiary(invitee, 5_000), Err(Error::AcceptanceRequired));
+            assert_eq!(contract.set_beneficiaries(vec![(invitee, 5_000)]), Err(Error::AcceptanceRequired));
+            
+            contract.invite_beneficiary(invitee, 6_000).unwrap();
+            contract.invite_beneficiary(other, 4_000).unwrap();
+            assert_eq!(contract.invite_beneficiary(other, 1_000), Err(Error::AlreadyInvited));
+            assert_eq!(contract.get_pending_invitations(), vec![(invitee, 6_000), (other, 4_000)]);
+            assert_eq!(contract.get_total_shares(), 0);
+            
+            // Nobody has accepted yet, so the payment is not credited to the invitees
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(1_000));
+            contract.receive_payment().unwrap();
+            assert_eq!(contract.get_undistributed(), U256::from(1_000));
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(invitee);
+            contract.accept_invitation().unwrap();
+            assert_eq!(contract.accept_invitation(), Err(Error::InvitationNotFound));
+            assert_eq!(contract.get_total_shares(), 6_000);
+            assert!(contract.get_beneficiary(invitee).unwrap().pending_balance.is_zero());
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(other);
+            contract.decline_invitation().unwrap();
+            assert!(contract.get_pending_invitations().is_empty());
+            assert!(contract.get_beneficiary(other).is_none());
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(contract.get_owner());
+            contract.invite_beneficiary(other, 4_000).unwrap();
+            contract.cancel_invitation(other).unwrap();
+            assert_eq!(contract.cancel_invitation(other), Err(Error::InvitationNotFound));
+            assert_eq!(contract.get_total_shares(), 6_000);
+        }
+
+        #[ink::test]
+        fn pending_balance_moves_between_beneficiaries() {
+            let (mut contract, accounts) = contract_with_shares(&[5_000, 5_000]);
+            
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(1_000));
+            contract.receive_payment().unwrap();
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts[0]);
+            assert_eq!(contract.transfer_pending(accounts[0], U256::from(100)), Err(Error::SameAccount));
+            assert_eq!(
+                contract.transfer_pending(H160::from([0x42; 20]), U256::from(100)),
+                Err(Error::BeneficiaryNotFound)
+            );
+            assert_eq!(
+                contract.transfer_pending(accounts[1], U256::from(501)),
+                Err(Error::InsufficientBalance { requested: U256::from(501), available: U256::from(500) })
+            );
+            
+            contract.transfer_pending(accounts[1], U256::from(200)).unwrap();
+            assert_eq!(pending_of(&contract, &accounts), vec![U256::from(300), U256::from(700)]);
+            assert!(contract.get_beneficiary(accounts[0]).unwrap().total_withdrawn.is_zero());
+            assert!(contract.get_beneficiary(accounts[1]).unwrap().total_withdrawn.is_zero());
+            
+            let events = ink::env::test::recorded_events().collect::<Vec<_>>();
+            let event = <PendingTransferred as ink::scale::Decode>::decode(&mut &events.last().unwrap().data[..]).unwrap();
+            assert_eq!((event.from, event.to, event.amount), (accounts[0], accounts[1], U256::from(200)));
+            
+            // Freezing either side blocks the move
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(contract.get_owner());
+            contract.freeze_beneficiary(accounts[1], true).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts[0]);
+            assert_eq!(contract.transfer_pending(accounts[1], U256::from(100)), Err(Error::BeneficiaryFrozen));
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts[1]);
+            assert_eq!(contract.transfer_pending(accounts[0], U256::from(100)), Err(Error::BeneficiaryFrozen));
+        }
+
+        #[ink::test]
+        fn rotated_account_keeps_record_and_drops_approvals() {
+            let (mut contract, accounts) = contract_with_shares(&[7_000, 3_000]);
+            let new_account = H160::from([0x42; 20]);
+            let spender = H160::from([0x43; 20]);
+            
+            set_contract_balance(U256::from(1_000));
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(1_000));
+            contract.receive_payment().unwrap();
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts[0]);
+            contract.withdraw(U256::from(100)).unwrap();
+            contract.approve(spender, U256::from(50), None).unwrap();
+            
+            assert_eq!(contract.rotate_account(H160::zero()), Err(Error::ZeroAddress));
+            assert_eq!(contract.rotate_account(accounts[1]), Err(Error::BeneficiaryAlreadyExists));
+            contract.rotate_account(new_account).unwrap();
+            
+            let events = ink::env::test::recorded_events().collect::<Vec<_>>();
+            let event = <BeneficiaryAccountRotated as ink::scale::Decode>::decode(&mut &events.last().unwrap().data[..]).unwrap();
+            assert_eq!((event.old, event.new), (accounts[0], new_account));
+            
+            assert!(contract.get_beneficiary(accounts[0]).is_none());
+            let rotated = contract.get_beneficiary(new_account).unwrap();
+            assert_eq!(rotated.share_bps, 7_000);
+            assert_eq!(rotated.pending_balance, U256::from(600));
+            assert_eq!(rotated.total_withdrawn, U256::from(100));
+            assert_eq!(rotated.withdrawal_count, 1);
+            assert_eq!(contract.get_beneficiaries()[0].account, new_account);
+            
+            // The old key's approval is gone and the old key can no longer act
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(spender);
+            assert!(contract.withdraw_from(new_account, U256::from(50), false).is_err());
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts[0]);
+            assert_eq!(contract.withdraw(U256::from(1)), Err(Error::Unauthorized));
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(new_account);
+            contract.withdraw(U256::from(600)).unwrap();
+        }
+
+        #[ink::test]
+        fn signed_approval_is_stored_once() {
+            let secret = SecretKey::from_slice(&[0x11; 32]).unwrap();
+            let owner = eth_address(&secret);
+            let spender = H160::from([0x42; 20]);
+            
+            let mut contract = SplitPayment::new();
+            contract.add_beneficiary(owner, 10_000).unwrap();
+            
+            let digest = contract.approval_digest(owner, spender, U256::from(100), None, 0);
+            let signature = sign(digest, &secret);
+            
+            // Submitted by a third party
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(H160::from([0x43; 20]));
+            contract.approve_with_signature(owner, spender, U256::from(100), None, 0, signature).unwrap();
+            assert_eq!(contract.get_approval(owner, spender), U256::from(100));
+            assert_eq!(contract.get_approval_nonce(owner), 1);
+            
+            // Replaying the same payload fails on the nonce
+            assert_eq!(
+                contract.approve_with_signature(owner, spender, U256::from(100), None, 0, signature),
+                Err(Error::InvalidNonce { expected: 1 })
+            );
+            
+            // A valid signature over different terms, or by another key, is rejected
+            let digest = contract.approval_digest(owner, spender, U256::from(100), None, 1);
+            let signature = sign(digest, &secret);
+            assert_eq!(
+                contract.approve_with_signature(owner, spender, U256::from(1_000), None, 1, signature),
+                Err(Error::InvalidSignature)
+            );
+            let other = SecretKey::from_slice(&[0x22; 32]).unwrap();
+            assert_eq!(
+                contract.approve_with_signature(owner, spender, U256::from(100), None, 1, sign(digest, &other)),
+                Err(Error::InvalidSignature)
+            );
+            assert_eq!(contract.get_approval_nonce(owner), 1);
+        }
+
+        #[ink::test]
+        fn relayed_withdrawal_pays_destination_and_relayer() {
+            let secret = SecretKey::from_slice(&[0x11; 32]).unwrap();
+            let beneficiary = eth_address(&secret);
+            let destination = H160::from([0x42; 20]);
+            let relayer = H160::from([0x43; 20]);
+            
+            let mut contract = SplitPayment::new();
+            contract.add_beneficiary(beneficiary, 10_000).unwrap();
+            set_contract_balance(U256::from(1_000));
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(1_000));
+            contract.receive_payment().unwrap();
+            
+            let digest = contract.withdrawal_digest(beneficiary, U256::from(300), destination, U256::from(10), 5_000, 0);
+            let signature = sign(digest, &secret);
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(relayer);
+            contract.withdraw_with_signature(beneficiary, U256::from(300), destination, U256::from(10), 5_000, 0, signature).unwrap();
+            assert_eq!(contract.get_pending_balance(beneficiary), U256::from(700));
+            assert_eq!(contract.get_beneficiary(beneficiary).unwrap().total_withdrawn, U256::from(300));
+            assert_eq!(contract.get_withdrawal_nonce(beneficiary), 1);
+            assert_eq!(ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(relayer), Ok(U256::from(10)));
+            
+            // Replaying the same payload fails on the nonce
+            assert_eq!(
+                contract.withdraw_with_signature(beneficiary, U256::from(300), destination, U256::from(10), 5_000, 0, signature),
+                Err(Error::InvalidNonce { expected: 1 })
+            );
+            assert_eq!(contract.get_pending_balance(beneficiary), U256::from(700));
+        }
+
+        #[ink::test]
+        fn relayed_withdrawal_past_deadline_is_rejected() {
+            let secret = SecretKey::from_slice(&[0x11; 32]).unwrap();
+            let beneficiary = eth_address(&secret);
+            let destination = H160::from([0x42; 20]);
+            
+            let mut contract = SplitPayment::new();
+            contract.add_beneficiary(beneficiary, 10_000).unwrap();
+            set_contract_balance(U256::from(1_000));
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(1_000));
+            contract.receive_payment().unwrap();
+            
+            let digest = contract.withdrawal_digest(beneficiary, U256::from(300), destination, U256::zero(), 5_000, 0);
+            let signature = sign(digest, &secret);
+            
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(5_001);
+            assert_eq!(
+                contract.withdraw_with_signature(beneficiary, U256::from(300), destination, U256::zero(), 5_000, 0, signature),
+                Err(Error::SignatureExpired)
+            );
+            assert_eq!(contract.get_withdrawal_nonce(beneficiary), 0);
+        }
+
+        #[ink::test]
+        fn relayed_withdrawal_by_wrong_signer_is_rejected() {
+            let secret = SecretKey::from_slice(&[0x11; 32]).unwrap();
+            let beneficiary = eth_address(&secret);
+            let destination = H160::from([0x42; 20]);
+            
+            let mut contract = SplitPayment::new();
+            contract.add_beneficiary(beneficiary, 10_000).unwrap();
+            set_contract_balance(U256::from(1_000));
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(1_000));
+            contract.receive_payment().unwrap();
+            
+            let digest = contract.withdrawal_digest(beneficiary, U256::from(300), destination, U256::zero(), 5_000, 0);
+            let attacker = SecretKey::from_slice(&[0x22; 32]).unwrap();
+            assert_eq!(
+                contract.withdraw_with_signature(beneficiary, U256::from(300), destination, U256::zero(), 5_000, 0, sign(digest, &attacker)),
+                Err(Error::InvalidSignature)
+            );
+            
+            // A genuine signature cannot be redirected to another destination
+            let signature = sign(digest, &secret);
+            assert_eq!(
+                contract.withdraw_with_signature(beneficiary, U256::from(300), H160::from([0x43; 20]), U256::zero(), 5_000, 0, signature),
+                Err(Error::InvalidSignature)
+            );
+            assert_eq!(contract.get_pending_balance(beneficiary), U256::from(1_000));
+        }
+
+        #[ink::test]
+        fn idempotent_payment_is_credited_once() {
+            let (mut contract, accounts) = contract_with_shares(&[10_000]);
+            let key = [0x01; 32];
+            
+            assert!(!contract.is_payment_processed(key));
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(500));
+            contract.receive_payment_idempotent(key).unwrap();
+            assert!(contract.is_payment_processed(key));
+            
+            let events_before = ink::env::test::recorded_events().count();
+            assert_eq!(contract.receive_payment_idempotent(key), Err(Error::DuplicatePayment));
+            assert_eq!(ink::env::test::recorded_events().count(), events_before);
+            assert_eq!(pending_of(&contract, &accounts), vec![U256::from(500)]);
+            
+            contract.receive_payment_idempotent([0x02; 32]).unwrap();
+            assert_eq!(pending_of(&contract, &accounts), vec![U256::from(1_000)]);
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts[0]);
+            assert_eq!(contract.prune_payment_keys(vec![key]), Err(Error::Unauthorized));
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(contract.get_owner());
+            contract.prune_payment_keys(vec![key]).unwrap();
+            assert!(!contract.is_payment_processed(key));
+        }
+
+        #[ink::test]
+        fn detailed_payment_reports_the_allocation() {
+            let (mut contract, accounts) = contract_with_shares(&[6_000, 3_000]);
+            contract.set_fee_bps(1_000).unwrap();
+            
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(1_000));
+            let summary = contract.receive_payment_detailed().unwrap();
+            assert_eq!(summary, DistributionSummary {
+                gross: U256::from(1_000),
+                fee: U256::from(100),
+                credited: vec![(accounts[0], U256::from(540)), (accounts[1], U256::from(270))],
+                undistributed: U256::from(90),
             });
             
-            Ok(())
+            contract.set_distribution_mode(DistributionMode::Manual).unwrap();
+            let summary = contract.receive_payment_detailed().unwrap();
+            assert!(summary.credited.is_empty());
+            assert_eq!(summary.undistributed, U256::from(900));
         }
 
-        /// Add a new beneficiary (only owner or managers)
-        #[ink(message)]
-        pub fn add_beneficiary(&mut self, account: H160, share_percentage: u8) -> Result<()> {
-            self.ensure_not_paused()?;
-            self.ensure_manager_or_owner()?;
+        #[ink::test]
+        fn ledger_overflow_is_an_error() {
+            let (mut contract, accounts) = contract_with_shares(&[5_000, 5_000]);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(10));
             
-            if account == H160::from([0u8; 32]) {
-                return Err(Error::InvalidBeneficiary);
-            }
+            contract.balances.insert(accounts[0], &(U256::MAX, U256::zero()));
+            assert_eq!(contract.receive_payment(), Err(Error::ArithmeticOverflow));
             
-            if share_percentage == 0 || self.total_shares.saturating_add(share_percentage) > 100 {
-                return Err(Error::InvalidShare);
-            }
+            contract.balances.insert(accounts[0], &(U256::zero(), U256::zero()));
+            contract.total_received = U256::MAX;
+            assert_eq!(contract.receive_payment(), Err(Error::ArithmeticOverflow));
+        }
+
+        #[ink::test]
+        fn tip_overflow_is_an_error() {
+            let (mut contract, accounts) = contract_with_shares(&[10_000]);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(10));
             
-            // Check if beneficiary already exists
-            if self.beneficiaries.iter().any(|b| b.account == account) {
-                return Err(Error::InvalidBeneficiary);
-            }
+            contract.balances.insert(accounts[0], &(U256::MAX, U256::zero()));
+            assert_eq!(contract.tip(accounts[0]), Err(Error::ArithmeticOverflow));
             
-            let beneficiary = Beneficiary {
-                account,
-                share_percentage,
-                pending_U256: 0,
-                total_withdrawn: 0,
-            };
+            contract.balances.insert(accounts[0], &(U256::zero(), U256::zero()));
+            contract.update_asset_stats(None, |stats| {
+                stats.total_pending = U256::MAX;
+                Ok(())
+            }).unwrap();
+            assert_eq!(contract.tip(accounts[0]), Err(Error::ArithmeticOverflow));
+        }
+
+        #[ink::test]
+        fn share_math_is_exact_near_max() {
+            let (contract, accounts) = contract_with_shares(&[5_000, 5_000]);
             
-            self.beneficiaries.push(beneficiary);
-            self.total_shares = self.total_shares.saturating_add(share_percentage);
+            let (allocation, leftover) = contract.preview_distribution(U256::MAX);
+            assert_eq!(allocation, vec![
+                (accounts[0], U256::MAX / 2 + 1),
+                (accounts[1], U256::MAX / 2),
+            ]);
+            assert!(leftover.is_zero());
+        }
+
+        #[ink::test]
+        fn update_config_changes_only_given_fields() {
+            let (mut contract, _) = contract_with_shares(&[5_000, 5_000]);
+            let before = contract.get_config();
             
-            self.env().emit_event(BeneficiaryAdded {
-                beneficiary: account,
-                share_percentage,
-                added_by: self.env().caller(),
+            contract.update_config(ConfigUpdate {
+                fee_bps: Some(250),
+                withdrawal_limit: Some(Some((U256::from(100), 1_000))),
+                ..Default::default()
+            }).unwrap();
+            assert_eq!(contract.get_config(), Config {
+                fee_bps: 250,
+                withdrawal_limit: Some((U256::from(100), 1_000)),
+                ..before.clone()
             });
             
-            Ok(())
+            // One invalid field rejects the whole patch
+            assert_eq!(
+                contract.update_config(ConfigUpdate {
+                    min_payment: Some(U256::from(5)),
+                    max_beneficiaries: Some(1),
+                    ..Default::default()
+                }),
+                Err(Error::TooManyBeneficiaries)
+            );
+            assert_eq!(contract.get_min_payment(), before.min_payment);
+            
+            contract.update_config(ConfigUpdate {
+                withdrawal_limit: Some(None),
+                ..Default::default()
+            }).unwrap();
+            assert_eq!(contract.get_config().withdrawal_limit, None);
         }
 
-        /// Remove a beneficiary (only owner or managers)
-        #[ink(message)]
-        pub fn remove_beneficiary(&mut self, account: H160) -> Result<()> {
-            self.ensure_not_paused()?;
-            self.ensure_manager_or_owner()?;
+        #[ink::test]
+        fn admin_log_keeps_the_latest_entries() {
+            let mut contract = SplitPayment::new();
+            let owner = contract.get_owner();
+            let manager = H160::from([0x42; 20]);
+            
+            for _ in 0..ADMIN_LOG_CAPACITY / 2 {
+                contract.pause().unwrap();
+                contract.unpause().unwrap();
+            }
+            assert_eq!(contract.get_admin_log_len(), ADMIN_LOG_CAPACITY);
+            assert_eq!(contract.get_admin_log(0, 1)[0].action, AdminActionKind::Pause);
+            
+            // The first pause is overwritten
+            contract.add_manager(manager, ManagerPermissions::default(), None).unwrap();
+            assert_eq!(contract.get_admin_log_len(), ADMIN_LOG_CAPACITY);
+            assert_eq!(contract.get_admin_log(0, 1)[0].action, AdminActionKind::Unpause);
+            assert_eq!(contract.get_admin_log(ADMIN_LOG_CAPACITY - 1, 10), vec![AdminLogEntry {
+                actor: owner,
+                action: AdminActionKind::AddManager,
+                target: Some(manager),
+                timestamp: 0,
+            }]);
+            assert!(contract.get_admin_log(ADMIN_LOG_CAPACITY, 10).is_empty());
+        }
+
+        #[ink::test]
+        fn beneficiary_labels() {
+            let mut contract = SplitPayment::new();
+            let account = H160::from([0x10; 20]);
+            
+            assert_eq!(
+                contract.add_beneficiary_with_label(account, 5_000, vec![b'a'; MAX_LABEL_LEN + 1]),
+                Err(Error::LabelTooLong)
+            );
+            contract.add_beneficiary_with_label(account, 5_000, b"Design".to_vec()).unwrap();
+            assert_eq!(contract.get_beneficiary(account).unwrap().label, Some(b"Design".to_vec()));
+            
+            assert_eq!(contract.set_beneficiary_label(account, Some(vec![0xff])), Err(Error::InvalidLabel));
+            
+            // Beneficiaries may relabel themselves; outsiders may not
+            ink::env::test::set_caller(H160::from([0x42; 20]));
+            assert_eq!(contract.set_beneficiary_label(account, None), Err(Error::Unauthorized));
+            ink::env::test::set_caller(account);
+            contract.set_beneficiary_label(account, None).unwrap();
+            assert_eq!(contract.get_beneficiaries()[0].label, None);
+        }
+
+        #[ink::test]
+        fn contract_metadata() {
+            assert!(matches!(
+                SplitPayment::new_named(vec![b'n'; MAX_NAME_LEN + 1], Vec::new()),
+                Err(Error::MetadataTooLong)
+            ));
             
-            let position = self.beneficiaries
-                .iter()
-                .position(|b| b.account == account)
-                .ok_or(Error::BeneficiaryNotFound)?;
+            let mut contract = SplitPayment::new_named(b"Studio".to_vec(), b"Album royalties".to_vec()).unwrap();
+            assert_eq!(contract.get_metadata(), (b"Studio".to_vec(), b"Album royalties".to_vec()));
             
-            let beneficiary = self.beneficiaries.remove(position);
-            self.total_shares = self.total_shares.saturating_sub(beneficiary.share_percentage);
+            assert_eq!(
+                contract.set_metadata(b"Studio".to_vec(), vec![b'd'; MAX_DESCRIPTION_LEN + 1]),
+                Err(Error::MetadataTooLong)
+            );
+            contract.set_metadata(b"Studio B".to_vec(), Vec::new()).unwrap();
+            assert_eq!(contract.get_metadata(), (b"Studio B".to_vec(), Vec::new()));
             
-            // If beneficiary has pending U256, transfer it
-            if beneficiary.pending_U256 > 0 {
-                self.env().transfer(account, beneficiary.pending_U256)
-                    .map_err(|_| Error::TransferFailed)?;
-            }
+            let events = ink::env::test::recorded_events().collect::<Vec<_>>();
+            let event = <MetadataChanged as ink::scale::Decode>::decode(&mut &events.last().unwrap().data[..]).unwrap();
+            assert_eq!(event.name, b"Studio B".to_vec());
             
-            self.env().emit_event(BeneficiaryRemoved {
-                beneficiary: account,
-                removed_by: self.env().caller(),
-            });
+            ink::env::test::set_caller(H160::from([0x42; 20]));
+            assert_eq!(contract.set_metadata(Vec::new(), Vec::new()), Err(Error::Unauthorized));
+        }
+
+        #[ink::test]
+        fn new_with_owner_sets_owner_and_fee_recipient() {
+            let owner = H160::from([0x42; 20]);
+            assert!(matches!(SplitPayment::new_with_owner(H160::zero()), Err(Error::ZeroAddress)));
             
-            Ok(())
+            let contract = SplitPayment::new_with_owner_and_beneficiaries(
+                owner,
+                vec![(H160::from([0x10; 20]), 6_000), (H160::from([0x11; 20]), 4_000)],
+            ).unwrap();
+            assert_eq!(contract.get_owner(), owner);
+            assert_eq!(contract.get_config().fee_recipient, owner);
+            assert_eq!(contract.get_beneficiary_count(), 2);
+            assert!(contract.get_children().is_empty());
         }
 
-        /// Grant approval for another account to withdraw on behalf of a beneficiary
-        #[ink(message)]
-        pub fn approve(&mut self, spender: H160, amount: U256, expires_at: Option<u64>) -> Result<()> {
-            self.ensure_not_paused()?;
-            let caller = self.env().caller();
+        #[ink::test]
+        fn operators_only_trigger_payouts() {
+            let (mut contract, accounts) = contract_with_shares(&[5_000, 5_000]);
+            let operator = H160::from([0x42; 20]);
             
-            // Ensure caller is a beneficiary
-            if !self.beneficiaries.iter().any(|b| b.account == caller) {
-                return Err(Error::Unauthorized);
-            }
+            contract.add_operator(operator).unwrap();
+            assert!(contract.is_operator(operator));
+            assert_eq!(contract.get_operators(), vec![operator]);
             
-            let approval = Approval {
-                spender,
-                amount,
-                expires_at,
-            };
+            set_contract_balance(U256::from(1_000));
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(1_000));
+            contract.receive_payment().unwrap();
             
-            self.approvals.insert((caller, spender), &approval);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(operator);
+            assert_eq!(contract.payout_all(), Ok(2));
+            assert_eq!(contract.add_beneficiary(H160::from([0x43; 20]), 1_000), Err(Error::Unauthorized));
+            assert_eq!(contract.remove_beneficiary(accounts[0], false), Err(Error::Unauthorized));
+            assert_eq!(contract.pause(), Err(Error::Unauthorized));
+            assert_eq!(contract.set_fee_bps(100), Err(Error::Unauthorized));
+            assert_eq!(contract.remove_operator(operator), Err(Error::Unauthorized));
             
-            self.env().emit_event(ApprovalGranted {
-                owner: caller,
-                spender,
-                amount,
-                expires_at,
-            });
+            let owner = contract.get_owner();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(owner);
+            contract.remove_operator(operator).unwrap();
+            assert_eq!(contract.remove_operator(operator), Err(Error::OperatorNotFound));
             
-            Ok(())
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(operator);
+            assert_eq!(contract.payout(accounts[0]), Err(Error::Unauthorized));
         }
 
-        /// Revoke approval for a spender
-        #[ink(message)]
-        pub fn revoke_approval(&mut self, spender: H160) -> Result<()> {
-            let caller = self.env().caller();
+        #[ink::test]
+        fn manager_appointments_expire() {
+            let mut contract = SplitPayment::new();
+            let owner = contract.get_owner();
+            let contractor = H160::from([0x42; 20]);
+            let staff = H160::from([0x43; 20]);
             
-            self.approvals.remove((caller, spender));
+            contract.add_manager(contractor, ManagerPermissions::all(), Some(1_000)).unwrap();
+            contract.add_manager(staff, ManagerPermissions::all(), None).unwrap();
+            assert_eq!(contract.get_manager_expiry(contractor), Some(1_000));
             
-            self.env().emit_event(ApprovalRevoked {
-                owner: caller,
-                spender,
-            });
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(contractor);
+            assert!(contract.add_beneficiary(H160::from([0x10; 20]), 1_000).is_ok());
             
-            Ok(())
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(1_000);
+            assert!(!contract.is_manager(contractor));
+            assert_eq!(contract.get_manager_permissions(contractor), None);
+            assert_eq!(contract.add_beneficiary(H160::from([0x11; 20]), 1_000), Err(Error::Unauthorized));
+            
+            // Anyone may prune; only the expired appointment goes
+            assert_eq!(contract.prune_expired_managers(), 1);
+            assert_eq!(contract.get_managers(), vec![staff]);
+            assert_eq!(contract.prune_expired_managers(), 0);
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(owner);
+            contract.add_manager(contractor, ManagerPermissions::all(), None).unwrap();
+            assert_eq!(contract.get_managers(), vec![staff, contractor]);
         }
 
-        /// Withdraw funds on behalf of a beneficiary (using approval)
-        #[ink(message)]
-        pub fn withdraw_from(&mut self, beneficiary: H160, amount: U256) -> Result<()> {
-            self.ensure_not_paused()?;
-            let caller = self.env().caller();
-            
-            // Get and validate approval
-            let approval = self.approvals.get((beneficiary, caller))
-                .ok_or(Error::InsufficientAllowance)?;
+        #[ink::test]
+        fn blacklisted_accounts_are_blocked() {
+            let (mut contract, accounts) = contract_with_shares(&[5_000, 5_000]);
+            let owner = contract.get_owner();
+            let attacker = H160::from([0x42; 20]);
             
-            // Check if approval has expired
-            if let Some(expires_at) = approval.expires_at {
-                let current_time = self.env().block_timestamp();
-                if current_time > expires_at {
-                    return Err(Error::InsufficientAllowance);
-                }
-            }
+            contract.set_blacklisted(attacker, true).unwrap();
+            assert_eq!(contract.add_beneficiary(attacker, 1_000), Err(Error::Blacklisted));
             
-            if approval.amount < amount {
-                return Err(Error::InsufficientAllowance);
-            }
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts[0]);
+            assert_eq!(contract.approve(attacker, U256::from(10), None), Err(Error::Blacklisted));
             
-            // Find beneficiary and check U256
-            let beneficiary_index = self.beneficiaries
-                .iter()
-                .position(|b| b.account == beneficiary)
-                .ok_or(Error::BeneficiaryNotFound)?;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(attacker);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(1_000));
+            assert_eq!(contract.receive_payment(), Err(Error::Blacklisted));
             
-            if self.beneficiaries[beneficiary_index].pending_U256 < amount {
-                return Err(Error::InsufficientU256);
-            }
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(owner);
+            contract.receive_payment().unwrap();
+            set_contract_balance(U256::from(1_000));
             
-            // Update beneficiary U256
-            self.beneficiaries[beneficiary_index].pending_U256 = 
-                self.beneficiaries[beneficiary_index].pending_U256.saturating_sub(amount);
-            self.beneficiaries[beneficiary_index].total_withdrawn = 
-                self.beneficiaries[beneficiary_index].total_withdrawn.saturating_add(amount);
+            // A blacklisted beneficiary keeps their balance but cannot withdraw it
+            contract.set_blacklisted(accounts[0], true).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts[0]);
+            assert_eq!(contract.withdraw(U256::from(500)), Err(Error::Blacklisted));
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts[1]);
+            assert_eq!(contract.withdraw_from(accounts[0], U256::from(10), false), Err(Error::Blacklisted));
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts[0]);
+            assert_eq!(pending_of(&contract, &accounts[..1]), vec![U256::from(500)]);
             
-            // Update approval
-            let mut updated_approval = approval;
-            updated_approval.amount = updated_approval.amount.saturating_sub(amount);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(owner);
+            contract.set_blacklisted(accounts[0], false).unwrap();
+            assert!(!contract.is_blacklisted(accounts[0]));
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts[0]);
+            assert!(contract.withdraw(U256::from(500)).is_ok());
+        }
+
+        #[ink::test]
+        fn insolvency_pauses_withdrawals() {
+            let (mut contract, accounts) = contract_with_shares(&[5_000, 5_000]);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(1_000));
+            contract.receive_payment().unwrap();
             
-            if updated_approval.amount == 0 {
-                self.approvals.remove((beneficiary, caller));
-            } else {
-                self.approvals.insert((beneficiary, caller), &updated_approval);
-            }
+            // Something drained the contract behind the ledger's back
+            set_contract_balance(U256::from(600));
+            assert!(!contract.check_solvency());
+            assert!(contract.is_paused());
             
-            // Transfer funds to the caller (spender)
-            self.env().transfer(caller, amount)
-                .map_err(|_| Error::TransferFailed)?;
+            let events = ink::env::test::recorded_events().collect::<Vec<_>>();
+            let event = <InvariantViolated as ink::scale::Decode>::decode(&mut &events[events.len() - 2].data[..]).unwrap();
+            assert!(!event.solvent);
             
-            self.env().emit_event(WithdrawalByApproval {
-                beneficiary,
-                spender: caller,
-                amount,
-            });
+            contract.unpause().unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts[0]);
+            assert_eq!(contract.withdraw(U256::from(500)), Err(Error::Insolvent));
             
-            Ok(())
+            set_contract_balance(U256::from(1_000));
+            assert!(contract.check_solvency());
+            let owner = contract.get_owner();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(owner);
+            contract.unpause().unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts[0]);
+            assert!(contract.withdraw(U256::from(500)).is_ok());
         }
 
-        /// Withdraw own funds (beneficiary)
-        #[ink(message)]
-        pub fn withdraw(&mut self, amount: U256) -> Result<()> {
-            self.ensure_not_paused()?;
-            let caller = self.env().caller();
+        #[ink::test]
+        fn payments_below_threshold_are_batched() {
+            let (mut contract, accounts) = contract_with_shares(&[5_000, 5_000]);
+            assert_eq!(contract.set_distribution_threshold(U256::from(1_000)), Ok(()));
+            assert_eq!(contract.get_distribution_threshold(), U256::from(1_000));
             
-            let beneficiary_index = self.beneficiaries
-                .iter()
-                .position(|b| b.account == caller)
-                .ok_or(Error::Unauthorized)?;
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(400));
+            contract.receive_payment().unwrap();
+            contract.receive_payment().unwrap();
+            assert_eq!(contract.get_pool_balance(), U256::from(800));
+            assert_eq!(pending_of(&contract, &accounts), vec![U256::zero(), U256::zero()]);
             
-            if self.beneficiaries[beneficiary_index].pending_U256 < amount {
-                return Err(Error::InsufficientU256);
-            }
+            // The third payment crosses the threshold and flushes the whole pool
+            contract.receive_payment().unwrap();
+            assert_eq!(contract.get_pool_balance(), U256::zero());
+            assert_eq!(pending_of(&contract, &accounts), vec![U256::from(600), U256::from(600)]);
             
-            self.beneficiaries[beneficiary_index].pending_U256 = 
-                self.beneficiaries[beneficiary_index].pending_U256.saturating_sub(amount);
-            self.beneficiaries[beneficiary_index].total_withdrawn = 
-                self.beneficiaries[beneficiary_index].total_withdrawn.saturating_add(amount);
+            let events = ink::env::test::recorded_events().collect::<Vec<_>>();
+            let event = <FundsDistributed as ink::scale::Decode>::decode(&mut &events[events.len() - 2].data[..]).unwrap();
+            assert_eq!(event.total_amount, U256::from(1_200));
             
-            self.env().transfer(caller, amount)
-                .map_err(|_| Error::TransferFailed)?;
+            // A manual flush works below the threshold
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(100));
+            contract.receive_payment().unwrap();
+            assert_eq!(contract.distribute(), Ok(U256::from(100)));
+            assert_eq!(pending_of(&contract, &accounts), vec![U256::from(650), U256::from(650)]);
             
-            Ok(())
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts[0]);
+            assert_eq!(contract.set_distribution_threshold(U256::zero()), Err(Error::Unauthorized));
         }
 
-        /// Add a manager (only owner)
-        #[ink(message)]
-        pub fn add_manager(&mut self, manager: H160) -> Result<()> {
-            self.ensure_owner()?;
+        #[ink::test]
+        fn removal_payout_goes_to_payout_address() {
+            let (mut contract, accounts) = contract_with_shares(&[10_000]);
+            let payout_address = H160::from([0x42; 20]);
             
-            self.managers.insert(manager, &true);
+            set_contract_balance(U256::from(500));
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(500));
+            contract.receive_payment().unwrap();
             
-            self.env().emit_event(ManagerAdded {
-                manager,
-                added_by: self.env().caller(),
-            });
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts[0]);
+            contract.set_payout_address(Some(payout_address)).unwrap();
             
-            Ok(())
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(contract.get_owner());
+            contract.remove_beneficiary(accounts[0], false).unwrap();
+            assert_eq!(ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(payout_address), Ok(U256::from(500)));
+            assert!(contract.get_escrowed(accounts[0]).is_zero());
         }
 
-        /// Remove a manager (only owner)
-        #[ink(message)]
-        pub fn remove_manager(&mut self, manager: H160) -> Result<()> {
-            self.ensure_owner()?;
+        #[ink::test]
+        fn chunked_distribution_matches_single_shot() {
+            let (mut contract, accounts) = contract_with_shares(&[3_333, 3_333, 3_334]);
+            contract.set_distribution_mode(DistributionMode::Manual).unwrap();
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(1_001));
+            contract.receive_payment().unwrap();
+            set_contract_balance(U256::from(1_001));
+            let (expected, leftover) = contract.preview_distribution(U256::from(1_001));
             
-            self.managers.remove(manager);
+            assert_eq!(contract.continue_distribution(1), Err(Error::NoFundsAvailable));
+            assert_eq!(contract.start_distribution(), Ok(U256::from(1_001)));
+            assert_eq!(contract.start_distribution(), Err(Error::DistributionInProgress));
+            assert_eq!(contract.get_pool_balance(), U256::zero());
             
-            self.env().emit_event(ManagerRemoved {
-                manager,
-                removed_by: self.env().caller(),
-            });
+            assert_eq!(contract.continue_distribution(2), Ok(1));
+            assert_eq!(contract.get_distribution_progress().map(|progress| progress.cursor), Some(2));
+            assert_eq!(contract.remove_beneficiary(accounts[2], false), Err(Error::DistributionInProgress));
+            assert!(contract.assert_invariants().unwrap().holds());
             
-            Ok(())
-        }
-
-        /// Pause the contract (only owner)
-        #[ink(message)]
-        pub fn pause(&mut self) -> Result<()> {
-            self.ensure_owner()?;
-            self.paused = true;
+            assert_eq!(contract.continue_distribution(2), Ok(0));
+            assert_eq!(contract.get_distribution_progress(), None);
             
-            self.env().emit_event(ContractPaused {
-                by: self.env().caller(),
-            });
+            // 333.63 : 333.63 : 333.73, floored along the running total
+            let pending = pending_of(&contract, &accounts);
+            assert_eq!(pending, vec![U256::from(333), U256::from(334), U256::from(334)]);
+            let total = |amounts: Vec<U256>| amounts.into_iter().fold(U256::zero(), |acc, amount| acc + amount);
+            assert_eq!(total(pending), total(expected.into_iter().map(|(_, amount)| amount).collect()));
+            assert_eq!(contract.get_undistributed(), leftover);
             
-            Ok(())
-        }
-
-        /// Unpause the contract (only owner)
-        #[ink(message)]
-        pub fn unpause(&mut self) -> Result<()> {
-            self.ensure_owner()?;
-            self.paused = false;
+            let events = ink::env::test::recorded_events().collect::<Vec<_>>();
+            let event = <FundsDistributed as ink::scale::Decode>::decode(&mut &events.last().unwrap().data[..]).unwrap();
+            assert_eq!(event.total_amount, U256::from(1_001));
             
-            self.env().emit_event(ContractUnpaused {
-                by: self.env().caller(),
-            });
+            // Caps and flat amounts need the single-call split
+            contract.set_tier(accounts[2], 0, Some(U256::from(100))).unwrap();
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(1_000));
+            contract.receive_payment().unwrap();
+            assert_eq!(contract.start_distribution(), Err(Error::ChunkingUnsupported));
             
-            Ok(())
-        }
-
-        /// Transfer ownership (only current owner)
-        #[ink(message)]
-        pub fn transfer_ownership(&mut self, new_owner: H160) -> Result<()> {
-            self.ensure_owner()?;
-            self.owner = new_owner;
-            Ok(())
-        }
-
-        // Query functions
-
-        /// Get contract owner
-        #[ink(message)]
-        pub fn get_owner(&self) -> H160 {
-            self.owner
+            assert!(contract.remove_beneficiary(accounts[2], false).is_ok());
         }
 
-        /// Check if account is a manager
-        #[ink(message)]
-        pub fn is_manager(&self, account: H160) -> bool {
-            self.managers.get(account).unwrap_or(false)
-        }
-
-        /// Get all beneficiaries
-        #[ink(message)]
-        pub fn get_beneficiaries(&self) -> Vec<Beneficiary> {
-            self.beneficiaries.clone()
-        }
-
-        /// Get beneficiary info
-        #[ink(message)]
-        pub fn get_beneficiary(&self, account: H160) -> Option<Beneficiary> {
-            self.beneficiaries.iter().find(|b| b.account == account).cloned()
-        }
-
-        /// Get approval amount
-        #[ink(message)]
-        pub fn get_approval(&self, owner: H160, spender: H160) -> U256 {
-            self.approvals.get((owner, spender))
-                .map(|a| a.amount)
-                .unwrap_or(0)
-        }
-
-        /// Get total shares allocated
-        #[ink(message)]
-        pub fn get_total_shares(&self) -> u8 {
-            self.total_shares
+        #[ink::test]
+        fn push_payouts_skip_blacklisted_beneficiaries() {
+            let (mut contract, accounts) = contract_with_shares(&[5_000, 5_000]);
+            
+            set_contract_balance(U256::from(1_000));
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(1_000));
+            contract.receive_payment().unwrap();
+            
+            contract.set_blacklisted(accounts[0], true).unwrap();
+            assert_eq!(contract.payout(accounts[0]), Err(Error::Blacklisted));
+            assert_eq!(contract.payout_all(), Ok(1));
+            assert_eq!(pending_of(&contract, &accounts), vec![U256::from(500), U256::zero()]);
         }
 
-        /// Check if contract is paused
-        #[ink(message)]
-        pub fn is_paused(&self) -> bool {
-            self.paused
+        #[ink::test]
+        fn failed_payouts_are_queued_for_retry() {
+            let (mut contract, accounts) = contract_with_shares(&[10_000]);
+            let payee = H160::from([0x20; 20]);
+            
+            // Transfers cannot be made to fail off-chain, so queue them directly
+            contract.queue_failed_payout(accounts[0], U256::from(200)).unwrap();
+            contract.freeze_beneficiary(accounts[0], true).unwrap();
+            contract.queue_failed_payout(payee, U256::from(300)).unwrap();
+            assert_eq!(contract.get_failed_payouts(), vec![(accounts[0], U256::from(200)), (payee, U256::from(300))]);
+            
+            // Queued funds are still owed
+            set_contract_balance(U256::from(400));
+            assert!(!contract.check_invariants().unwrap().solvent);
+            assert!(!contract.check_solvency());
+            contract.unpause().unwrap();
+            
+            set_contract_balance(U256::from(500));
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(H160::from([0x42; 20]));
+            assert_eq!(contract.retry_failed_payout(accounts[0]), Err(Error::BeneficiaryFrozen));
+            assert_eq!(contract.retry_all_failed(10), Ok(1));
+            assert_eq!(contract.get_failed_payouts(), vec![(accounts[0], U256::from(200))]);
+            assert_eq!(contract.retry_failed_payout(payee), Err(Error::NoFundsAvailable));
+            
+            let events = ink::env::test::recorded_events().collect::<Vec<_>>();
+            let event = <Payout as ink::scale::Decode>::decode(&mut &events.last().unwrap().data[..]).unwrap();
+            assert_eq!(event.beneficiary, payee);
+            assert_eq!(event.amount, U256::from(300));
         }
 
-        /// Get contract statistics
-        #[ink(message)]
-        pub fn get_stats(&self) -> (U256, U256, U256) {
-            (
-                self.total_received,
-                self.total_distributed,
-                self.env().U256()
-            )
+        fn set_contract_balance(amount: U256) {
+            let contract = ink::env::test::callee::<ink::env::DefaultEnvironment>();
+            ink::env::test::set_contract_balance(contract, amount);
         }
 
-        // Private helper functions
-
-        /// Distribute funds among beneficiaries
-        fn distribute_funds(&mut self, amount: U256) -> Result<()> {
-            if self.beneficiaries.is_empty() || self.total_shares == 0 {
-                return Ok(());
-            }
-
-            for beneficiary in &mut self.beneficiaries {
-                let share_amount = amount
-                    .saturating_mul(beneficiary.share_percentage as U256)
-                    .saturating_div(100);
-                
-                beneficiary.pending_U256 = beneficiary.pending_U256.saturating_add(share_amount);
-            }
+        /// Build a contract whose beneficiaries hold `shares`, in order
+        fn contract_with_shares(shares: &[u16]) -> (SplitPayment, Vec<H160>) {
+            let mut contract = SplitPayment::new();
+            let mut accounts = Vec::new();
             
-            self.total_distributed = self.total_distributed.saturating_add(amount);
+            for (i, share_bps) in shares.iter().enumerate() {
+                let account = H160::from([0x10 + i as u8; 20]);
+                contract.add_beneficiary(account, *share_bps).unwrap();
+                accounts.push(account);
+            }
             
-            self.env().emit_event(FundsDistributed {
-                total_amount: amount,
-                beneficiary_count: self.beneficiaries.len() as u32,
-            });
+            (contract, accounts)
+        }
+
+        /// Recoverable ECDSA signature over `digest`, laid out as `ecdsa_recover` expects
+        fn sign(digest: [u8; 32], secret: &SecretKey) -> [u8; 65] {
+            let (recovery_id, compact) = Secp256k1::new()
+                .sign_ecdsa_recoverable(&Message::from_digest(digest), secret)
+                .serialize_compact();
             
-            Ok(())
+            let mut signature = [0u8; 65];
+            signature[..64].copy_from_slice(&compact);
+            signature[64] = i32::from(recovery_id) as u8;
+            signature
         }
 
-        /// Ensure caller is the owner
-        fn ensure_owner(&self) -> Result<()> {
-            if self.env().caller() == self.owner {
-                Ok(())
-            } else {
-                Err(Error::Unauthorized)
-            }
+        /// Account controlled by `secret`
+        fn eth_address(secret: &SecretKey) -> H160 {
+            let public_key = PublicKey::from_secret_key(&Secp256k1::new(), secret).serialize();
+            let mut address = [0u8; 20];
+            ink::env::ecdsa_to_eth_address(&public_key, &mut address).unwrap();
+            H160::from(address)
         }
 
-        /// Ensure caller is owner or manager
-        fn ensure_manager_or_owner(&self) -> Result<()> {
-            let caller = self.env().caller();
-            if caller == self.owner || self.managers.get(caller).unwrap_or(false) {
-                Ok(())
-            } else {
-                Err(Error::Unauthorized)
-            }
+        fn pending_of(contract: &SplitPayment, accounts: &[H160]) -> Vec<U256> {
+            accounts
+                .iter()
+                .map(|a| contract.get_beneficiary(*a).unwrap().pending_balance)
+                .collect()
         }
 
-        /// Ensure contract is not paused
-        fn ensure_not_paused(&self) -> Result<()> {
-            if self.paused {
-                Err(Error::ContractPaused)
-            } else {
-                Ok(())
+        #[ink::test]
+        fn largest_remainder_rounding_is_exact() {
+            let three: &[u16] = &[3333, 3333, 3334];
+            let seven: &[u16] = &[1428, 1428, 1428, 1428, 1428, 1428, 1432];
+            
+            for shares in [three, seven] {
+                for amount in [1u64, 7, 999] {
+                    let (mut contract, accounts) = contract_with_shares(shares);
+                    
+                    ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(amount));
+                    contract.receive_payment().unwrap();
+                    
+                    let total = pending_of(&contract, &accounts)
+                        .into_iter()
+                        .fold(U256::zero(), |acc, p| acc + p);
+                    assert_eq!(total, U256::from(amount));
+                    assert_eq!(contract.get_undistributed(), U256::zero());
+                }
             }
         }
-    }
-
-    #[cfg(test)]
-    mod tests {
-        use super::*;
 
         #[ink::test]
-        fn constructor_works() {
-            let contract = SplitPayment::new();
-            assert_eq!(contract.is_paused(), false);
-            assert_eq!(contract.get_total_shares(), 0);
+        fn largest_remainder_rounding_is_deterministic() {
+            let (mut contract, accounts) = contract_with_shares(&[3333, 3333, 3334]);
+            
+            // Largest remainder wins the single unit
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(1));
+            contract.receive_payment().unwrap();
+            assert_eq!(
+                pending_of(&contract, &accounts),
+                vec![U256::zero(), U256::zero(), U256::from(1)]
+            );
+            
+            // Equal remainders are broken by list order
+            let (mut contract, accounts) = contract_with_shares(&[5000, 5000]);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(U256::from(1));
+            contract.receive_payment().unwrap();
+            assert_eq!(
+                pending_of(&contract, &accounts),
+                vec![U256::from(1), U256::zero()]
+            );
         }
 
         #[ink::test]
-        fn add_beneficiary_works() {
+        fn share_above_max_bps_is_rejected() {
             let mut contract = SplitPayment::new();
             let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
             
-            assert!(contract.add_beneficiary(accounts.alice, 50).is_ok());
-            assert_eq!(contract.get_total_shares(), 50);
+            assert_eq!(
+                contract.add_beneficiary(accounts.alice, 0),
+                Err(Error::InvalidShare { requested: 0, available: MAX_SHARE_BPS })
+            );
+            assert_eq!(
+                contract.add_beneficiary(accounts.alice, MAX_SHARE_BPS + 1),
+                Err(Error::InvalidShare { requested: MAX_SHARE_BPS + 1, available: MAX_SHARE_BPS })
+            );
             
-            let beneficiary = contract.get_beneficiary(accounts.alice).unwrap();
-            assert_eq!(beneficiary.share_percentage, 50);
+            contract.add_beneficiary(accounts.alice, 250).unwrap();
+            assert_eq!(
+                contract.add_beneficiary(accounts.bob, 9751),
+                Err(Error::InvalidShare { requested: 9751, available: 9750 })
+            );
+            assert!(contract.add_beneficiary(accounts.bob, 9750).is_ok());
         }
 
         #[ink::test]
-        fn approval_system_works() {
-            let mut contract = SplitPayment::new();
+        fn new_with_beneficiaries_works() {
             let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
             
-            // Add beneficiary first
-            contract.add_beneficiary(accounts.alice, 50).unwrap();
-            
-            // Set caller to alice for approval
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
-            
-            // Grant approval
-            assert!(contract.approve(accounts.bob, 1000, None).is_ok());
-            assert_eq!(contract.get_approval(accounts.alice, accounts.bob), 1000);
+            let contract = SplitPayment::new_with_beneficiaries(vec![
+                (accounts.alice, 6000),
+                (accounts.bob, 4000),
+            ]).unwrap();
             
-            // Revoke approval
-            assert!(contract.revoke_approval(accounts.bob).is_ok());
-            assert_eq!(contract.get_approval(accounts.alice, accounts.bob), 0);
+            assert_eq!(contract.get_total_shares(), MAX_SHARE_BPS);
+            assert_eq!(contract.get_beneficiaries().len(), 2);
+            assert_eq!(contract.get_beneficiary(accounts.bob).unwrap().share_bps, 4000);
         }
 
         #[ink::test]
-        fn access_control_works() {
-            let mut contract = SplitPayment::new();
+        fn new_with_invalid_beneficiaries_fails() {
             let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
             
-            // Non-owner cannot add manager
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
-            assert_eq!(contract.add_manager(accounts.bob), Err(Error::Unauthorized));
+            // Shares exceed 100%
+            assert_eq!(
+                SplitPayment::new_with_beneficiaries(vec![(accounts.alice, 6000), (accounts.bob, 4001)]).err(),
+                Some(Error::InvalidShare { requested: 4001, available: 4000 })
+            );
             
-            // Owner can add manager
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.eve); // Owner
-            assert!(contract.add_manager(accounts.alice).is_ok());
-            assert!(contract.is_manager(accounts.alice));
+            // Duplicate account
+            assert_eq!(
+                SplitPayment::new_with_beneficiaries(vec![(accounts.alice, 1000), (accounts.alice, 1000)]).err(),
+                Some(Error::BeneficiaryAlreadyExists)
+            );
+            
+            // Zero address
+            assert_eq!(
+                SplitPayment::new_with_beneficiaries(vec![(H160::zero(), 1000)]).err(),
+                Some(Error::ZeroAddress)
+            );
+        }
+    }
+
+    #[cfg(all(test, feature = "e2e-tests"))]
+    mod e2e_tests {
+        use super::*;
+        use ink_e2e::ContractsBackend;
+
+        type E2EResult<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+        #[ink_e2e::test]
+        async fn payout_to_nested_splitter_is_tracked<Client: E2EBackend>(mut client: Client) -> E2EResult<()> {
+            let mut child_constructor = SplitPaymentRef::new();
+            let child = client
+                .instantiate("split_payment", &ink_e2e::alice(), &mut child_constructor)
+                .submit()
+                .await
+                .expect("child instantiate failed");
+            let child_call = child.call_builder::<SplitPayment>();
+
+            let mut parent_constructor = SplitPaymentRef::new();
+            let parent = client
+                .instantiate("split_payment", &ink_e2e::alice(), &mut parent_constructor)
+                .submit()
+                .await
+                .expect("parent instantiate failed");
+            let mut parent_call = parent.call_builder::<SplitPayment>();
+
+            client
+                .call(&ink_e2e::alice(), &parent_call.add_beneficiary(child.addr, 4000))
+                .submit()
+                .await
+                .expect("add_beneficiary failed");
+            client
+                .call(&ink_e2e::alice(), &parent_call.set_forward_as_payment(child.addr, true))
+                .submit()
+                .await
+                .expect("set_forward_as_payment failed");
+            client
+                .call(&ink_e2e::bob(), &parent_call.receive_payment())
+                .value(1_000_000_000_000)
+                .submit()
+                .await
+                .expect("receive_payment failed");
+            client
+                .call(&ink_e2e::alice(), &parent_call.payout(child.addr))
+                .submit()
+                .await
+                .expect("payout failed");
+
+            // The child saw a payment, not a bare transfer
+            let child_stats = client
+                .call(&ink_e2e::alice(), &child_call.get_stats())
+                .dry_run()
+                .await?
+                .return_value();
+            assert_eq!(child_stats.0, U256::from(400_000_000_000u64));
+
+            let parent_pending = client
+                .call(&ink_e2e::alice(), &parent_call.get_pending_balance(child.addr))
+                .dry_run()
+                .await?
+                .return_value();
+            assert_eq!(parent_pending, U256::zero());
+
+            Ok(())
+        }
+
+        #[ink_e2e::test]
+        async fn trait_messages_are_callable<Client: E2EBackend>(mut client: Client) -> E2EResult<()> {
+            use crate::SplitPaymentInterface;
+
+            let mut constructor = SplitPaymentRef::new();
+            let contract = client
+                .instantiate("split_payment", &ink_e2e::alice(), &mut constructor)
+                .submit()
+                .await
+                .expect("instantiate failed");
+            let mut call_builder = contract.call_builder::<SplitPayment>();
+            let beneficiary = H160::from([0x10; 20]);
+
+            client
+                .call(&ink_e2e::alice(), &call_builder.add_beneficiary(beneficiary, 10_000))
+                .submit()
+                .await
+                .expect("add_beneficiary failed");
+
+            // Go through the trait selectors rather than the inherent messages
+            client
+                .call(&ink_e2e::bob(), &SplitPaymentInterface::receive_payment(&mut call_builder))
+                .value(1_000_000_000_000)
+                .submit()
+                .await
+                .expect("receive_payment failed");
+
+            let total_shares = client
+                .call(&ink_e2e::alice(), &SplitPaymentInterface::get_total_shares(&call_builder))
+                .dry_run()
+                .await?
+                .return_value();
+            assert_eq!(total_shares, 10_000);
+
+            let pending = client
+                .call(&ink_e2e::alice(), &SplitPaymentInterface::get_pending_balance(&call_builder, beneficiary))
+                .dry_run()
+                .await?
+                .return_value();
+            assert_eq!(pending, U256::from(1_000_000_000_000u64));
+
+            Ok(())
         }
     }
 }
\ No newline at end of file